@@ -0,0 +1,2 @@
+pub mod exhaustiveness;
+pub mod typecheck;
@@ -0,0 +1,270 @@
+use super::super::frontend::{ast, prelude};
+use std::collections::HashMap;
+
+/// Checks that every match expression over an enum covers all of its
+/// variants. Enum declarations are remembered between calls so the REPL can
+/// check matches on enums declared in earlier lines.
+pub struct ExhaustivenessChecker {
+    enums: HashMap<String, ast::EnumDeclarationStatement>,
+    errors: Vec<String>,
+}
+
+impl ExhaustivenessChecker {
+    pub fn new() -> Self {
+        let enums = prelude::enums()
+            .into_iter()
+            .map(|enum_declaration| (enum_declaration.identifier.to_owned(), enum_declaration))
+            .collect();
+
+        ExhaustivenessChecker {
+            enums,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Makes an enum declared in another module known to the checker.
+    pub fn register_enum(&mut self, enum_declaration: ast::EnumDeclarationStatement) {
+        self.enums
+            .insert(enum_declaration.identifier.to_owned(), enum_declaration);
+    }
+
+    pub fn check_program(&mut self, program: &ast::Statement) -> Result<(), String> {
+        self.errors.clear();
+        self.check_statement(program);
+
+        match self.errors.is_empty() {
+            true => Ok(()),
+            false => Err(self.errors.join("\n")),
+        }
+    }
+
+    fn check_statement(&mut self, statement: &ast::Statement) {
+        match statement {
+            ast::Statement::Program(program) => self.check_block(&program.body),
+            ast::Statement::VariableDeclaration(variable_declaration) => {
+                if let Some(value) = &variable_declaration.value {
+                    self.check_expression(value);
+                }
+            }
+            ast::Statement::DestructuringDeclaration(destructuring_declaration) => {
+                self.check_expression(&destructuring_declaration.value);
+            }
+            ast::Statement::EnumDeclaration(enum_declaration) => {
+                self.enums.insert(
+                    enum_declaration.identifier.to_owned(),
+                    enum_declaration.to_owned(),
+                );
+            }
+            ast::Statement::FunctionDeclaration(function_declaration) => {
+                self.check_block(&function_declaration.body.body)
+            }
+            ast::Statement::Import(_)
+            | ast::Statement::StructDeclaration(_)
+            | ast::Statement::TypeAlias(_)
+            | ast::Statement::NewtypeDeclaration(_) => (),
+            ast::Statement::Export(export_statement) => {
+                self.check_statement(&export_statement.declaration)
+            }
+            ast::Statement::Throw(throw_statement) => self.check_expression(&throw_statement.value),
+            ast::Statement::Try(try_statement) => {
+                self.check_block(&try_statement.body.body);
+                if let Some(catch_clause) = &try_statement.catch_clause {
+                    self.check_block(&catch_clause.body.body);
+                }
+                if let Some(finally_body) = &try_statement.finally_body {
+                    self.check_block(&finally_body.body);
+                }
+            }
+            ast::Statement::Expression(expression) => self.check_expression(expression),
+        }
+    }
+
+    fn check_block(&mut self, body: &[ast::LocatedStatement]) {
+        for located in body {
+            self.check_statement(&located.statement);
+        }
+    }
+
+    fn check_expression(&mut self, expression: &ast::Expression) {
+        match expression {
+            ast::Expression::Binary(binary_expression) => {
+                self.check_expression(&binary_expression.left);
+                self.check_expression(&binary_expression.right);
+            }
+            ast::Expression::VariableAssignment(variable_assignment_expression) => {
+                self.check_expression(&variable_assignment_expression.assignee);
+                self.check_expression(&variable_assignment_expression.value);
+            }
+            ast::Expression::DestructuringAssignment(destructuring_assignment_expression) => {
+                self.check_expression(&destructuring_assignment_expression.value);
+            }
+            ast::Expression::Member(member_expression) => {
+                self.check_expression(&member_expression.object);
+            }
+            ast::Expression::MethodCall(method_call_expression) => {
+                self.check_expression(&method_call_expression.object);
+                for argument in &method_call_expression.arguments {
+                    self.check_expression(argument);
+                }
+            }
+            ast::Expression::Call(call_expression) => {
+                self.check_expression(&call_expression.callee);
+                for argument in &call_expression.arguments {
+                    self.check_expression(argument);
+                }
+            }
+            ast::Expression::Propagation(propagation_expression) => {
+                self.check_expression(&propagation_expression.value);
+            }
+            ast::Expression::Template(template) => {
+                for part in &template.parts {
+                    if let ast::TemplatePart::Interpolation { expression, .. } = part {
+                        self.check_expression(expression);
+                    }
+                }
+            }
+            ast::Expression::Tuple(tuple) => {
+                for element in &tuple.elements {
+                    self.check_expression(element);
+                }
+            }
+            ast::Expression::Array(array) => {
+                for element in &array.elements {
+                    self.check_expression(element);
+                }
+            }
+            ast::Expression::Object(object) => {
+                for (_, value) in &object.properties {
+                    self.check_expression(value);
+                }
+            }
+            ast::Expression::Struct(struct_literal) => {
+                for (_, value) in &struct_literal.fields {
+                    self.check_expression(value);
+                }
+            }
+            ast::Expression::EnumVariant(enum_variant_expression) => {
+                match &enum_variant_expression.payload {
+                    ast::EnumPayload::Unit => (),
+                    ast::EnumPayload::Tuple(values) => {
+                        for value in values {
+                            self.check_expression(value);
+                        }
+                    }
+                    ast::EnumPayload::Struct(fields) => {
+                        for (_, value) in fields {
+                            self.check_expression(value);
+                        }
+                    }
+                }
+            }
+            ast::Expression::Match(match_expression) => {
+                self.check_expression(&match_expression.subject);
+                for arm in &match_expression.arms {
+                    if let Some(guard) = &arm.guard {
+                        self.check_expression(guard);
+                    }
+                    self.check_expression(&arm.body);
+                }
+                self.check_match(match_expression);
+            }
+            _ => (),
+        }
+    }
+
+    fn check_match(&mut self, match_expression: &ast::MatchExpression) {
+        // A catch-all arm without a guard makes any match exhaustive
+        if match_expression
+            .arms
+            .iter()
+            .any(|arm| arm.guard.is_none() && arm.pattern.is_irrefutable())
+        {
+            return;
+        }
+
+        let mut enum_identifier: Option<&String> = None;
+        for arm in &match_expression.arms {
+            if let ast::Pattern::EnumVariant {
+                enum_identifier: identifier,
+                ..
+            } = &arm.pattern
+            {
+                match enum_identifier {
+                    Some(existing) if existing != identifier => {
+                        self.errors.push(format!(
+                            "Match arms cannot mix patterns from the enums {} and {}",
+                            existing, identifier
+                        ));
+                        return;
+                    }
+                    _ => enum_identifier = Some(identifier),
+                }
+            }
+        }
+
+        // Matches over non-enum values cannot be checked without knowing the
+        // type of the subject, they are reported at runtime instead
+        let enum_identifier = match enum_identifier {
+            Some(identifier) => identifier,
+            None => return,
+        };
+
+        let declaration = match self.enums.get(enum_identifier) {
+            Some(declaration) => declaration,
+            None => {
+                self.errors
+                    .push(format!("Enum \"{}\" does not exist", enum_identifier));
+                return;
+            }
+        };
+
+        let mut covered: Vec<&String> = Vec::new();
+        for arm in &match_expression.arms {
+            let (variant_identifier, payload) = match &arm.pattern {
+                ast::Pattern::EnumVariant {
+                    variant_identifier,
+                    payload,
+                    ..
+                } => (variant_identifier, payload),
+                _ => continue,
+            };
+
+            if declaration.find_variant(variant_identifier).is_none() {
+                self.errors.push(format!(
+                    "Enum {} has no variant named {}",
+                    enum_identifier, variant_identifier
+                ));
+                return;
+            }
+
+            let payload_is_irrefutable = match payload {
+                ast::EnumPayload::Unit => true,
+                ast::EnumPayload::Tuple(patterns) => {
+                    patterns.iter().all(|pattern| pattern.is_irrefutable())
+                }
+                ast::EnumPayload::Struct(fields) => {
+                    fields.iter().all(|(_, pattern)| pattern.is_irrefutable())
+                }
+            };
+
+            if arm.guard.is_none() && payload_is_irrefutable {
+                covered.push(variant_identifier);
+            }
+        }
+
+        let missing: Vec<String> = declaration
+            .variants
+            .iter()
+            .filter(|variant| !covered.contains(&&variant.identifier))
+            .map(|variant| format!("{}::{}", enum_identifier, variant.identifier))
+            .collect();
+
+        if !missing.is_empty() {
+            self.errors.push(format!(
+                "Non-exhaustive match on enum {}, patterns not covered: {}",
+                enum_identifier,
+                missing.join(", ")
+            ));
+        }
+    }
+}
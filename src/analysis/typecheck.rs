@@ -0,0 +1,2076 @@
+use super::super::frontend::{ast, prelude};
+use super::super::runtime::{
+    environment::{self, Environment},
+    generics,
+    values::ValueType,
+};
+use std::collections::{HashMap, HashSet};
+
+const INTEGER_TYPES: [&str; 11] = [
+    "i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128", "bigint",
+];
+const FLOAT_TYPES: [&str; 2] = ["f32", "f64"];
+const DECIMAL_TYPE: &str = "decimal";
+/// The other types built into the language that take no type arguments
+const BUILTIN_TYPES: [&str; 7] = ["str", "char", "bool", "null", "array", "object", "fn"];
+
+#[derive(Clone)]
+struct Variable {
+    /// `None` when the type cannot be known before the program runs
+    value_type: Option<ValueType>,
+    constant: bool,
+    /// True for variables declared without a type annotation, which may be
+    /// given a value of any type
+    dynamic: bool,
+    /// False for variables declared without a value until one is assigned
+    assigned: bool,
+    /// Where the variable was declared, `None` for variables declared
+    /// before the program being checked, such as in earlier REPL lines
+    location: Option<ast::SourceLocation>,
+    /// Whether the variable has been read since it was declared
+    read: bool,
+    /// The annotation of a typed variable with type aliases expanded, `None`
+    /// when it is dynamic or mentions type parameters
+    declared: Option<ast::TypeExpr>,
+    /// Whether the variable may hold null, because it is declared with an
+    /// optional type or was given a value that may be null
+    nullable: bool,
+}
+
+/// Infers the types of declarations, assignments and binary expressions
+/// before a program is evaluated, mirroring the checks done at runtime by
+/// `helpers::evaluate_variable_type`. Every error found is reported at once.
+///
+/// Variables are remembered between calls so the REPL can check lines that
+/// use variables declared in earlier lines.
+pub struct TypeChecker {
+    scopes: Vec<HashMap<String, Variable>>,
+    functions: HashMap<String, ast::FunctionDeclarationStatement>,
+    structs: HashMap<String, ast::StructDeclarationStatement>,
+    type_aliases: HashMap<String, ast::TypeExpr>,
+    /// The type wrapped by each newtype
+    newtypes: HashMap<String, ast::TypeExpr>,
+    /// The names of the enums, structs, newtypes and type aliases declared
+    /// anywhere in the program, which types may use before their declaration
+    type_names: HashSet<String>,
+    /// The type parameters of the function being checked, whose types are
+    /// only known once it is called
+    type_parameters: Vec<String>,
+    /// The return type of the function being checked, `Some(None)` when it
+    /// is declared without one
+    return_type: Option<Option<ast::TypeExpr>>,
+    /// The instantiation of the generic function called by the last call
+    /// inferred, e.g. `id<i32>`, named in errors about the value it returns
+    instantiation: Option<String>,
+    /// Whether redeclaring a variable in the same scope is an error rather
+    /// than shadowing it
+    strict_shadowing: bool,
+    mutability: ast::Mutability,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+    location: Option<ast::SourceLocation>,
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            scopes: vec![HashMap::new()],
+            functions: HashMap::new(),
+            structs: HashMap::new(),
+            type_aliases: HashMap::new(),
+            newtypes: HashMap::new(),
+            type_names: HashSet::new(),
+            type_parameters: Vec::new(),
+            return_type: None,
+            instantiation: None,
+            strict_shadowing: false,
+            mutability: ast::Mutability::default(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            location: None,
+        }
+    }
+
+    /// Declares a variable imported from a module that has been checked but
+    /// not evaluated, so only its type is known.
+    pub fn declare_import(&mut self, identifier: String, value_type: Option<ValueType>) {
+        self.scopes[0].insert(
+            identifier,
+            Variable {
+                value_type,
+                constant: true,
+                dynamic: true,
+                assigned: true,
+                location: None,
+                read: false,
+                declared: None,
+                nullable: false,
+            },
+        );
+    }
+
+    /// The type of a global variable once a program has been checked,
+    /// `Some(None)` when it is declared but its type is only known at
+    /// runtime.
+    pub fn global_type(&self, identifier: &str) -> Option<Option<ValueType>> {
+        self.scopes[0]
+            .get(identifier)
+            .map(|variable| variable.value_type.to_owned())
+    }
+
+    /// Checks a program against the variables already declared in the
+    /// global scope of `environment`, such as imported names.
+    pub fn check_program(
+        &mut self,
+        program: &ast::Statement,
+        environment: &Environment,
+    ) -> Result<(), String> {
+        for (identifier, binding) in environment.bindings() {
+            let value_type = match &binding.declared_type {
+                Some(declared_type) => self.declared_type(declared_type),
+                None => Some(binding.value.to_owned().as_value_type()),
+            };
+            self.scopes[0]
+                .entry(identifier.to_owned())
+                .or_insert(Variable {
+                    value_type,
+                    constant: binding.constant,
+                    dynamic: binding.declared_type.is_none(),
+                    assigned: binding.assigned,
+                    location: None,
+                    read: false,
+                    declared: binding.declared_type.to_owned(),
+                    nullable: matches!(binding.declared_type, Some(ast::TypeExpr::Optional(_))),
+                });
+        }
+        for struct_declaration in environment.struct_declarations() {
+            self.structs
+                .entry(struct_declaration.identifier.to_owned())
+                .or_insert(struct_declaration);
+        }
+        for (identifier, value_type) in environment.type_aliases() {
+            self.type_aliases.entry(identifier).or_insert(value_type);
+        }
+        for newtype_declaration in environment.newtype_declarations() {
+            self.newtypes
+                .entry(newtype_declaration.identifier)
+                .or_insert(newtype_declaration.value_type);
+        }
+        self.type_names.extend(environment.enum_identifiers());
+        if let ast::Statement::Program(program) = program {
+            self.type_names.extend(
+                program
+                    .body
+                    .iter()
+                    .filter_map(|statement| type_name(&statement.statement))
+                    .map(str::to_string),
+            );
+        }
+
+        self.strict_shadowing = environment.strict_shadowing();
+        self.mutability = environment.mutability();
+
+        // Declarations from a program that fails to check are forgotten
+        let global_scope = self.scopes[0].to_owned();
+        self.errors.clear();
+        self.warnings.clear();
+        self.location = None;
+        self.check_statement(program);
+
+        if self.errors.is_empty() {
+            // Redeclaring a variable from an earlier REPL line is deliberate
+            for variable in self.scopes[0].values_mut() {
+                variable.location = None;
+            }
+            return Ok(());
+        }
+        self.scopes = vec![global_scope];
+        Err(self.errors.join("\n"))
+    }
+
+    fn error(&mut self, message: String) {
+        let error = match self.location {
+            Some(location) => format!(
+                "TypeError: {} (line {}, column {})",
+                message, location.line, location.column
+            ),
+            None => format!("TypeError: {}", message),
+        };
+        self.errors.push(error);
+    }
+
+    /// The warnings found by the last call to `check_program`, which do not
+    /// stop the program from running.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    fn warn(&mut self, message: String) {
+        let warning = match self.location {
+            Some(location) => format!(
+                "Warning: {} (line {}, column {})",
+                message, location.line, location.column
+            ),
+            None => format!("Warning: {}", message),
+        };
+        self.warnings.push(warning);
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn exit_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn lookup(&self, identifier: &str) -> Option<&Variable> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(identifier))
+    }
+
+    /// Looks up a variable and marks it as read.
+    fn read(&mut self, identifier: &str) -> Option<&Variable> {
+        let variable = self.lookup_mut(identifier)?;
+        variable.read = true;
+        Some(variable)
+    }
+
+    fn lookup_mut(&mut self, identifier: &str) -> Option<&mut Variable> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(identifier))
+    }
+
+    fn declare(&mut self, identifier: &str, value_type: Option<ValueType>, constant: bool) {
+        self.insert_variable(
+            identifier,
+            Variable {
+                value_type,
+                constant,
+                dynamic: true,
+                assigned: true,
+                location: self.location,
+                read: false,
+                declared: None,
+                nullable: false,
+            },
+        );
+    }
+
+    /// Declares a variable with a type annotation, which every value
+    /// assigned to it must match.
+    fn declare_typed(
+        &mut self,
+        identifier: &str,
+        declared: &ast::TypeExpr,
+        value_type: Option<ValueType>,
+        constant: bool,
+    ) {
+        let type_parameters = self.type_parameters.to_owned();
+        self.check_type_names(declared, &type_parameters);
+        let expanded = self.expand_type_aliases(declared, &self.type_parameters);
+        let nullable = matches!(expanded, ast::TypeExpr::Optional(_));
+        let declared = match declared.mentions(&self.type_parameters) {
+            true => None,
+            false => Some(expanded),
+        };
+        self.insert_variable(
+            identifier,
+            Variable {
+                value_type,
+                constant,
+                dynamic: false,
+                assigned: true,
+                location: self.location,
+                read: false,
+                declared,
+                nullable,
+            },
+        );
+    }
+
+    /// Declares a variable in the current scope, shadowing any variable of
+    /// the same name already declared in it unless shadowing is strict.
+    /// Shadowing a variable that was never read is likely a mistake, so it
+    /// is warned about.
+    fn insert_variable(&mut self, identifier: &str, variable: Variable) {
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("the global scope is never exited");
+        match scope.get(identifier) {
+            Some(_) if self.strict_shadowing => {
+                self.error(format!(
+                    "Cannot declare variable {} as it has already been defined",
+                    identifier
+                ));
+                return;
+            }
+            Some(Variable {
+                location: Some(location),
+                read: false,
+                ..
+            }) => {
+                let message = format!(
+                    "{} shadows the variable declared in the same scope on line {}, which is never read",
+                    identifier, location.line
+                );
+                self.warn(message);
+            }
+            _ => (),
+        }
+        // A global declared with the name of a function replaces it
+        if self.scopes.len() == 1 {
+            self.functions.remove(identifier);
+        }
+        self.scopes
+            .last_mut()
+            .expect("the global scope is never exited")
+            .insert(identifier.to_string(), variable);
+    }
+
+    fn set_assigned(&mut self, scope_index: usize, identifier: &str, assigned: bool) {
+        if let Some(variable) = self.scopes[scope_index].get_mut(identifier) {
+            variable.assigned = assigned;
+        }
+    }
+
+    fn mark_assigned(&mut self, identifier: &str) {
+        if let Some(scope_index) = self
+            .scopes
+            .iter()
+            .rposition(|scope| scope.contains_key(identifier))
+        {
+            self.set_assigned(scope_index, identifier, true);
+        }
+    }
+
+    /// The variables declared without a value that have not been assigned
+    /// yet, along with the index of their scope.
+    fn unassigned(&self) -> Vec<(usize, String)> {
+        let mut unassigned = Vec::new();
+        for (scope_index, scope) in self.scopes.iter().enumerate() {
+            for (identifier, variable) in scope {
+                if !variable.assigned {
+                    unassigned.push((scope_index, identifier.to_owned()));
+                }
+            }
+        }
+        unassigned
+    }
+
+    /// Marks variables as unassigned again, so that each branch of a match
+    /// or try statement is checked from the same starting point.
+    fn mark_unassigned(&mut self, unassigned: &[(usize, String)]) {
+        for (scope_index, identifier) in unassigned {
+            if *scope_index < self.scopes.len() {
+                self.set_assigned(*scope_index, identifier, false);
+            }
+        }
+    }
+
+    fn check_statement(&mut self, statement: &ast::Statement) {
+        match statement {
+            ast::Statement::Program(program) => self.check_statements(&program.body),
+            ast::Statement::VariableDeclaration(variable_declaration) => {
+                self.check_variable_declaration(variable_declaration)
+            }
+            ast::Statement::DestructuringDeclaration(destructuring_declaration) => {
+                let value_type = self.infer(&destructuring_declaration.value);
+                let constant = self.mutability.is_immutable(
+                    destructuring_declaration.constant,
+                    destructuring_declaration.mutable,
+                );
+                self.bind_pattern(&destructuring_declaration.pattern, value_type, constant);
+            }
+            ast::Statement::EnumDeclaration(enum_declaration) => {
+                self.type_names
+                    .insert(enum_declaration.identifier.to_owned());
+                for variant in &enum_declaration.variants {
+                    let payload_types = match &variant.payload {
+                        ast::EnumPayload::Unit => Vec::new(),
+                        ast::EnumPayload::Tuple(payload_types) => payload_types.iter().collect(),
+                        ast::EnumPayload::Struct(fields) => {
+                            fields.iter().map(|(_, field_type)| field_type).collect()
+                        }
+                    };
+                    for payload_type in payload_types {
+                        self.check_type_names(payload_type, &enum_declaration.type_parameters);
+                    }
+                }
+            }
+            ast::Statement::Import(_) => (),
+            ast::Statement::FunctionDeclaration(function_declaration) => {
+                self.check_function_declaration(function_declaration)
+            }
+            ast::Statement::StructDeclaration(struct_declaration) => {
+                let mut struct_declaration = struct_declaration.to_owned();
+                let type_parameters =
+                    ast::type_parameter_identifiers(&struct_declaration.type_parameters);
+                self.type_names
+                    .insert(struct_declaration.identifier.to_owned());
+                for (_, field_type) in &mut struct_declaration.fields {
+                    self.check_type_names(field_type, &type_parameters);
+                    *field_type = self.expand_type_aliases(field_type, &type_parameters);
+                    if !field_type.mentions(&type_parameters) {
+                        self.check_hashable(field_type);
+                    }
+                }
+                self.structs
+                    .insert(struct_declaration.identifier.to_owned(), struct_declaration);
+            }
+            ast::Statement::TypeAlias(type_alias) => {
+                self.type_names.insert(type_alias.identifier.to_owned());
+                self.check_type_names(&type_alias.value_type, &[]);
+                let value_type = self.expand_type_aliases(&type_alias.value_type, &[]);
+                self.type_aliases
+                    .insert(type_alias.identifier.to_owned(), value_type);
+            }
+            ast::Statement::NewtypeDeclaration(newtype_declaration) => {
+                self.type_names
+                    .insert(newtype_declaration.identifier.to_owned());
+                self.check_type_names(&newtype_declaration.value_type, &[]);
+                let value_type = self.expand_type_aliases(&newtype_declaration.value_type, &[]);
+                self.newtypes
+                    .insert(newtype_declaration.identifier.to_owned(), value_type);
+            }
+            ast::Statement::Export(export_statement) => {
+                self.check_statement(&export_statement.declaration)
+            }
+            ast::Statement::Throw(throw_statement) => {
+                self.infer(&throw_statement.value);
+            }
+            ast::Statement::Try(try_statement) => {
+                // The try block may stop before assigning a variable, so an
+                // assignment is only certain when the catch block makes it
+                // too, or when the finally block makes it
+                let unassigned = self.unassigned();
+                self.check_block(&try_statement.body.body);
+                let mut unassigned_after = self.unassigned();
+                if let Some(catch_clause) = &try_statement.catch_clause {
+                    self.mark_unassigned(&unassigned);
+                    self.enter_scope();
+                    if let Some(identifier) = &catch_clause.identifier {
+                        self.declare(identifier, Some(ValueType::Object), false);
+                    }
+                    self.check_statements(&catch_clause.body.body);
+                    self.exit_scope();
+                    for variable in self.unassigned() {
+                        if !unassigned_after.contains(&variable) {
+                            unassigned_after.push(variable);
+                        }
+                    }
+                }
+                if let Some(finally_body) = &try_statement.finally_body {
+                    self.mark_unassigned(&unassigned);
+                    self.check_block(&finally_body.body);
+                    let unassigned_after_finally = self.unassigned();
+                    unassigned_after.retain(|variable| unassigned_after_finally.contains(variable));
+                }
+                for (scope_index, identifier) in &unassigned {
+                    let assigned =
+                        !unassigned_after.contains(&(*scope_index, identifier.to_owned()));
+                    self.set_assigned(*scope_index, identifier, assigned);
+                }
+            }
+            ast::Statement::Expression(expression) => {
+                self.infer(expression);
+            }
+        }
+    }
+
+    fn check_statements(&mut self, body: &[ast::LocatedStatement]) {
+        for located in body {
+            self.location = Some(located.location);
+            self.check_statement(&located.statement);
+        }
+    }
+
+    fn check_block(&mut self, body: &[ast::LocatedStatement]) {
+        self.enter_scope();
+        self.check_statements(body);
+        self.exit_scope();
+    }
+
+    fn check_variable_declaration(
+        &mut self,
+        variable_declaration: &ast::VariableDeclarationStatement,
+    ) {
+        let value_type = match &variable_declaration.value {
+            Some(value) => self.infer(value),
+            None => None,
+        };
+
+        let value_type = match (
+            &variable_declaration.value_type,
+            &variable_declaration.value,
+        ) {
+            (Some(declared), Some(value)) => self.check_declared(
+                declared,
+                &value_type,
+                Some(value),
+                &variable_declaration.identifier,
+            ),
+            (Some(declared), None) if !declared.mentions(&self.type_parameters) => {
+                self.declared_type(&self.expand_type_aliases(declared, &self.type_parameters))
+            }
+            (_, None) => None,
+            _ => value_type,
+        };
+
+        let constant = self
+            .mutability
+            .is_immutable(variable_declaration.constant, variable_declaration.mutable);
+        match &variable_declaration.value_type {
+            Some(declared) => self.declare_typed(
+                &variable_declaration.identifier,
+                declared,
+                value_type,
+                constant,
+            ),
+            None => {
+                self.declare(&variable_declaration.identifier, value_type, constant);
+                if let Some(value) = &variable_declaration.value {
+                    let nullable = self.may_be_null(value);
+                    if let Some(variable) = self.lookup_mut(&variable_declaration.identifier) {
+                        variable.nullable = nullable;
+                    }
+                }
+            }
+        }
+        if variable_declaration.value.is_none() {
+            let scope_index = self.scopes.len() - 1;
+            self.set_assigned(scope_index, &variable_declaration.identifier, false);
+        }
+    }
+
+    /// Checks a value against the type a variable is declared with,
+    /// returning the type of the variable. Types mentioning the type
+    /// parameters of the function being checked are left to the runtime.
+    fn check_declared(
+        &mut self,
+        declared: &ast::TypeExpr,
+        value_type: &Option<ValueType>,
+        value: Option<&ast::Expression>,
+        identifier: &str,
+    ) -> Option<ValueType> {
+        // Types with unknown names are reported where they are declared
+        if declared.mentions(&self.type_parameters) || !self.knows_type_names(declared) {
+            return None;
+        }
+        let declared = &self.expand_type_aliases(declared, &self.type_parameters);
+        self.check_hashable(declared);
+        let instantiation = match value {
+            Some(ast::Expression::Call(_)) => self.instantiation.take(),
+            _ => None,
+        };
+        if let Err(m) = check_type(declared, value_type, value, identifier) {
+            match instantiation {
+                Some(instantiation) => self.error(format!("{} (in {})", m, instantiation)),
+                None => self.error(m),
+            }
+        }
+        if let Err(m) = self.check_not_null(declared, value, identifier) {
+            self.error(m);
+        }
+        self.declared_type(declared)
+    }
+
+    /// Reports a value that may be null given to a type that is not
+    /// optional, e.g. an `i32?` variable assigned to an `i32` one.
+    fn check_not_null(
+        &mut self,
+        declared: &ast::TypeExpr,
+        value: Option<&ast::Expression>,
+        identifier: &str,
+    ) -> Result<(), String> {
+        let value = match value {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+        if matches!(declared, ast::TypeExpr::Optional(_))
+            || *declared == ast::TypeExpr::named("null")
+            || !self.may_be_null(value)
+        {
+            return Ok(());
+        }
+        Err(format!(
+            "Incorrect value for {}, expected: {}, got: a value that may be null, use ?? to give a value in its place",
+            identifier, declared
+        ))
+    }
+
+    /// Whether an expression may evaluate to null without having been
+    /// checked for it with `??`.
+    fn may_be_null(&self, expression: &ast::Expression) -> bool {
+        match expression {
+            ast::Expression::Identifier(identifier) => self
+                .lookup(&identifier.symbol)
+                .is_some_and(|variable| variable.nullable),
+            ast::Expression::Call(call_expression) => match &call_expression.callee {
+                ast::Expression::Identifier(identifier) => self
+                    .functions
+                    .get(&identifier.symbol)
+                    .and_then(|function_declaration| function_declaration.return_type.as_ref())
+                    .is_some_and(|return_type| matches!(return_type, ast::TypeExpr::Optional(_))),
+                _ => false,
+            },
+            // `?.` gives null for the rest of the chain it starts
+            ast::Expression::Member(member_expression) => {
+                member_expression.optional || self.may_be_null_chain(&member_expression.object)
+            }
+            ast::Expression::MethodCall(method_call_expression) => {
+                method_call_expression.optional
+                    || self.may_be_null_chain(&method_call_expression.object)
+            }
+            ast::Expression::Binary(binary_expression) if binary_expression.operator == "??" => {
+                self.may_be_null(&binary_expression.right)
+            }
+            _ => false,
+        }
+    }
+
+    fn may_be_null_chain(&self, object: &ast::Expression) -> bool {
+        matches!(
+            object,
+            ast::Expression::Member(_) | ast::Expression::MethodCall(_)
+        ) && self.may_be_null(object)
+    }
+
+    /// Reports names in a type that are not declared types, and type
+    /// arguments given to types that take none, e.g. `i32<str>`.
+    fn check_type_names(&mut self, declared: &ast::TypeExpr, type_parameters: &[String]) {
+        let mut errors = Vec::new();
+        self.type_name_errors(declared, type_parameters, &mut errors);
+        for m in errors {
+            self.error(m);
+        }
+    }
+
+    fn knows_type_names(&self, declared: &ast::TypeExpr) -> bool {
+        let mut errors = Vec::new();
+        self.type_name_errors(declared, &self.type_parameters, &mut errors);
+        errors.is_empty()
+    }
+
+    fn type_name_errors(
+        &self,
+        declared: &ast::TypeExpr,
+        type_parameters: &[String],
+        errors: &mut Vec<String>,
+    ) {
+        match declared {
+            ast::TypeExpr::Named {
+                identifier,
+                type_arguments,
+            } => {
+                let takes_type_arguments = match identifier.as_str() {
+                    "Map" | "Set" => true,
+                    identifier
+                        if is_builtin_type(identifier)
+                            || type_parameters.contains(&identifier.to_string())
+                            || self.type_aliases.contains_key(identifier)
+                            || self.newtypes.contains_key(identifier) =>
+                    {
+                        false
+                    }
+                    identifier
+                        if self.structs.contains_key(identifier)
+                            || self.type_names.contains(identifier) =>
+                    {
+                        true
+                    }
+                    identifier => {
+                        errors.push(format!("Unknown type: {}", identifier));
+                        return;
+                    }
+                };
+                if !takes_type_arguments && !type_arguments.is_empty() {
+                    errors.push(format!(
+                        "{} does not take type arguments, got: {}",
+                        identifier, declared
+                    ));
+                }
+                for type_argument in type_arguments {
+                    self.type_name_errors(type_argument, type_parameters, errors);
+                }
+            }
+            ast::TypeExpr::Array(element_type) | ast::TypeExpr::Optional(element_type) => {
+                self.type_name_errors(element_type, type_parameters, errors)
+            }
+            ast::TypeExpr::Tuple(element_types) => {
+                for element_type in element_types {
+                    self.type_name_errors(element_type, type_parameters, errors);
+                }
+            }
+            ast::TypeExpr::Function {
+                parameters,
+                return_type,
+            } => {
+                for parameter in parameters {
+                    self.type_name_errors(parameter, type_parameters, errors);
+                }
+                self.type_name_errors(return_type, type_parameters, errors);
+            }
+        }
+    }
+
+    /// Reports maps and sets in a declared type whose keys or values cannot
+    /// be hashed.
+    fn check_hashable(&mut self, declared: &ast::TypeExpr) {
+        if let Err(m) = prelude::check_hashable(declared) {
+            self.error(m);
+        }
+    }
+
+    /// `declared_type` with user types told apart from structs and
+    /// newtypes.
+    fn declared_type(&self, declared: &ast::TypeExpr) -> Option<ValueType> {
+        match declared_type(declared) {
+            Some(ValueType::Enum(identifier)) if self.structs.contains_key(&identifier) => {
+                Some(ValueType::Struct(identifier))
+            }
+            Some(ValueType::Enum(identifier)) if self.newtypes.contains_key(&identifier) => {
+                Some(ValueType::Newtype(identifier))
+            }
+            value_type => value_type,
+        }
+    }
+
+    /// Replaces the type aliases in a type with the type they stand for.
+    fn expand_type_aliases(
+        &self,
+        declared: &ast::TypeExpr,
+        type_parameters: &[String],
+    ) -> ast::TypeExpr {
+        let mut type_aliases = self.type_aliases.to_owned();
+        type_aliases.retain(|identifier, _| !type_parameters.contains(identifier));
+        declared.substitute(&type_aliases)
+    }
+
+    /// Checks the body of a function against the global scope and its
+    /// parameters, which is all a function can see when it is called.
+    fn check_function_declaration(
+        &mut self,
+        function_declaration: &ast::FunctionDeclarationStatement,
+    ) {
+        let mut function_declaration = function_declaration.to_owned();
+        let type_parameters =
+            ast::type_parameter_identifiers(&function_declaration.type_parameters);
+        for (_, parameter_type) in &mut function_declaration.parameters {
+            *parameter_type = self.expand_type_aliases(parameter_type, &type_parameters);
+        }
+        if let Some(return_type) = &mut function_declaration.return_type {
+            *return_type = self.expand_type_aliases(return_type, &type_parameters);
+        }
+        let function_declaration = &function_declaration;
+        self.declare(
+            &function_declaration.identifier,
+            Some(ValueType::Function),
+            true,
+        );
+        self.functions.insert(
+            function_declaration.identifier.to_owned(),
+            function_declaration.to_owned(),
+        );
+
+        // Functions may be called once their globals have been assigned, which
+        // is left to the runtime to check, and by then dynamically typed
+        // globals may hold a value of any type
+        let mut global_scope = self.scopes[0].to_owned();
+        for variable in global_scope.values_mut() {
+            variable.assigned = true;
+            if variable.dynamic && !variable.constant {
+                variable.value_type = None;
+            }
+        }
+        let scopes = std::mem::replace(&mut self.scopes, vec![global_scope]);
+        let type_parameters = std::mem::replace(
+            &mut self.type_parameters,
+            ast::type_parameter_identifiers(&function_declaration.type_parameters),
+        );
+        let return_type = self
+            .return_type
+            .replace(function_declaration.return_type.to_owned());
+        if let Some(declared) = &function_declaration.return_type {
+            let type_parameters = self.type_parameters.to_owned();
+            self.check_type_names(declared, &type_parameters);
+        }
+        self.enter_scope();
+        for (identifier, declared) in &function_declaration.parameters {
+            let parameter_type = match declared.mentions(&self.type_parameters) {
+                true => None,
+                false => {
+                    self.check_hashable(declared);
+                    self.declared_type(declared)
+                }
+            };
+            self.declare_typed(identifier, declared, parameter_type, false);
+        }
+
+        let body = &function_declaration.body.body;
+        if let Some((last, rest)) = body.split_last() {
+            self.check_statements(rest);
+            self.location = Some(last.location);
+            match (&last.statement, &function_declaration.return_type) {
+                (ast::Statement::Expression(value), Some(return_type)) => {
+                    let value_type = self.infer(value);
+                    self.check_declared(
+                        return_type,
+                        &value_type,
+                        Some(value),
+                        &format!("the return value of {}", function_declaration.identifier),
+                    );
+                }
+                (statement, _) => self.check_statement(statement),
+            }
+        }
+
+        // Globals read by the function count as read once it is declared
+        let function_scopes = std::mem::replace(&mut self.scopes, scopes);
+        for (identifier, variable) in &function_scopes[0] {
+            if variable.read {
+                if let Some(global) = self.scopes[0].get_mut(identifier) {
+                    global.read = true;
+                }
+            }
+        }
+        self.type_parameters = type_parameters;
+        self.return_type = return_type;
+    }
+
+    /// Declares the names bound by a pattern, using the type of the value
+    /// being destructured where it is known.
+    fn bind_pattern(
+        &mut self,
+        pattern: &ast::Pattern,
+        value_type: Option<ValueType>,
+        constant: bool,
+    ) {
+        match pattern {
+            ast::Pattern::Binding {
+                identifier,
+                value_type: declared,
+                mutable,
+            } => {
+                let constant = constant && !mutable;
+                match declared {
+                    Some(declared) => {
+                        let value_type =
+                            self.check_declared(declared, &value_type, None, identifier);
+                        self.declare_typed(identifier, declared, value_type, constant);
+                    }
+                    None => self.declare(identifier, value_type, constant),
+                }
+            }
+            ast::Pattern::Tuple(patterns) => {
+                let element_types = match value_type {
+                    Some(ValueType::Tuple(element_types))
+                        if element_types.len() == patterns.len() =>
+                    {
+                        element_types.into_iter().map(Some).collect()
+                    }
+                    _ => vec![None; patterns.len()],
+                };
+                for (pattern, element_type) in patterns.iter().zip(element_types) {
+                    self.bind_pattern(pattern, element_type, constant);
+                }
+            }
+            _ => {
+                for identifier in pattern.binding_identifiers() {
+                    self.declare(&identifier, None, constant);
+                }
+            }
+        }
+    }
+
+    /// Infers the type of an expression, reporting any type errors found
+    /// inside it.
+    fn infer(&mut self, expression: &ast::Expression) -> Option<ValueType> {
+        match expression {
+            ast::Expression::Integer(_) => Some(ValueType::Integer32),
+            ast::Expression::BigInt(_) => Some(ValueType::BigInt),
+            ast::Expression::Decimal(_) => Some(ValueType::Decimal),
+            ast::Expression::Float(_) => Some(ValueType::Float32),
+            ast::Expression::String(_) => Some(ValueType::String),
+            ast::Expression::Template(template) => {
+                for part in &template.parts {
+                    if let ast::TemplatePart::Interpolation { expression, .. } = part {
+                        self.infer(expression);
+                    }
+                }
+                Some(ValueType::String)
+            }
+            ast::Expression::Character(_) => Some(ValueType::Character),
+            ast::Expression::Boolean(_) => Some(ValueType::Boolean),
+            ast::Expression::Null(_) => Some(ValueType::Null),
+            ast::Expression::Identifier(identifier) => match self.read(&identifier.symbol) {
+                Some(variable) if !variable.assigned => {
+                    self.error(format!(
+                        "Variable \"{}\" may be read before a value has been assigned to it",
+                        identifier.symbol
+                    ));
+                    None
+                }
+                Some(variable) => variable.value_type.to_owned(),
+                None => {
+                    self.error(format!("Variable \"{}\" does not exist", identifier.symbol));
+                    None
+                }
+            },
+            // `typeof` does not read the variable, so it may not have been
+            // assigned yet
+            ast::Expression::Typeof(typeof_expression) => {
+                if self.read(&typeof_expression.identifier).is_none() {
+                    self.error(format!(
+                        "Variable \"{}\" does not exist",
+                        typeof_expression.identifier
+                    ));
+                }
+                Some(ValueType::Object)
+            }
+            ast::Expression::Tuple(tuple) => {
+                let element_types: Vec<Option<ValueType>> = tuple
+                    .elements
+                    .iter()
+                    .map(|element| self.infer(element))
+                    .collect();
+                element_types
+                    .into_iter()
+                    .collect::<Option<Vec<ValueType>>>()
+                    .map(ValueType::Tuple)
+            }
+            ast::Expression::Array(array) => {
+                for element in &array.elements {
+                    self.infer(element);
+                }
+                Some(ValueType::Array)
+            }
+            ast::Expression::Object(object) => {
+                for (_, value) in &object.properties {
+                    self.infer(value);
+                }
+                Some(ValueType::Object)
+            }
+            ast::Expression::Binary(binary_expression) => {
+                self.infer_binary_expression(binary_expression)
+            }
+            ast::Expression::VariableAssignment(variable_assignment_expression) => {
+                self.infer_assignment_expression(variable_assignment_expression)
+            }
+            ast::Expression::DestructuringAssignment(destructuring_assignment_expression) => {
+                self.infer(&destructuring_assignment_expression.value);
+                for identifier in destructuring_assignment_expression
+                    .pattern
+                    .binding_identifiers()
+                {
+                    self.check_assignee(&identifier, None);
+                }
+                None
+            }
+            ast::Expression::Member(member_expression) => {
+                let object_type = self.infer(&member_expression.object);
+                // `object?.property` may be null
+                if member_expression.optional {
+                    return None;
+                }
+                match (object_type, &member_expression.property) {
+                    (Some(ValueType::Tuple(element_types)), ast::MemberProperty::Index(index)) => {
+                        match element_types.get(*index) {
+                            Some(element_type) => Some(element_type.to_owned()),
+                            None => {
+                                self.error(format!(
+                                    "Tuple index {} is out of bounds for a tuple of length {}",
+                                    index,
+                                    element_types.len()
+                                ));
+                                None
+                            }
+                        }
+                    }
+                    (Some(ValueType::Newtype(identifier)), ast::MemberProperty::Index(0)) => {
+                        let value_type = self.newtypes.get(&identifier)?.to_owned();
+                        self.declared_type(&value_type)
+                    }
+                    (Some(ValueType::Struct(identifier)), ast::MemberProperty::Name(field)) => {
+                        let field_type = match self.structs.get(&identifier) {
+                            Some(struct_declaration) => {
+                                let type_parameters = ast::type_parameter_identifiers(
+                                    &struct_declaration.type_parameters,
+                                );
+                                struct_declaration.field_type(field).map(|field_type| {
+                                    match field_type.mentions(&type_parameters) {
+                                        true => None,
+                                        false => Some(field_type.to_owned()),
+                                    }
+                                })
+                            }
+                            None => return None,
+                        };
+                        match field_type {
+                            Some(field_type) => {
+                                field_type.and_then(|field_type| self.declared_type(&field_type))
+                            }
+                            None => {
+                                self.error(format!(
+                                    "Struct {} has no field named {}",
+                                    identifier, field
+                                ));
+                                None
+                            }
+                        }
+                    }
+                    _ => None,
+                }
+            }
+            ast::Expression::MethodCall(method_call_expression) => {
+                self.infer_method_call_expression(method_call_expression)
+            }
+            ast::Expression::Call(call_expression) => self.infer_call_expression(call_expression),
+            ast::Expression::Struct(struct_literal) => self.infer_struct_literal(struct_literal),
+            ast::Expression::Propagation(propagation_expression) => {
+                let value_type = self.infer(&propagation_expression.value);
+                self.check_propagation(value_type);
+                None
+            }
+            ast::Expression::EnumVariant(enum_variant_expression) => {
+                match &enum_variant_expression.payload {
+                    ast::EnumPayload::Unit => (),
+                    ast::EnumPayload::Tuple(values) => {
+                        for value in values {
+                            self.infer(value);
+                        }
+                    }
+                    ast::EnumPayload::Struct(fields) => {
+                        for (_, value) in fields {
+                            self.infer(value);
+                        }
+                    }
+                }
+                Some(ValueType::Enum(
+                    enum_variant_expression.enum_identifier.to_owned(),
+                ))
+            }
+            ast::Expression::Match(match_expression) => {
+                self.infer_match_expression(match_expression)
+            }
+        }
+    }
+
+    /// Reports `?` applied to a value that is not an `Option` or `Result`,
+    /// or used in a function that cannot return the `None` or `Err` it
+    /// hands back. Outside functions it ends the program, which is left to
+    /// the runtime.
+    fn check_propagation(&mut self, value_type: Option<ValueType>) {
+        let propagated = match value_type {
+            Some(ValueType::Enum(identifier))
+                if identifier == "Option" || identifier == "Result" =>
+            {
+                Some(identifier)
+            }
+            Some(value_type) => {
+                self.error(format!(
+                    "The ? operator can only be applied to Option and Result values, got: {}",
+                    value_type.as_string()
+                ));
+                return;
+            }
+            None => None,
+        };
+        let return_type = match &self.return_type {
+            Some(return_type) => return_type.to_owned(),
+            None => return,
+        };
+        let returned = match &return_type {
+            Some(ast::TypeExpr::Named { identifier, .. })
+                if identifier == "Option" || identifier == "Result" =>
+            {
+                identifier.to_owned()
+            }
+            Some(return_type) if return_type.mentions(&self.type_parameters) => return,
+            Some(return_type) => {
+                self.error(format!(
+                    "The ? operator can only be used in a function returning Option or Result, not {}",
+                    return_type
+                ));
+                return;
+            }
+            None => {
+                self.error(
+                    "The ? operator can only be used in a function declared to return Option or Result"
+                        .to_string(),
+                );
+                return;
+            }
+        };
+        if let Some(propagated) = propagated.filter(|propagated| *propagated != returned) {
+            self.error(format!(
+                "The ? operator on {} values cannot be used in a function returning {}",
+                propagated, returned
+            ));
+        }
+    }
+
+    fn infer_binary_expression(
+        &mut self,
+        binary_expression: &ast::BinaryExpression,
+    ) -> Option<ValueType> {
+        let left_type = self.infer(&binary_expression.left);
+        let right_type = self.infer(&binary_expression.right);
+        let operator = binary_expression.operator.as_str();
+
+        if operator == "??" {
+            return match (left_type, right_type) {
+                (Some(ValueType::Null), right_type) => right_type,
+                (left_type, right_type) if left_type == right_type => left_type,
+                _ => None,
+            };
+        }
+        let is_comparison = matches!(
+            operator,
+            "===" | "==" | "!==" | "!=" | "<" | ">" | "<=" | ">="
+        );
+
+        // Newtypes only combine with values of the same newtype
+        match (&left_type, &right_type) {
+            (Some(ValueType::Newtype(lhs)), Some(ValueType::Newtype(rhs))) if lhs == rhs => {
+                return match is_comparison {
+                    true => Some(ValueType::Boolean),
+                    false => left_type,
+                };
+            }
+            (Some(ValueType::Null), _) | (_, Some(ValueType::Null)) => (),
+            (Some(left), Some(right))
+                if matches!(left, ValueType::Newtype(_))
+                    || matches!(right, ValueType::Newtype(_)) =>
+            {
+                self.error(format!(
+                    "Cannot apply {} to {} and {}",
+                    operator,
+                    left.to_owned().as_string(),
+                    right.to_owned().as_string()
+                ));
+                return None;
+            }
+            _ => (),
+        }
+
+        if matches!(operator, "===" | "==" | "!==" | "!=") {
+            return Some(ValueType::Boolean);
+        }
+
+        let (left_type, right_type) = match (left_type, right_type) {
+            (Some(left_type), Some(right_type)) => (left_type, right_type),
+            _ if matches!(operator, "<" | ">" | "<=" | ">=") => return Some(ValueType::Boolean),
+            _ => return None,
+        };
+
+        let result = match operator {
+            "<" | ">" | "<=" | ">=" => match left_type == right_type && is_ordered(&left_type) {
+                true => Some(ValueType::Boolean),
+                false => None,
+            },
+            "&" | "|" | "^" => match is_integer(&left_type) && left_type == right_type {
+                true => Some(left_type.to_owned()),
+                false => None,
+            },
+            // Any integer can be shifted by an integer amount
+            "<<" | ">>" => match is_integer(&left_type) && is_integer(&right_type) {
+                true => Some(left_type.to_owned()),
+                false => None,
+            },
+            _ if is_digit(&left_type) && left_type == right_type => Some(left_type.to_owned()),
+            // Any number can be raised to an integer power
+            "**" if is_digit(&left_type) && is_integer(&right_type) => Some(left_type.to_owned()),
+            "+" if left_type == ValueType::String
+                && matches!(right_type, ValueType::String | ValueType::Character) =>
+            {
+                Some(ValueType::String)
+            }
+            "*" if left_type == ValueType::String && is_integer(&right_type) => {
+                Some(ValueType::String)
+            }
+            _ => None,
+        };
+
+        if result.is_none() {
+            self.error(format!(
+                "Cannot perform the ({}) operator to a {} and a {}",
+                operator,
+                left_type.as_string(),
+                right_type.as_string()
+            ));
+        }
+        result
+    }
+
+    fn infer_assignment_expression(
+        &mut self,
+        variable_assignment_expression: &ast::VariableAssignmentExpression,
+    ) -> Option<ValueType> {
+        let value_type = self.infer(&variable_assignment_expression.value);
+
+        let identifier = match &variable_assignment_expression.assignee {
+            ast::Expression::Identifier(identifier) => &identifier.symbol,
+            _ => {
+                self.error("Invalid left hand side expression. Expected identifier".to_string());
+                return None;
+            }
+        };
+
+        self.check_assigned_null(identifier, &variable_assignment_expression.value);
+        let variable_type = match self.check_assignee(identifier, value_type.to_owned()) {
+            Some(variable_type) => variable_type,
+            None => return value_type,
+        };
+
+        if let Err(m) = check_type(
+            &variable_type.to_owned().as_type_expr(),
+            &value_type,
+            Some(&variable_assignment_expression.value),
+            identifier,
+        ) {
+            self.error(m);
+        }
+        Some(variable_type)
+    }
+
+    /// Checks a value that may be null against the declared type of a
+    /// variable, or remembers that a dynamic variable may now hold null.
+    fn check_assigned_null(&mut self, identifier: &str, value: &ast::Expression) {
+        let nullable = self.may_be_null(value);
+        let variable = match self.lookup_mut(identifier) {
+            Some(variable) => variable,
+            None => return,
+        };
+        match &variable.declared {
+            Some(declared) => {
+                let declared = declared.to_owned();
+                if let Err(m) = self.check_not_null(&declared, Some(value), identifier) {
+                    self.error(m);
+                }
+            }
+            None if variable.dynamic => variable.nullable = nullable,
+            None => (),
+        }
+    }
+
+    /// Reports assignments to undeclared or constant variables, returning
+    /// the type values assigned to the variable must have when it is known.
+    /// Dynamically typed variables accept any value, and their type is only
+    /// kept while every value assigned to them has the same type.
+    fn check_assignee(
+        &mut self,
+        identifier: &str,
+        value_type: Option<ValueType>,
+    ) -> Option<ValueType> {
+        let variable = self.lookup(identifier).cloned();
+        self.mark_assigned(identifier);
+        match variable {
+            // Immutable variables declared without a value may be assigned once
+            Some(variable) if variable.constant && variable.assigned => {
+                self.error(environment::reassignment_error(identifier, self.mutability));
+                None
+            }
+            Some(variable) if variable.dynamic => {
+                let assigned_type = match variable.constant || variable.value_type == value_type {
+                    true => value_type,
+                    false => None,
+                };
+                if let Some(variable) = self.lookup_mut(identifier) {
+                    variable.value_type = assigned_type;
+                }
+                None
+            }
+            Some(variable) => variable.value_type,
+            None => {
+                self.error(format!(
+                    "Unknown variable: {} detected during variable assignment",
+                    identifier
+                ));
+                None
+            }
+        }
+    }
+
+    /// Checks the keys and values given to the methods of a map or set
+    /// declared with type arguments, e.g. `m.insert(1, "a")` for a
+    /// `Map<i32, str>`.
+    fn check_element_arguments(
+        &mut self,
+        method_call_expression: &ast::MethodCallExpression,
+        argument_types: &[Option<ValueType>],
+    ) {
+        let receiver = match &method_call_expression.object {
+            ast::Expression::Identifier(identifier) => &identifier.symbol,
+            _ => return,
+        };
+        let declared = match self.lookup(receiver) {
+            Some(Variable {
+                declared: Some(declared),
+                ..
+            }) => declared.to_owned(),
+            _ => return,
+        };
+        let (identifier, type_arguments) = match &declared {
+            ast::TypeExpr::Named {
+                identifier,
+                type_arguments,
+            } => (identifier.as_str(), type_arguments.as_slice()),
+            _ => return,
+        };
+        let method = method_call_expression.method.as_str();
+        let element_types = match (identifier, type_arguments, method) {
+            ("Map", [key_type, value_type], "insert") => vec![
+                (key_type, format!("a key of {}", receiver)),
+                (value_type, format!("a value of {}", receiver)),
+            ],
+            ("Map", [key_type, _], "get" | "remove" | "contains") => {
+                vec![(key_type, format!("a key of {}", receiver))]
+            }
+            ("Set", [value_type], "insert" | "remove" | "contains") => {
+                vec![(value_type, format!("a value of {}", receiver))]
+            }
+            _ => return,
+        };
+
+        for ((element_type, label), (argument, argument_type)) in element_types
+            .into_iter()
+            .zip(method_call_expression.arguments.iter().zip(argument_types))
+        {
+            if let Err(m) = check_type(element_type, argument_type, Some(argument), &label) {
+                self.error(m);
+            }
+        }
+    }
+
+    /// `insert` and `remove` change a map or set in place, so they need a
+    /// variable that can be reassigned.
+    fn check_changed_receiver(&mut self, method_call_expression: &ast::MethodCallExpression) {
+        let identifier = match (
+            &method_call_expression.object,
+            method_call_expression.optional,
+        ) {
+            (ast::Expression::Identifier(identifier), false) => &identifier.symbol,
+            _ => {
+                self.error(format!(
+                    "{} changes its receiver in place, call it on a variable",
+                    method_call_expression.method
+                ));
+                return;
+            }
+        };
+        if self
+            .lookup(identifier)
+            .is_some_and(|variable| variable.constant && variable.assigned)
+        {
+            self.error(environment::reassignment_error(identifier, self.mutability));
+        }
+    }
+
+    fn infer_method_call_expression(
+        &mut self,
+        method_call_expression: &ast::MethodCallExpression,
+    ) -> Option<ValueType> {
+        let object_type = self.infer(&method_call_expression.object);
+        let type_parameters = self.type_parameters.to_owned();
+        for type_argument in &method_call_expression.type_arguments {
+            self.check_type_names(type_argument, &type_parameters);
+        }
+        let argument_types: Vec<Option<ValueType>> = method_call_expression
+            .arguments
+            .iter()
+            .map(|argument| self.infer(argument))
+            .collect();
+        let object_type = match method_call_expression.optional {
+            true => optional_object_type(object_type),
+            false => object_type,
+        };
+
+        if matches!(object_type, Some(ValueType::Map | ValueType::Set)) {
+            self.check_element_arguments(method_call_expression, &argument_types);
+        }
+
+        let method = method_call_expression.method.as_str();
+        let return_type = match (&object_type, method) {
+            (None, _) => return None,
+            (Some(ValueType::String), "len") => ValueType::Integer32,
+            (Some(ValueType::String), "is_empty" | "contains" | "starts_with" | "ends_with") => {
+                ValueType::Boolean
+            }
+            (Some(ValueType::String), "chars" | "bytes" | "split") => ValueType::Array,
+            (
+                Some(ValueType::String),
+                "trim" | "trim_start" | "trim_end" | "to_upper" | "to_lower" | "replace" | "repeat"
+                | "slice",
+            ) => ValueType::String,
+            (Some(ValueType::String), "find") => ValueType::Enum(String::from("Option")),
+            (Some(ValueType::String), "parse") => ValueType::Enum(String::from("Result")),
+            (
+                Some(ValueType::Character),
+                "is_digit" | "is_alphabetic" | "is_alphanumeric" | "is_whitespace" | "is_upper"
+                | "is_lower",
+            ) => ValueType::Boolean,
+            (Some(ValueType::Character), "to_upper" | "to_lower") => ValueType::Character,
+            (Some(ValueType::Character), "to_digit") => ValueType::Enum(String::from("Option")),
+            (Some(ValueType::Character), "to_string") => ValueType::String,
+            (Some(ValueType::Decimal), "round" | "div") => ValueType::Decimal,
+            (Some(ValueType::Decimal), "scale") => ValueType::Integer32,
+            (Some(ValueType::Decimal), "to_string") => ValueType::String,
+            (Some(ValueType::Map), "insert" | "remove") => {
+                self.check_changed_receiver(method_call_expression);
+                ValueType::Enum(String::from("Option"))
+            }
+            (Some(ValueType::Set), "insert" | "remove") => {
+                self.check_changed_receiver(method_call_expression);
+                ValueType::Boolean
+            }
+            (Some(ValueType::Set), "union" | "intersection" | "difference") => ValueType::Set,
+            (Some(ValueType::Map | ValueType::Set), "len") => ValueType::Integer32,
+            (Some(ValueType::Map | ValueType::Set), "is_empty" | "contains") => ValueType::Boolean,
+            (Some(ValueType::Map), "get") => ValueType::Enum(String::from("Option")),
+            (Some(ValueType::Map), "keys" | "values" | "entries") => ValueType::Array,
+            (Some(ValueType::Set), "values") => ValueType::Array,
+            // Namespaces and structs can hold functions, which are checked
+            // when they are called
+            (Some(ValueType::Object | ValueType::Struct(_)), _) => return None,
+            (Some(object_type), _) => {
+                self.error(format!(
+                    "No method named {} found for a value of type {}",
+                    method,
+                    object_type.to_owned().as_string()
+                ));
+                return None;
+            }
+        };
+
+        Some(return_type)
+    }
+
+    fn infer_call_expression(
+        &mut self,
+        call_expression: &ast::CallExpression,
+    ) -> Option<ValueType> {
+        if let ast::Expression::Identifier(identifier) = &call_expression.callee {
+            if let Some(value_type) = self.newtypes.get(&identifier.symbol).cloned() {
+                let newtype = self.infer_newtype_construction(
+                    &identifier.symbol,
+                    &value_type,
+                    call_expression,
+                );
+                self.instantiation = None;
+                return newtype;
+            }
+        }
+
+        let callee_type = self.infer(&call_expression.callee);
+        let type_parameters = self.type_parameters.to_owned();
+        for type_argument in &call_expression.type_arguments {
+            self.check_type_names(type_argument, &type_parameters);
+        }
+        let argument_types: Vec<Option<ValueType>> = call_expression
+            .arguments
+            .iter()
+            .map(|argument| self.infer(argument))
+            .collect();
+        self.instantiation = None;
+
+        match callee_type {
+            Some(ValueType::Function) | None => (),
+            Some(callee_type) => {
+                self.error(format!(
+                    "Cannot call a value of type {}",
+                    callee_type.as_string()
+                ));
+                return None;
+            }
+        }
+
+        // Only calls to functions declared by name can be checked
+        let function_declaration = match &call_expression.callee {
+            ast::Expression::Identifier(identifier) => {
+                self.functions.get(&identifier.symbol).cloned()?
+            }
+            _ => return None,
+        };
+
+        if call_expression.arguments.len() != function_declaration.parameters.len() {
+            self.error(format!(
+                "{} expects {} argument(s), got {}",
+                function_declaration.identifier,
+                function_declaration.parameters.len(),
+                call_expression.arguments.len()
+            ));
+            return None;
+        }
+
+        let type_parameters =
+            ast::type_parameter_identifiers(&function_declaration.type_parameters);
+        let mut type_arguments: HashMap<String, ast::TypeExpr> = type_parameters
+            .iter()
+            .cloned()
+            .zip(call_expression.type_arguments.iter().cloned())
+            .collect();
+        for ((_, parameter_type), argument_type) in
+            function_declaration.parameters.iter().zip(&argument_types)
+        {
+            if let Some(argument_type) = argument_type {
+                infer_type_arguments(
+                    parameter_type,
+                    argument_type,
+                    &type_parameters,
+                    &mut type_arguments,
+                );
+            }
+        }
+        for (((identifier, parameter_type), argument), argument_type) in function_declaration
+            .parameters
+            .iter()
+            .zip(&call_expression.arguments)
+            .zip(&argument_types)
+        {
+            let parameter_type = parameter_type.substitute(&type_arguments);
+            if parameter_type.mentions(&type_parameters) || !self.knows_type_names(&parameter_type)
+            {
+                continue;
+            }
+            if let Err(m) = check_type(&parameter_type, argument_type, Some(argument), identifier) {
+                self.error(format!("{} (in {})", m, function_declaration.identifier));
+            }
+            if let Err(m) = self.check_not_null(&parameter_type, Some(argument), identifier) {
+                self.error(format!("{} (in {})", m, function_declaration.identifier));
+            }
+        }
+
+        self.instantiation = match type_parameters.is_empty() {
+            true => None,
+            false => Some(generics::instantiation(
+                &function_declaration.identifier,
+                &function_declaration.type_parameters,
+                &type_arguments,
+            )),
+        };
+        let return_type = function_declaration
+            .return_type
+            .as_ref()?
+            .substitute(&type_arguments);
+        match return_type.mentions(&type_parameters) {
+            true => None,
+            false => self.declared_type(&return_type),
+        }
+    }
+
+    fn infer_newtype_construction(
+        &mut self,
+        identifier: &str,
+        value_type: &ast::TypeExpr,
+        call_expression: &ast::CallExpression,
+    ) -> Option<ValueType> {
+        let argument = match call_expression.arguments.as_slice() {
+            [argument] => argument,
+            arguments => {
+                self.error(format!(
+                    "Newtype {} expects 1 argument, got {}",
+                    identifier,
+                    arguments.len()
+                ));
+                return None;
+            }
+        };
+
+        let argument_type = self.infer(argument);
+        if let Err(m) = check_type(value_type, &argument_type, Some(argument), identifier) {
+            self.error(m);
+        }
+        Some(ValueType::Newtype(identifier.to_string()))
+    }
+
+    fn infer_struct_literal(&mut self, struct_literal: &ast::StructLiteral) -> Option<ValueType> {
+        let field_types: Vec<Option<ValueType>> = struct_literal
+            .fields
+            .iter()
+            .map(|(_, value)| self.infer(value))
+            .collect();
+
+        let struct_declaration = match self.structs.get(&struct_literal.identifier) {
+            Some(struct_declaration) => struct_declaration.to_owned(),
+            None => {
+                self.error(format!(
+                    "Struct \"{}\" does not exist",
+                    struct_literal.identifier
+                ));
+                return None;
+            }
+        };
+
+        for (field, _) in &struct_declaration.fields {
+            if !struct_literal
+                .fields
+                .iter()
+                .any(|(identifier, _)| identifier == field)
+            {
+                self.error(format!(
+                    "Missing field {} in {}",
+                    field, struct_literal.identifier
+                ));
+            }
+        }
+
+        let type_parameters = ast::type_parameter_identifiers(&struct_declaration.type_parameters);
+        for ((field, value), field_type) in struct_literal.fields.iter().zip(&field_types) {
+            match struct_declaration.field_type(field) {
+                Some(declared) if !declared.mentions(&type_parameters) => {
+                    if let Err(m) = check_type(
+                        declared,
+                        field_type,
+                        Some(value),
+                        &format!("{}.{}", struct_literal.identifier, field),
+                    ) {
+                        self.error(m);
+                    }
+                }
+                Some(_) => (),
+                None => self.error(format!(
+                    "{} has no field named {}",
+                    struct_literal.identifier, field
+                )),
+            }
+        }
+
+        Some(ValueType::Struct(struct_literal.identifier.to_owned()))
+    }
+
+    fn infer_match_expression(
+        &mut self,
+        match_expression: &ast::MatchExpression,
+    ) -> Option<ValueType> {
+        let subject_type = self.infer(&match_expression.subject);
+
+        // Variables are only assigned after the match if every arm assigns
+        // them
+        let unassigned = self.unassigned();
+        let mut unassigned_after = Vec::new();
+        let mut arm_types: Vec<Option<ValueType>> = Vec::new();
+        for arm in &match_expression.arms {
+            self.mark_unassigned(&unassigned);
+            self.enter_scope();
+            self.bind_pattern(&arm.pattern, subject_type.to_owned(), false);
+            if let Some(guard) = &arm.guard {
+                match self.infer(guard) {
+                    Some(ValueType::Boolean) | None => (),
+                    Some(guard_type) => self.error(format!(
+                        "Match guards must evaluate to a bool, got: {}",
+                        guard_type.as_string()
+                    )),
+                }
+            }
+            arm_types.push(self.infer(&arm.body));
+            self.exit_scope();
+            for variable in self.unassigned() {
+                if !unassigned_after.contains(&variable) {
+                    unassigned_after.push(variable);
+                }
+            }
+        }
+        self.mark_unassigned(&unassigned_after);
+
+        // Arms of different types leave the type of the match unknown
+        match arm_types.split_first() {
+            Some((first, rest)) if rest.iter().all(|arm_type| arm_type == first) => {
+                first.to_owned()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Checks that a value of type `value_type` can be given to a variable
+/// declared with the type `declared`. `value` is the expression the value
+/// came from, used to check integer literals against the range of their type.
+/// Infers the type arguments of a call to a generic function from the
+/// types of its arguments, like `generics::infer_type_arguments` does with
+/// the values given at runtime.
+fn infer_type_arguments(
+    parameter_type: &ast::TypeExpr,
+    argument_type: &ValueType,
+    type_parameters: &[String],
+    type_arguments: &mut HashMap<String, ast::TypeExpr>,
+) {
+    match (parameter_type, argument_type) {
+        // Null says nothing about the type it stands in for
+        (_, ValueType::Null) => (),
+        (
+            ast::TypeExpr::Named {
+                identifier,
+                type_arguments: named_arguments,
+            },
+            _,
+        ) if named_arguments.is_empty() && type_parameters.contains(identifier) => {
+            type_arguments
+                .entry(identifier.to_owned())
+                .or_insert(argument_type.to_owned().as_type_expr());
+        }
+        (ast::TypeExpr::Optional(value_type), _) => {
+            infer_type_arguments(value_type, argument_type, type_parameters, type_arguments)
+        }
+        (ast::TypeExpr::Tuple(element_types), ValueType::Tuple(argument_types))
+            if element_types.len() == argument_types.len() =>
+        {
+            for (element_type, argument_type) in element_types.iter().zip(argument_types) {
+                infer_type_arguments(element_type, argument_type, type_parameters, type_arguments);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn check_type(
+    declared: &ast::TypeExpr,
+    value_type: &Option<ValueType>,
+    value: Option<&ast::Expression>,
+    identifier: &str,
+) -> Result<(), String> {
+    let value_type = match value_type {
+        Some(value_type) => value_type,
+        None => return Ok(()),
+    };
+
+    let mismatch = || {
+        Err(format!(
+            "Incorrect value for {}, expected: {}, got: {}",
+            identifier,
+            declared,
+            value_type.to_owned().as_string()
+        ))
+    };
+
+    match declared {
+        ast::TypeExpr::Named {
+            identifier: symbol,
+            type_arguments,
+        } if type_arguments.is_empty() => match symbol.as_str() {
+            // Decimals without a fractional part can be given to integers
+            symbol if INTEGER_TYPES.contains(&symbol) => {
+                if !is_integer(value_type) && *value_type != ValueType::Decimal {
+                    return mismatch();
+                }
+                match value {
+                    Some(ast::Expression::Integer(integer))
+                        if !integer_literal_fits(&integer.value, symbol) =>
+                    {
+                        Err(format!(
+                            "Integer literal {} for {} does not fit in a {}",
+                            integer.value, identifier, symbol
+                        ))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            symbol if FLOAT_TYPES.contains(&symbol) || symbol == DECIMAL_TYPE => {
+                match is_digit(value_type) {
+                    true => Ok(()),
+                    false => mismatch(),
+                }
+            }
+            symbol => match value_type.to_owned().as_string() == symbol {
+                true => Ok(()),
+                false => mismatch(),
+            },
+        },
+        ast::TypeExpr::Named {
+            identifier: enum_identifier,
+            ..
+        } => match value_type {
+            ValueType::Enum(identifier) | ValueType::Struct(identifier)
+                if identifier == enum_identifier =>
+            {
+                Ok(())
+            }
+            ValueType::Map | ValueType::Set
+                if value_type.to_owned().as_string() == *enum_identifier =>
+            {
+                Ok(())
+            }
+            _ => mismatch(),
+        },
+        ast::TypeExpr::Tuple(declared_types) => match value_type {
+            ValueType::Tuple(element_types) if element_types.len() == declared_types.len() => {
+                let elements: Vec<Option<&ast::Expression>> = match value {
+                    Some(ast::Expression::Tuple(tuple)) => {
+                        tuple.elements.iter().map(Some).collect()
+                    }
+                    _ => vec![None; element_types.len()],
+                };
+                for (index, (declared_type, element_type)) in
+                    declared_types.iter().zip(element_types).enumerate()
+                {
+                    check_type(
+                        declared_type,
+                        &Some(element_type.to_owned()),
+                        elements[index],
+                        &format!("{}.{}", identifier, index),
+                    )?;
+                }
+                Ok(())
+            }
+            _ => mismatch(),
+        },
+        ast::TypeExpr::Array(element_type) => match value_type {
+            ValueType::Array => match value {
+                Some(ast::Expression::Array(array)) => {
+                    for (index, element) in array.elements.iter().enumerate() {
+                        check_type(
+                            element_type,
+                            &literal_type(element),
+                            Some(element),
+                            &format!("{}[{}]", identifier, index),
+                        )?;
+                    }
+                    Ok(())
+                }
+                _ => Ok(()),
+            },
+            _ => mismatch(),
+        },
+        ast::TypeExpr::Optional(inner_type) => match value_type {
+            ValueType::Null => Ok(()),
+            _ => check_type(inner_type, &Some(value_type.to_owned()), value, identifier),
+        },
+        ast::TypeExpr::Function { .. } => match value_type {
+            ValueType::Function => Ok(()),
+            _ => mismatch(),
+        },
+    }
+}
+
+/// The type of the object of `object?.property` once it is known not to be
+/// null. Option and Result objects are unwrapped, so their payload type is
+/// not known.
+fn optional_object_type(object_type: Option<ValueType>) -> Option<ValueType> {
+    match object_type {
+        Some(ValueType::Null) => None,
+        Some(ValueType::Enum(identifier)) if identifier == "Option" || identifier == "Result" => {
+            None
+        }
+        object_type => object_type,
+    }
+}
+
+/// The type of a value once it has been given to a variable declared with
+/// the type `declared`, `None` when it depends on the value.
+fn is_builtin_type(identifier: &str) -> bool {
+    INTEGER_TYPES.contains(&identifier)
+        || FLOAT_TYPES.contains(&identifier)
+        || identifier == DECIMAL_TYPE
+        || BUILTIN_TYPES.contains(&identifier)
+}
+
+/// The name of the type declared by a statement, if it declares one.
+fn type_name(statement: &ast::Statement) -> Option<&str> {
+    match statement {
+        ast::Statement::EnumDeclaration(enum_declaration) => Some(&enum_declaration.identifier),
+        ast::Statement::StructDeclaration(struct_declaration) => {
+            Some(&struct_declaration.identifier)
+        }
+        ast::Statement::TypeAlias(type_alias) => Some(&type_alias.identifier),
+        ast::Statement::NewtypeDeclaration(newtype_declaration) => {
+            Some(&newtype_declaration.identifier)
+        }
+        ast::Statement::Export(export_statement) => type_name(&export_statement.declaration),
+        _ => None,
+    }
+}
+
+fn declared_type(declared: &ast::TypeExpr) -> Option<ValueType> {
+    let value_type = match declared {
+        ast::TypeExpr::Named { identifier, .. } => match identifier.as_str() {
+            "i8" => ValueType::Integer8,
+            "i16" => ValueType::Integer16,
+            "i32" => ValueType::Integer32,
+            "i64" => ValueType::Integer64,
+            "i128" => ValueType::Integer128,
+            "u8" => ValueType::UnsignedInteger8,
+            "u16" => ValueType::UnsignedInteger16,
+            "u32" => ValueType::UnsignedInteger32,
+            "u64" => ValueType::UnsignedInteger64,
+            "u128" => ValueType::UnsignedInteger128,
+            "bigint" => ValueType::BigInt,
+            "decimal" => ValueType::Decimal,
+            "f32" => ValueType::Float32,
+            "f64" => ValueType::Float64,
+            "str" => ValueType::String,
+            "char" => ValueType::Character,
+            "bool" => ValueType::Boolean,
+            "null" => ValueType::Null,
+            "array" => ValueType::Array,
+            "object" => ValueType::Object,
+            "Map" => ValueType::Map,
+            "Set" => ValueType::Set,
+            // User types and generic enums such as `Option<i32>`
+            _ => ValueType::Enum(identifier.to_owned()),
+        },
+        ast::TypeExpr::Tuple(element_types) => {
+            return element_types
+                .iter()
+                .map(declared_type)
+                .collect::<Option<Vec<ValueType>>>()
+                .map(ValueType::Tuple)
+        }
+        ast::TypeExpr::Array(_) => ValueType::Array,
+        ast::TypeExpr::Function { .. } => ValueType::Function,
+        ast::TypeExpr::Optional(_) => return None,
+    };
+
+    Some(value_type)
+}
+
+/// The type of literal array elements, which are not otherwise inferred
+/// one by one.
+fn literal_type(expression: &ast::Expression) -> Option<ValueType> {
+    match expression {
+        ast::Expression::Integer(_) => Some(ValueType::Integer32),
+        ast::Expression::BigInt(_) => Some(ValueType::BigInt),
+        ast::Expression::Decimal(_) => Some(ValueType::Decimal),
+        ast::Expression::Float(_) => Some(ValueType::Float32),
+        ast::Expression::String(_) | ast::Expression::Template(_) => Some(ValueType::String),
+        ast::Expression::Character(_) => Some(ValueType::Character),
+        ast::Expression::Boolean(_) => Some(ValueType::Boolean),
+        ast::Expression::Null(_) => Some(ValueType::Null),
+        _ => None,
+    }
+}
+
+fn integer_literal_fits(literal: &str, symbol: &str) -> bool {
+    match symbol {
+        "i8" => literal.parse::<i8>().is_ok(),
+        "i16" => literal.parse::<i16>().is_ok(),
+        "i32" => literal.parse::<i32>().is_ok(),
+        "i64" => literal.parse::<i64>().is_ok(),
+        "i128" => literal.parse::<i128>().is_ok(),
+        "u8" => literal.parse::<u8>().is_ok(),
+        "u16" => literal.parse::<u16>().is_ok(),
+        "u32" => literal.parse::<u32>().is_ok(),
+        "u64" => literal.parse::<u64>().is_ok(),
+        "u128" => literal.parse::<u128>().is_ok(),
+        _ => true,
+    }
+}
+
+fn is_integer(value_type: &ValueType) -> bool {
+    INTEGER_TYPES.contains(&value_type.to_owned().as_string().as_str())
+}
+
+fn is_digit(value_type: &ValueType) -> bool {
+    let identifier = value_type.to_owned().as_string();
+    is_integer(value_type)
+        || FLOAT_TYPES.contains(&identifier.as_str())
+        || identifier == DECIMAL_TYPE
+}
+
+/// Types that can be compared with `<`, `>`, `<=` and `>=`.
+fn is_ordered(value_type: &ValueType) -> bool {
+    is_digit(value_type)
+        || matches!(
+            value_type,
+            ValueType::String | ValueType::Character | ValueType::Boolean
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::parser::Parser;
+
+    fn check(source: &str) -> Result<(), String> {
+        let program = Parser::new().produce_ast(source)?;
+        let mut environment = Environment::create(None);
+        environment.init_global_scope()?;
+        TypeChecker::new().check_program(&program, &environment)
+    }
+
+    #[test]
+    fn checks_arguments_against_map_and_set_type_arguments() {
+        assert!(check("let m: Map<i32, str> = Map();\nm.insert(1, \"a\")").is_ok());
+        let map = check("let m: Map<i32, str> = Map();\nm.insert(\"x\", 5)").unwrap_err();
+        assert!(map.contains("a key of m, expected: i32, got: str"));
+        assert!(map.contains("a value of m, expected: str, got: i32"));
+        let set = check("let s: Set<str> = Set();\ns.insert(1)").unwrap_err();
+        assert!(set.contains("a value of s, expected: str, got: i32"));
+        assert!(check("let m: Map<i32, str> = Map();\nm.get(\"x\")").is_err());
+    }
+
+    #[test]
+    fn rejects_nullable_values_given_to_non_nullable_types() {
+        let variable = check("let x: i32? = null;\nlet y: i32 = x;").unwrap_err();
+        assert!(variable
+            .contains("Incorrect value for y, expected: i32, got: a value that may be null"));
+        assert!(check("fn f() -> i32? { null }\nlet y: i32 = f();").is_err());
+        assert!(check("fn g(a: i32) -> i32 { a }\nlet x: i32? = 1;\ng(x)").is_err());
+        assert!(check("fn h(a: i32?) -> i32 { a }").is_err());
+        assert!(check("let x: i32? = 1;\nlet mut y = 0;\ny = x;\nlet z: i32 = y;").is_err());
+        assert!(check("let x: i32? = null;\nlet y: i32 = x ?? 0;").is_ok());
+        assert!(check("let x: i32? = null;\nlet y: i32? = x;").is_ok());
+    }
+
+    #[test]
+    fn checks_propagation_against_the_function_return_type() {
+        let returned = check("fn k() -> i32 { let v = Err(\"x\")?; 1 }").unwrap_err();
+        assert!(returned.contains("function returning Option or Result, not i32"));
+        let mismatched = check("fn k() -> Result<i32, str> { let x = None?; Ok(1) }").unwrap_err();
+        assert!(
+            mismatched.contains("on Option values cannot be used in a function returning Result")
+        );
+        assert!(check("fn k() { let v = Err(\"x\")?; 1 }").is_err());
+        assert!(check("fn k() -> i32 { let x = 5?; x }").is_err());
+        assert!(check("fn k() -> Option<i32> { let x = Some(2)?; Some(x) }").is_ok());
+        assert!(
+            check("type R = Result<i32, str>;\nfn k() -> R { let v = Err(\"x\")?; Ok(1) }").is_ok()
+        );
+    }
+
+    #[test]
+    fn infers_the_return_type_of_generic_calls() {
+        let id = "fn id<T>(x: T) -> T { x }\n";
+        let error = check(&format!("{}let y: str = id(1);", id)).unwrap_err();
+        assert!(error.contains("Incorrect value for y, expected: str, got: i32 (in id<i32>)"));
+        assert!(check(&format!("{}let y: i32 = id(1);", id)).is_ok());
+        let pair = "fn p<A, B>(a: A, b: B) -> (B, A) { (b, a) }\n";
+        assert!(check(&format!("{}let t: (str, i32) = p(1, \"a\");", pair)).is_ok());
+        assert!(check(&format!("{}let t: (i32, str) = p(1, \"a\");", pair)).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_types_and_type_arguments_on_primitives() {
+        let unknown = check("let a: Unknown = 1;").unwrap_err();
+        assert_eq!(
+            unknown,
+            "TypeError: Unknown type: Unknown (line 1, column 1)"
+        );
+        let primitive = check("let a: i32<str> = 1;").unwrap_err();
+        assert!(primitive.contains("i32 does not take type arguments, got: i32<str>"));
+        assert!(check("let a: Option<Foo> = None;").is_err());
+        assert!(check("fn f(x: Nope) { x }").is_err());
+        assert!(check("let x = \"5\".parse::<Foo>();").is_err());
+        assert!(check("fn f(p: Point) -> Point { p }\nstruct Point { x: i32 }").is_ok());
+        assert!(check("type A = i32;\nlet m: Map<str, (A, Option<bool>)>? = null;").is_ok());
+    }
+
+    #[test]
+    fn makes_only_bindings_declared_mut_mutable() {
+        let program = Parser::new()
+            .produce_ast("let (mut a, b) = (1, 2);\na = 3;\nb = 4;")
+            .unwrap();
+        let mut environment = Environment::create(None);
+        environment.init_global_scope().unwrap();
+        environment.set_mutability(ast::Mutability::Rust);
+        let error = TypeChecker::new()
+            .check_program(&program, &environment)
+            .unwrap_err();
+        assert!(error.contains("Cannot reassign to immutable variable b"));
+        assert!(!error.contains("variable a"));
+    }
+}
@@ -1,12 +1,41 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
-#[command(version)]
+#[command(version, args_conflicts_with_subcommands = true)]
 /// Run a Jarlang file by giving the entry point,
 /// or start a REPL session.
 ///
 /// Note: When in a REPL session, you can exit the session by typing "exit", Ctrl + D or Ctrl + C
 pub struct CLI {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// The path to your Jarlang entry file
     pub path: Option<String>,
+
+    /// Disallow redeclaring a variable in the same scope instead of shadowing it
+    #[arg(long, global = true)]
+    pub strict_shadowing: bool,
+
+    /// Raise a NotANumber error as soon as a float operation produces NaN
+    #[arg(long, global = true)]
+    pub trap_nan: bool,
+
+    /// Print the value of the last statement once the program has finished
+    #[arg(long, global = true)]
+    pub print_result: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the package described by a jarlang.toml manifest
+    Run {
+        /// The directory holding jarlang.toml, defaults to the current directory
+        directory: Option<String>,
+    },
+    /// Check a Jarlang file for type errors without running it
+    Check {
+        /// The path to the Jarlang file to check
+        path: String,
+    },
 }
@@ -1,36 +1,108 @@
+use std::collections::HashMap;
+use std::fmt;
+
 #[derive(Debug, Clone)]
 pub enum NodeType {
     // Statements
     ProgramStatement,
+    BlockStatement,
     VariableDeclarationStatement,
+    DestructuringDeclarationStatement,
+    EnumDeclarationStatement,
+    ThrowStatement,
+    TryStatement,
+    ImportStatement,
+    ExportStatement,
+    FunctionDeclarationStatement,
+    StructDeclarationStatement,
+    TypeAliasStatement,
+    NewtypeDeclarationStatement,
 
     // Other
     Identifier,
 
     // Literal data types
     IntegerLiteral,
+    BigIntLiteral,
+    DecimalLiteral,
     FloatLiteral,
     CharacterLiteral,
+    BooleanLiteral,
+    NullLiteral,
     StringLiteral,
+    TemplateLiteral,
+    TupleLiteral,
+    ArrayLiteral,
+    ObjectLiteral,
+    StructLiteral,
 
     // Expressions
     BinaryExpression,
     VariableAssignmentExpression,
+    DestructuringAssignmentExpression,
+    MemberExpression,
+    MethodCallExpression,
+    CallExpression,
+    PropagationExpression,
+    EnumVariantExpression,
+    MatchExpression,
+    TypeofExpression,
 }
 
 // Statements
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Statement {
     Program(ProgramStatement),
     VariableDeclaration(VariableDeclarationStatement),
+    DestructuringDeclaration(DestructuringDeclarationStatement),
+    EnumDeclaration(EnumDeclarationStatement),
+    Throw(ThrowStatement),
+    Try(TryStatement),
+    Import(ImportStatement),
+    Export(ExportStatement),
+    FunctionDeclaration(FunctionDeclarationStatement),
+    StructDeclaration(StructDeclarationStatement),
+    TypeAlias(TypeAliasStatement),
+    NewtypeDeclaration(NewtypeDeclarationStatement),
     Expression(Expression),
 }
 
-#[derive(Debug)]
+/// A line and column in the source code, both starting at 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    pub fn create(line: usize, column: usize) -> Self {
+        SourceLocation { line, column }
+    }
+}
+
+/// A statement along with the location of its first token
+#[derive(Debug, Clone)]
+pub struct LocatedStatement {
+    pub location: SourceLocation,
+    pub statement: Statement,
+}
+
+impl LocatedStatement {
+    pub fn create(location: SourceLocation, statement: Statement) -> Self {
+        LocatedStatement {
+            location,
+            statement,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ProgramStatement {
     pub kind: NodeType,
-    pub body: Vec<Statement>,
+    pub body: Vec<LocatedStatement>,
+    /// Set by `#![mutability = "..."]` at the top of the file
+    pub mutability: Option<Mutability>,
 }
 
 impl ProgramStatement {
@@ -38,29 +110,126 @@ impl ProgramStatement {
         ProgramStatement {
             kind: NodeType::ProgramStatement,
             body: Vec::new(),
+            mutability: None,
+        }
+    }
+}
+
+/// Which variables may be reassigned, chosen per file with
+/// `#![mutability = "..."]` or per package in the manifest
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Mutability {
+    /// `let` variables may be reassigned, `const` ones may not
+    #[default]
+    Js,
+    /// `let` variables are immutable unless declared `let mut`
+    Rust,
+}
+
+impl Mutability {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "js" => Ok(Mutability::Js),
+            "rust" => Ok(Mutability::Rust),
+            _ => Err(format!(
+                "Expected mutability to be \"js\" or \"rust\", got: {}",
+                value
+            )),
+        }
+    }
+
+    /// Whether a variable declared with `const`, `let` or `let mut` cannot
+    /// be reassigned.
+    pub fn is_immutable(self, constant: bool, mutable: bool) -> bool {
+        constant || (self == Mutability::Rust && !mutable)
+    }
+}
+
+/// `{ ... }`, statements evaluated in their own scope
+#[derive(Debug, Clone)]
+pub struct BlockStatement {
+    pub kind: NodeType,
+    pub body: Vec<LocatedStatement>,
+}
+
+impl BlockStatement {
+    pub fn create(body: Vec<LocatedStatement>) -> Self {
+        BlockStatement {
+            kind: NodeType::BlockStatement,
+            body,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThrowStatement {
+    pub kind: NodeType,
+    pub value: Expression,
+}
+
+impl ThrowStatement {
+    pub fn create(value: Expression) -> Self {
+        ThrowStatement {
+            kind: NodeType::ThrowStatement,
+            value,
+        }
+    }
+}
+
+/// `try { } catch (e) { } finally { }`, at least one of the catch and
+/// finally clauses is present
+#[derive(Debug, Clone)]
+pub struct TryStatement {
+    pub kind: NodeType,
+    pub body: BlockStatement,
+    pub catch_clause: Option<CatchClause>,
+    pub finally_body: Option<BlockStatement>,
+}
+
+impl TryStatement {
+    pub fn create(
+        body: BlockStatement,
+        catch_clause: Option<CatchClause>,
+        finally_body: Option<BlockStatement>,
+    ) -> Self {
+        TryStatement {
+            kind: NodeType::TryStatement,
+            body,
+            catch_clause,
+            finally_body,
         }
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct CatchClause {
+    pub identifier: Option<String>,
+    pub body: BlockStatement,
+}
+
 #[derive(Debug, Clone)]
 pub struct VariableDeclarationStatement {
     pub kind: NodeType,
     pub constant: bool,
+    /// `let mut`
+    pub mutable: bool,
     pub identifier: String,
     pub value: Option<Expression>,
-    pub value_type: Option<IdentifierExpression>,
+    pub value_type: Option<TypeExpr>,
 }
 
 impl VariableDeclarationStatement {
     pub fn create(
         constant: bool,
+        mutable: bool,
         identifier: String,
         value: Option<Expression>,
-        value_type: Option<IdentifierExpression>,
+        value_type: Option<TypeExpr>,
     ) -> Self {
         VariableDeclarationStatement {
             kind: NodeType::VariableDeclarationStatement,
             constant,
+            mutable,
             identifier,
             value,
             value_type,
@@ -68,17 +237,287 @@ impl VariableDeclarationStatement {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct DestructuringDeclarationStatement {
+    pub kind: NodeType,
+    pub constant: bool,
+    /// `let mut`, makes every binding of the pattern mutable
+    pub mutable: bool,
+    pub pattern: Pattern,
+    pub value: Expression,
+}
+
+impl DestructuringDeclarationStatement {
+    pub fn create(constant: bool, mutable: bool, pattern: Pattern, value: Expression) -> Self {
+        DestructuringDeclarationStatement {
+            kind: NodeType::DestructuringDeclarationStatement,
+            constant,
+            mutable,
+            pattern,
+            value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumDeclarationStatement {
+    pub kind: NodeType,
+    pub identifier: String,
+    pub type_parameters: Vec<String>,
+    pub variants: Vec<EnumVariantDeclaration>,
+}
+
+impl EnumDeclarationStatement {
+    pub fn create(
+        identifier: String,
+        type_parameters: Vec<String>,
+        variants: Vec<EnumVariantDeclaration>,
+    ) -> Self {
+        EnumDeclarationStatement {
+            kind: NodeType::EnumDeclarationStatement,
+            identifier,
+            type_parameters,
+            variants,
+        }
+    }
+
+    pub fn find_variant(&self, identifier: &str) -> Option<&EnumVariantDeclaration> {
+        self.variants
+            .iter()
+            .find(|variant| variant.identifier == identifier)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumVariantDeclaration {
+    pub identifier: String,
+    pub payload: EnumPayload<TypeExpr>,
+}
+
+/// The shape of the data carried by an enum variant. Declarations carry the
+/// payload types, construction expressions carry the payload values.
+#[derive(Debug, Clone)]
+pub enum EnumPayload<T> {
+    Unit,
+    Tuple(Vec<T>),
+    Struct(Vec<(String, T)>),
+}
+
+/// `import { a, b as c } from "./util.jrl";`, `import * as util from
+/// "./util.jrl";` or `import "./util.jrl";`
+#[derive(Debug, Clone)]
+pub struct ImportStatement {
+    pub kind: NodeType,
+    pub specifier: String,
+    pub bindings: ImportBindings,
+}
+
+impl ImportStatement {
+    pub fn create(specifier: String, bindings: ImportBindings) -> Self {
+        ImportStatement {
+            kind: NodeType::ImportStatement,
+            specifier,
+            bindings,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ImportBindings {
+    /// Only evaluates the module
+    None,
+    /// Exported names along with an optional local alias
+    Named(Vec<(String, Option<String>)>),
+    /// Every exported variable as properties of a single object
+    Namespace(String),
+}
+
+/// `export` in front of a variable, destructuring, enum, function or struct
+/// declaration
+#[derive(Debug, Clone)]
+pub struct ExportStatement {
+    pub kind: NodeType,
+    pub declaration: Box<Statement>,
+}
+
+impl ExportStatement {
+    pub fn create(declaration: Statement) -> Self {
+        ExportStatement {
+            kind: NodeType::ExportStatement,
+            declaration: Box::new(declaration),
+        }
+    }
+
+    /// The names declared by the exported declaration.
+    pub fn identifiers(&self) -> Vec<String> {
+        match self.declaration.as_ref() {
+            Statement::VariableDeclaration(variable_declaration) => {
+                vec![variable_declaration.identifier.to_owned()]
+            }
+            Statement::DestructuringDeclaration(destructuring_declaration) => {
+                destructuring_declaration.pattern.binding_identifiers()
+            }
+            Statement::EnumDeclaration(enum_declaration) => {
+                vec![enum_declaration.identifier.to_owned()]
+            }
+            Statement::FunctionDeclaration(function_declaration) => {
+                vec![function_declaration.identifier.to_owned()]
+            }
+            Statement::StructDeclaration(struct_declaration) => {
+                vec![struct_declaration.identifier.to_owned()]
+            }
+            Statement::TypeAlias(type_alias) => vec![type_alias.identifier.to_owned()],
+            Statement::NewtypeDeclaration(newtype_declaration) => {
+                vec![newtype_declaration.identifier.to_owned()]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A type parameter of a function or struct along with the traits its type
+/// arguments must implement, e.g. `T: Display + Eq`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeParameter {
+    pub identifier: String,
+    pub bounds: Vec<String>,
+}
+
+/// The names of the given type parameters.
+pub fn type_parameter_identifiers(type_parameters: &[TypeParameter]) -> Vec<String> {
+    type_parameters
+        .iter()
+        .map(|type_parameter| type_parameter.identifier.to_owned())
+        .collect()
+}
+
+/// `fn first<T>(xs: [T]) -> T? { ... }`, the function returns the value of
+/// the last statement of its body
+#[derive(Debug, Clone)]
+pub struct FunctionDeclarationStatement {
+    pub kind: NodeType,
+    pub identifier: String,
+    pub type_parameters: Vec<TypeParameter>,
+    pub parameters: Vec<(String, TypeExpr)>,
+    pub return_type: Option<TypeExpr>,
+    pub body: BlockStatement,
+}
+
+impl FunctionDeclarationStatement {
+    pub fn create(
+        identifier: String,
+        type_parameters: Vec<TypeParameter>,
+        parameters: Vec<(String, TypeExpr)>,
+        return_type: Option<TypeExpr>,
+        body: BlockStatement,
+    ) -> Self {
+        FunctionDeclarationStatement {
+            kind: NodeType::FunctionDeclarationStatement,
+            identifier,
+            type_parameters,
+            parameters,
+            return_type,
+            body,
+        }
+    }
+}
+
+/// `struct Pair<A, B> { a: A, b: B }`
+#[derive(Debug, Clone)]
+pub struct StructDeclarationStatement {
+    pub kind: NodeType,
+    pub identifier: String,
+    pub type_parameters: Vec<TypeParameter>,
+    pub fields: Vec<(String, TypeExpr)>,
+}
+
+impl StructDeclarationStatement {
+    pub fn create(
+        identifier: String,
+        type_parameters: Vec<TypeParameter>,
+        fields: Vec<(String, TypeExpr)>,
+    ) -> Self {
+        StructDeclarationStatement {
+            kind: NodeType::StructDeclarationStatement,
+            identifier,
+            type_parameters,
+            fields,
+        }
+    }
+
+    pub fn field_type(&self, identifier: &str) -> Option<&TypeExpr> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == identifier)
+            .map(|(_, value_type)| value_type)
+    }
+}
+
+/// `type Id = u64;`, another name for an existing type
+#[derive(Debug, Clone)]
+pub struct TypeAliasStatement {
+    pub kind: NodeType,
+    pub identifier: String,
+    pub value_type: TypeExpr,
+}
+
+impl TypeAliasStatement {
+    pub fn create(identifier: String, value_type: TypeExpr) -> Self {
+        TypeAliasStatement {
+            kind: NodeType::TypeAliasStatement,
+            identifier,
+            value_type,
+        }
+    }
+}
+
+/// `newtype Meters(f64);`, a type that wraps another type but is distinct
+/// from it
+#[derive(Debug, Clone)]
+pub struct NewtypeDeclarationStatement {
+    pub kind: NodeType,
+    pub identifier: String,
+    pub value_type: TypeExpr,
+}
+
+impl NewtypeDeclarationStatement {
+    pub fn create(identifier: String, value_type: TypeExpr) -> Self {
+        NewtypeDeclarationStatement {
+            kind: NodeType::NewtypeDeclarationStatement,
+            identifier,
+            value_type,
+        }
+    }
+}
+
 // Expressions
 
 #[derive(Debug, Clone)]
 pub enum Expression {
     Binary(Box<BinaryExpression>),
     VariableAssignment(Box<VariableAssignmentExpression>),
+    DestructuringAssignment(Box<DestructuringAssignmentExpression>),
+    Member(Box<MemberExpression>),
+    MethodCall(Box<MethodCallExpression>),
+    Call(Box<CallExpression>),
+    Propagation(Box<PropagationExpression>),
+    EnumVariant(Box<EnumVariantExpression>),
+    Match(Box<MatchExpression>),
+    Typeof(TypeofExpression),
     Identifier(IdentifierExpression),
     Integer(IntegerLiteral),
+    BigInt(BigIntLiteral),
+    Decimal(DecimalLiteral),
     Float(FloatLiteral),
     Character(CharacterLiteral),
+    Boolean(BooleanLiteral),
+    Null(NullLiteral),
     String(StringLiteral),
+    Template(TemplateLiteral),
+    Tuple(TupleLiteral),
+    Array(ArrayLiteral),
+    Object(ObjectLiteral),
+    Struct(StructLiteral),
 }
 
 #[derive(Debug, Clone)]
@@ -117,6 +556,410 @@ impl VariableAssignmentExpression {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct DestructuringAssignmentExpression {
+    pub kind: NodeType,
+    pub pattern: Pattern,
+    pub value: Expression,
+}
+
+impl DestructuringAssignmentExpression {
+    pub fn create(pattern: Pattern, value: Expression) -> Self {
+        DestructuringAssignmentExpression {
+            kind: NodeType::DestructuringAssignmentExpression,
+            pattern,
+            value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MemberExpression {
+    pub kind: NodeType,
+    pub object: Expression,
+    pub property: MemberProperty,
+    /// `object?.property`, null when the object is null
+    pub optional: bool,
+}
+
+impl MemberExpression {
+    pub fn create(object: Expression, property: MemberProperty, optional: bool) -> Self {
+        MemberExpression {
+            kind: NodeType::MemberExpression,
+            object,
+            property,
+            optional,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MemberProperty {
+    /// `tuple.0`
+    Index(usize),
+    /// `object.name`
+    Name(String),
+}
+
+/// `object.method(arguments)` or `object.method::<T>(arguments)`
+#[derive(Debug, Clone)]
+pub struct MethodCallExpression {
+    pub kind: NodeType,
+    pub object: Expression,
+    pub method: String,
+    pub type_arguments: Vec<TypeExpr>,
+    pub arguments: Vec<Expression>,
+    /// `object?.method()`, null when the object is null
+    pub optional: bool,
+}
+
+impl MethodCallExpression {
+    pub fn create(
+        object: Expression,
+        method: String,
+        type_arguments: Vec<TypeExpr>,
+        arguments: Vec<Expression>,
+        optional: bool,
+    ) -> Self {
+        MethodCallExpression {
+            kind: NodeType::MethodCallExpression,
+            object,
+            method,
+            type_arguments,
+            arguments,
+            optional,
+        }
+    }
+}
+
+/// `callee(arguments)` or `callee::<T>(arguments)`
+#[derive(Debug, Clone)]
+pub struct CallExpression {
+    pub kind: NodeType,
+    pub callee: Expression,
+    pub type_arguments: Vec<TypeExpr>,
+    pub arguments: Vec<Expression>,
+}
+
+impl CallExpression {
+    pub fn create(
+        callee: Expression,
+        type_arguments: Vec<TypeExpr>,
+        arguments: Vec<Expression>,
+    ) -> Self {
+        CallExpression {
+            kind: NodeType::CallExpression,
+            callee,
+            type_arguments,
+            arguments,
+        }
+    }
+}
+
+/// `value?`, unwraps an `Ok`/`Some` or propagates an `Err`/`None`
+#[derive(Debug, Clone)]
+pub struct PropagationExpression {
+    pub kind: NodeType,
+    pub value: Expression,
+}
+
+impl PropagationExpression {
+    pub fn create(value: Expression) -> Self {
+        PropagationExpression {
+            kind: NodeType::PropagationExpression,
+            value,
+        }
+    }
+}
+
+/// `typeof x`, the declared and runtime types of a variable
+#[derive(Debug, Clone)]
+pub struct TypeofExpression {
+    pub kind: NodeType,
+    pub identifier: String,
+}
+
+impl TypeofExpression {
+    pub fn create(identifier: String) -> Self {
+        TypeofExpression {
+            kind: NodeType::TypeofExpression,
+            identifier,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumVariantExpression {
+    pub kind: NodeType,
+    pub enum_identifier: String,
+    pub variant_identifier: String,
+    pub payload: EnumPayload<Expression>,
+}
+
+impl EnumVariantExpression {
+    pub fn create(
+        enum_identifier: String,
+        variant_identifier: String,
+        payload: EnumPayload<Expression>,
+    ) -> Self {
+        EnumVariantExpression {
+            kind: NodeType::EnumVariantExpression,
+            enum_identifier,
+            variant_identifier,
+            payload,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchExpression {
+    pub kind: NodeType,
+    pub subject: Expression,
+    pub arms: Vec<MatchArm>,
+}
+
+impl MatchExpression {
+    pub fn create(subject: Expression, arms: Vec<MatchArm>) -> Self {
+        MatchExpression {
+            kind: NodeType::MatchExpression,
+            subject,
+            arms,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: Expression,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// `_`
+    Wildcard,
+    /// `name` or `name: i32`, binds the matched value to a new variable
+    Binding {
+        identifier: String,
+        value_type: Option<TypeExpr>,
+        /// `mut name`, makes just this variable mutable
+        mutable: bool,
+    },
+    /// `1`, `2.5`, `true`, `null`
+    Literal(Expression),
+    /// `1..10` or `1..=10`
+    Range {
+        start: Expression,
+        end: Expression,
+        inclusive: bool,
+    },
+    /// `(a, b)`
+    Tuple(Vec<Pattern>),
+    /// `[first, ..rest]`, `[.., last]`
+    Array {
+        head: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+        tail: Vec<Pattern>,
+    },
+    /// `{ x, y: other }`
+    Object(Vec<(String, Pattern)>),
+    /// `Shape::Circle(r)`, `Shape::Rect { w, h }`, `Shape::Empty`
+    EnumVariant {
+        enum_identifier: String,
+        variant_identifier: String,
+        payload: EnumPayload<Pattern>,
+    },
+}
+
+impl Pattern {
+    /// The names of every variable bound by the pattern.
+    pub fn binding_identifiers(&self) -> Vec<String> {
+        self.bindings()
+            .into_iter()
+            .filter_map(|pattern| match pattern {
+                Pattern::Binding { identifier, .. } => Some(identifier.to_owned()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether any variable bound by the pattern is declared `mut`.
+    pub fn has_mutable_binding(&self) -> bool {
+        self.bindings()
+            .into_iter()
+            .any(|pattern| matches!(pattern, Pattern::Binding { mutable: true, .. }))
+    }
+
+    /// Every binding in the pattern, e.g. `a` and `mut b` in `(a, [mut b])`.
+    fn bindings(&self) -> Vec<&Pattern> {
+        match self {
+            Pattern::Binding { .. } => vec![self],
+            Pattern::Tuple(patterns) => patterns.iter().flat_map(Pattern::bindings).collect(),
+            Pattern::Array { head, rest, tail } => head
+                .iter()
+                .chain(rest.iter().map(|rest| rest.as_ref()))
+                .chain(tail.iter())
+                .flat_map(Pattern::bindings)
+                .collect(),
+            Pattern::Object(fields) => fields
+                .iter()
+                .flat_map(|(_, pattern)| pattern.bindings())
+                .collect(),
+            Pattern::EnumVariant { payload, .. } => match payload {
+                EnumPayload::Unit => Vec::new(),
+                EnumPayload::Tuple(patterns) => {
+                    patterns.iter().flat_map(Pattern::bindings).collect()
+                }
+                EnumPayload::Struct(fields) => fields
+                    .iter()
+                    .flat_map(|(_, pattern)| pattern.bindings())
+                    .collect(),
+            },
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Range { .. } => Vec::new(),
+        }
+    }
+
+    /// Whether the pattern matches every value it could be given.
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Pattern::Wildcard | Pattern::Binding { .. } => true,
+            Pattern::Tuple(patterns) => patterns.iter().all(Pattern::is_irrefutable),
+            _ => false,
+        }
+    }
+}
+
+/// A type annotation such as `i32`, `[str]`, `(i32, char)`, `u8?`,
+/// `fn(i32) -> str` or `Result<i32, str>`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeExpr {
+    /// Primitive types, user types and type parameters
+    Named {
+        identifier: String,
+        type_arguments: Vec<TypeExpr>,
+    },
+    Array(Box<TypeExpr>),
+    Tuple(Vec<TypeExpr>),
+    /// `T?`, either a `T` or null
+    Optional(Box<TypeExpr>),
+    Function {
+        parameters: Vec<TypeExpr>,
+        return_type: Box<TypeExpr>,
+    },
+}
+
+impl TypeExpr {
+    /// A named type without type arguments.
+    pub fn named(identifier: &str) -> Self {
+        TypeExpr::Named {
+            identifier: identifier.to_string(),
+            type_arguments: Vec::new(),
+        }
+    }
+
+    /// Whether any of the given type parameters appear in the type.
+    pub fn mentions(&self, type_parameters: &[String]) -> bool {
+        match self {
+            TypeExpr::Named {
+                identifier,
+                type_arguments,
+            } => {
+                type_parameters.contains(identifier)
+                    || type_arguments
+                        .iter()
+                        .any(|type_argument| type_argument.mentions(type_parameters))
+            }
+            TypeExpr::Array(element_type) | TypeExpr::Optional(element_type) => {
+                element_type.mentions(type_parameters)
+            }
+            TypeExpr::Tuple(element_types) => element_types
+                .iter()
+                .any(|element_type| element_type.mentions(type_parameters)),
+            TypeExpr::Function {
+                parameters,
+                return_type,
+            } => {
+                return_type.mentions(type_parameters)
+                    || parameters
+                        .iter()
+                        .any(|parameter| parameter.mentions(type_parameters))
+            }
+        }
+    }
+
+    /// Replaces the type parameters found in `type_arguments` with their
+    /// type argument.
+    pub fn substitute(&self, type_arguments: &HashMap<String, TypeExpr>) -> TypeExpr {
+        let substitute_all = |types: &[TypeExpr]| {
+            types
+                .iter()
+                .map(|value_type| value_type.substitute(type_arguments))
+                .collect()
+        };
+
+        match self {
+            TypeExpr::Named {
+                identifier,
+                type_arguments: named_arguments,
+            } => match type_arguments.get(identifier) {
+                Some(type_argument) if named_arguments.is_empty() => type_argument.to_owned(),
+                _ => TypeExpr::Named {
+                    identifier: identifier.to_owned(),
+                    type_arguments: substitute_all(named_arguments),
+                },
+            },
+            TypeExpr::Array(element_type) => {
+                TypeExpr::Array(Box::new(element_type.substitute(type_arguments)))
+            }
+            TypeExpr::Tuple(element_types) => TypeExpr::Tuple(substitute_all(element_types)),
+            TypeExpr::Optional(value_type) => {
+                TypeExpr::Optional(Box::new(value_type.substitute(type_arguments)))
+            }
+            TypeExpr::Function {
+                parameters,
+                return_type,
+            } => TypeExpr::Function {
+                parameters: substitute_all(parameters),
+                return_type: Box::new(return_type.substitute(type_arguments)),
+            },
+        }
+    }
+}
+
+/// Types are written the same way they are annotated.
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeExpr::Named {
+                identifier,
+                type_arguments,
+            } => match type_arguments.is_empty() {
+                true => write!(f, "{}", identifier),
+                false => write!(f, "{}<{}>", identifier, display_types(type_arguments)),
+            },
+            TypeExpr::Array(element_type) => write!(f, "[{}]", element_type),
+            TypeExpr::Tuple(element_types) => match element_types.len() {
+                1 => write!(f, "({},)", element_types[0]),
+                _ => write!(f, "({})", display_types(element_types)),
+            },
+            TypeExpr::Optional(value_type) => write!(f, "{}?", value_type),
+            TypeExpr::Function {
+                parameters,
+                return_type,
+            } => write!(f, "fn({}) -> {}", display_types(parameters), return_type),
+        }
+    }
+}
+
+fn display_types(types: &[TypeExpr]) -> String {
+    types
+        .iter()
+        .map(TypeExpr::to_string)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 #[derive(Debug, Clone)]
 pub struct IdentifierExpression {
     pub kind: NodeType,
@@ -147,6 +990,40 @@ impl IntegerLiteral {
     }
 }
 
+/// An integer literal with the `n` suffix, e.g. `10n`, holding the digits
+/// without the suffix.
+#[derive(Debug, Clone)]
+pub struct BigIntLiteral {
+    pub kind: NodeType,
+    pub value: String,
+}
+
+impl BigIntLiteral {
+    pub fn create(value: String) -> Self {
+        BigIntLiteral {
+            kind: NodeType::BigIntLiteral,
+            value,
+        }
+    }
+}
+
+/// A number literal with the `d` suffix, e.g. `12.50d`, holding the digits
+/// without the suffix.
+#[derive(Debug, Clone)]
+pub struct DecimalLiteral {
+    pub kind: NodeType,
+    pub value: String,
+}
+
+impl DecimalLiteral {
+    pub fn create(value: String) -> Self {
+        DecimalLiteral {
+            kind: NodeType::DecimalLiteral,
+            value,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FloatLiteral {
     pub kind: NodeType,
@@ -168,8 +1045,163 @@ pub struct CharacterLiteral {
     pub value: char,
 }
 
+impl CharacterLiteral {
+    pub fn create(value: char) -> Self {
+        CharacterLiteral {
+            kind: NodeType::CharacterLiteral,
+            value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BooleanLiteral {
+    pub kind: NodeType,
+    pub value: bool,
+}
+
+impl BooleanLiteral {
+    pub fn create(value: bool) -> Self {
+        BooleanLiteral {
+            kind: NodeType::BooleanLiteral,
+            value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NullLiteral {
+    pub kind: NodeType,
+}
+
+impl NullLiteral {
+    pub fn create() -> Self {
+        NullLiteral {
+            kind: NodeType::NullLiteral,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StringLiteral {
     pub kind: NodeType,
     pub value: String,
 }
+
+impl StringLiteral {
+    pub fn create(value: String) -> Self {
+        StringLiteral {
+            kind: NodeType::StringLiteral,
+            value,
+        }
+    }
+}
+
+/// `` `Hello ${name}, total ${total:>8.2}` ``
+#[derive(Debug, Clone)]
+pub struct TemplateLiteral {
+    pub kind: NodeType,
+    pub parts: Vec<TemplatePart>,
+}
+
+impl TemplateLiteral {
+    pub fn create(parts: Vec<TemplatePart>) -> Self {
+        TemplateLiteral {
+            kind: NodeType::TemplateLiteral,
+            parts,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TemplatePart {
+    Text(String),
+    Interpolation {
+        expression: Expression,
+        format: Option<FormatSpec>,
+    },
+}
+
+/// A Rust-like format specifier, `[[fill]align][+][0][width][.precision]`
+#[derive(Debug, Clone)]
+pub struct FormatSpec {
+    pub fill: char,
+    pub align: Option<FormatAlignment>,
+    pub sign_plus: bool,
+    pub zero_pad: bool,
+    pub width: Option<usize>,
+    pub precision: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatAlignment {
+    /// `<`
+    Left,
+    /// `^`
+    Center,
+    /// `>`
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct TupleLiteral {
+    pub kind: NodeType,
+    pub elements: Vec<Expression>,
+}
+
+impl TupleLiteral {
+    pub fn create(elements: Vec<Expression>) -> Self {
+        TupleLiteral {
+            kind: NodeType::TupleLiteral,
+            elements,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayLiteral {
+    pub kind: NodeType,
+    pub elements: Vec<Expression>,
+}
+
+impl ArrayLiteral {
+    pub fn create(elements: Vec<Expression>) -> Self {
+        ArrayLiteral {
+            kind: NodeType::ArrayLiteral,
+            elements,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectLiteral {
+    pub kind: NodeType,
+    pub properties: Vec<(String, Expression)>,
+}
+
+impl ObjectLiteral {
+    pub fn create(properties: Vec<(String, Expression)>) -> Self {
+        ObjectLiteral {
+            kind: NodeType::ObjectLiteral,
+            properties,
+        }
+    }
+}
+
+/// `Pair { a: 1, b: "one" }`
+#[derive(Debug, Clone)]
+pub struct StructLiteral {
+    pub kind: NodeType,
+    pub identifier: String,
+    pub fields: Vec<(String, Expression)>,
+}
+
+impl StructLiteral {
+    pub fn create(identifier: String, fields: Vec<(String, Expression)>) -> Self {
+        StructLiteral {
+            kind: NodeType::StructLiteral,
+            identifier,
+            fields,
+        }
+    }
+}
@@ -1,22 +1,54 @@
+use super::ast;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     // Complex
     Identifier,
     BinaryOperator,
+    StringLiteral,
+    CharacterLiteral,
+    /// `true` or `false`
+    BooleanLiteral,
+    NullLiteral,
     EOF,
 
+    // Template strings, `text ${expression:format} text`
+    TemplateStart,
+    TemplateText,
+    InterpolationStart,
+    FormatSpecifier,
+    InterpolationEnd,
+    TemplateEnd,
+
     // Symbols
     Equals,
     OpenParen,
     ClosedParen,
+    OpenBrace,
+    ClosedBrace,
+    OpenBracket,
+    ClosedBracket,
+    Comma,
+    Dot,
     Colon,
+    DoubleColon,
     SemiColon,
-    DoubleQuote,
-    SingleQuote,
+    FatArrow,
+    Arrow,
+    QuestionMark,
+    /// `?.`, optional chaining
+    QuestionDot,
+    /// `??`, null coalescing
+    DoubleQuestionMark,
+    DoubleDot,
+    DoubleDotEquals,
+    /// `#!`, starts an inner attribute such as `#![mutability = "rust"]`
+    HashBang,
 
     // Comparison operators
     Equality,
     Inequality,
+    ComparisonOperator,
 
     // Unary operators
     Increment,
@@ -26,6 +58,29 @@ pub enum TokenType {
     // Variable assignment
     Let,
     Const,
+    Mut,
+
+    // Enums and pattern matching
+    Enum,
+    Match,
+    If,
+
+    // Exception handling
+    Try,
+    Catch,
+    Finally,
+    Throw,
+
+    // Modules
+    Import,
+    Export,
+
+    // Functions and user types
+    Fn,
+    Struct,
+    Type,
+    Newtype,
+    Typeof,
 
     // Variable literal types
     Number,
@@ -38,23 +93,54 @@ pub enum TokenType {
 pub struct Token {
     pub token_type: TokenType,
     pub value: String,
+    pub location: ast::SourceLocation,
 }
 
 impl Token {
     fn create(token_type: TokenType, value: String) -> Self {
-        Token { token_type, value }
+        Token {
+            token_type,
+            value,
+            location: ast::SourceLocation::create(1, 1),
+        }
     }
 }
 
-pub const KEYWORDS: [&'static str; 5] = ["let", "const", "bool", "str", "char"];
+pub const KEYWORDS: [&'static str; 25] = [
+    "let", "const", "mut", "bool", "str", "char", "enum", "match", "if", "try", "catch", "finally",
+    "throw", "import", "export", "fn", "struct", "type", "newtype", "typeof", "true", "false",
+    "null", "inf", "nan",
+];
 
 fn create_reserved(keyword: &str) -> Result<Token, String> {
     match keyword {
         "let" => Ok(Token::create(TokenType::Let, String::from(keyword))),
         "const" => Ok(Token::create(TokenType::Const, String::from(keyword))),
+        "mut" => Ok(Token::create(TokenType::Mut, String::from(keyword))),
         "bool" => Ok(Token::create(TokenType::Boolean, String::from(keyword))),
         "str" => Ok(Token::create(TokenType::String, String::from(keyword))),
         "char" => Ok(Token::create(TokenType::Character, String::from(keyword))),
+        "try" => Ok(Token::create(TokenType::Try, String::from(keyword))),
+        "catch" => Ok(Token::create(TokenType::Catch, String::from(keyword))),
+        "finally" => Ok(Token::create(TokenType::Finally, String::from(keyword))),
+        "throw" => Ok(Token::create(TokenType::Throw, String::from(keyword))),
+        "import" => Ok(Token::create(TokenType::Import, String::from(keyword))),
+        "export" => Ok(Token::create(TokenType::Export, String::from(keyword))),
+        "enum" => Ok(Token::create(TokenType::Enum, String::from(keyword))),
+        "match" => Ok(Token::create(TokenType::Match, String::from(keyword))),
+        "if" => Ok(Token::create(TokenType::If, String::from(keyword))),
+        "fn" => Ok(Token::create(TokenType::Fn, String::from(keyword))),
+        "struct" => Ok(Token::create(TokenType::Struct, String::from(keyword))),
+        "type" => Ok(Token::create(TokenType::Type, String::from(keyword))),
+        "newtype" => Ok(Token::create(TokenType::Newtype, String::from(keyword))),
+        "typeof" => Ok(Token::create(TokenType::Typeof, String::from(keyword))),
+        "true" | "false" => Ok(Token::create(
+            TokenType::BooleanLiteral,
+            String::from(keyword),
+        )),
+        "null" => Ok(Token::create(TokenType::NullLiteral, String::from(keyword))),
+        // Float special values are number literals, parsed by Rust as floats
+        "inf" | "nan" => Ok(Token::create(TokenType::Number, String::from(keyword))),
         // "int" => Ok(Token::create(TokenType::Integer, String::from(keyword))),
         // "float" => Ok(Token::create(TokenType::Float, String::from(keyword))),
         _ => return Err(format!("Unknown keyword: {}", keyword).to_string()),
@@ -75,12 +161,186 @@ fn is_skipabble(val: &char) -> bool {
     }
 }
 
+/// Reads a single, possibly escaped, character from a string or character
+/// literal.
+fn read_string_character(src_chars: &mut Vec<char>) -> Result<char, String> {
+    let character = src_chars.remove(0);
+    if character != '\\' {
+        return Ok(character);
+    }
+
+    if src_chars.is_empty() {
+        return Err("Unterminated escape sequence".to_string());
+    }
+
+    match src_chars.remove(0) {
+        'n' => Ok('\n'),
+        't' => Ok('\t'),
+        'r' => Ok('\r'),
+        '0' => Ok('\0'),
+        '\\' => Ok('\\'),
+        '"' => Ok('"'),
+        '\'' => Ok('\''),
+        '`' => Ok('`'),
+        '$' => Ok('$'),
+        escaped => Err(format!("Unknown escape sequence: \\{}", escaped)),
+    }
+}
+
+/// Reads the rest of a template string following its opening backtick. Each
+/// interpolated expression is tokenised on its own between an
+/// InterpolationStart and InterpolationEnd token.
+fn tokenise_template(src_chars: &mut Vec<char>, tokens: &mut Vec<Token>) -> Result<(), String> {
+    let mut text = String::new();
+
+    loop {
+        match src_chars.first() {
+            Some('`') => break,
+            Some('$') if src_chars.get(1) == Some(&'{') => {
+                if !text.is_empty() {
+                    tokens.push(Token::create(TokenType::TemplateText, text.to_owned()));
+                    text.clear();
+                }
+                src_chars.drain(..2);
+
+                let interpolation = read_interpolation(src_chars)?;
+                let (expression, format_specifier) = split_format_specifier(&interpolation);
+                if expression.trim().is_empty() {
+                    return Err("Empty interpolation found in template string".to_string());
+                }
+
+                tokens.push(Token::create(
+                    TokenType::InterpolationStart,
+                    "${".to_string(),
+                ));
+                let mut expression_tokens = tokenise(&expression)?;
+                expression_tokens.pop();
+                tokens.append(&mut expression_tokens);
+                if let Some(format_specifier) = format_specifier {
+                    tokens.push(Token::create(TokenType::FormatSpecifier, format_specifier));
+                }
+                tokens.push(Token::create(TokenType::InterpolationEnd, "}".to_string()));
+            }
+            Some(_) => text.push(read_string_character(src_chars)?),
+            None => return Err("Unterminated template string".to_string()),
+        }
+    }
+
+    if !text.is_empty() {
+        tokens.push(Token::create(TokenType::TemplateText, text));
+    }
+    tokens.push(Token::create(
+        TokenType::TemplateEnd,
+        src_chars.remove(0).to_string(),
+    ));
+
+    Ok(())
+}
+
+/// Reads the source of an interpolation up to its closing brace, skipping
+/// over braces nested inside the expression or its string literals.
+fn read_interpolation(src_chars: &mut Vec<char>) -> Result<String, String> {
+    let mut source = String::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+
+    while !src_chars.is_empty() {
+        let character = src_chars.remove(0);
+        match (quote, character) {
+            (Some(_), '\\') if !src_chars.is_empty() => {
+                source.push(character);
+                source.push(src_chars.remove(0));
+                continue;
+            }
+            (Some(open), _) if open == character => quote = None,
+            (Some(_), _) => (),
+            (None, '"' | '\'' | '`') => quote = Some(character),
+            (None, '{' | '(' | '[') => depth += 1,
+            (None, '}') if depth == 0 => return Ok(source),
+            (None, '}' | ')' | ']') => depth -= 1,
+            (None, _) => (),
+        }
+        source.push(character);
+    }
+
+    Err("Unterminated interpolation in template string".to_string())
+}
+
+/// Splits `value:>8.2` into the expression and its format specifier. The
+/// specifier follows the last `:` outside of any brackets that is not part
+/// of a `::` path.
+fn split_format_specifier(interpolation: &str) -> (String, Option<String>) {
+    let characters: Vec<char> = interpolation.chars().collect();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut split: Option<usize> = None;
+
+    let mut index = 0;
+    while index < characters.len() {
+        let character = characters[index];
+        match (quote, character) {
+            (Some(_), '\\') => index += 1,
+            (Some(open), _) if open == character => quote = None,
+            (Some(_), _) => (),
+            (None, '"' | '\'' | '`') => quote = Some(character),
+            (None, '{' | '(' | '[') => depth += 1,
+            (None, '}' | ')' | ']') => depth -= 1,
+            (None, ':') if depth == 0 && characters.get(index + 1) == Some(&':') => index += 1,
+            (None, ':') if depth == 0 => split = Some(index),
+            (None, _) => (),
+        }
+        index += 1;
+    }
+
+    match split {
+        Some(split) => (
+            characters[..split].iter().collect(),
+            Some(characters[split + 1..].iter().collect()),
+        ),
+        None => (interpolation.to_string(), None),
+    }
+}
+
+/// The location of every character in the source, followed by the location
+/// just past its end.
+fn locate_characters(src_chars: &[char]) -> Vec<ast::SourceLocation> {
+    let mut locations = Vec::with_capacity(src_chars.len() + 1);
+    let mut location = ast::SourceLocation::create(1, 1);
+
+    for character in src_chars {
+        locations.push(location);
+        match character {
+            '\n' => location = ast::SourceLocation::create(location.line + 1, 1),
+            _ => location.column += 1,
+        }
+    }
+    locations.push(location);
+
+    locations
+}
+
+fn locate_tokens(tokens: &mut [Token], location: ast::SourceLocation) {
+    for token in tokens {
+        token.location = location;
+    }
+}
+
 pub fn tokenise(source_code: &str) -> Result<Vec<Token>, String> {
     let mut tokens = Vec::<Token>::new();
 
     let mut src_chars: Vec<char> = source_code.chars().collect();
+    let locations = locate_characters(&src_chars);
+
+    // Tokens are given the location of the character they started at once
+    // they have been read
+    let mut located = 0;
+    let mut token_start = locations[0];
 
     while src_chars.len() > 0 {
+        locate_tokens(&mut tokens[located..], token_start);
+        located = tokens.len();
+        token_start = locations[locations.len() - 1 - src_chars.len()];
+
         match src_chars[0] {
             '(' => tokens.push(Token::create(
                 TokenType::OpenParen,
@@ -90,54 +350,176 @@ pub fn tokenise(source_code: &str) -> Result<Vec<Token>, String> {
                 TokenType::ClosedParen,
                 src_chars.remove(0).to_string(),
             )),
-            ';' => tokens.push(Token::create(
-                TokenType::SemiColon,
+            '{' => tokens.push(Token::create(
+                TokenType::OpenBrace,
                 src_chars.remove(0).to_string(),
             )),
-            ':' => tokens.push(Token::create(
-                TokenType::Colon,
+            '}' => tokens.push(Token::create(
+                TokenType::ClosedBrace,
                 src_chars.remove(0).to_string(),
             )),
-            '"' => tokens.push(Token::create(
-                TokenType::DoubleQuote,
+            ',' => tokens.push(Token::create(
+                TokenType::Comma,
                 src_chars.remove(0).to_string(),
             )),
-            '\'' => tokens.push(Token::create(
-                TokenType::SingleQuote,
+            ';' => tokens.push(Token::create(
+                TokenType::SemiColon,
                 src_chars.remove(0).to_string(),
             )),
-            '!' => {
-                if src_chars[1] == '=' && src_chars[2] == '=' {
-                    for _ in 1..=3 {
-                        src_chars.remove(0);
-                    }
-                    tokens.push(Token::create(TokenType::Inequality, "!==".to_string()))
+            ':' => {
+                if src_chars.get(1) == Some(&':') {
+                    src_chars.drain(..2);
+                    tokens.push(Token::create(TokenType::DoubleColon, "::".to_string()));
+                    continue;
                 }
                 tokens.push(Token::create(
-                    TokenType::Not,
+                    TokenType::Colon,
                     src_chars.remove(0).to_string(),
                 ))
             }
-            '=' => {
-                if src_chars[1] == '=' && src_chars[2] == '=' {
-                    for _ in 1..=3 {
-                        src_chars.remove(0);
+            '?' => match src_chars.get(1) {
+                Some('.') => {
+                    src_chars.drain(..2);
+                    tokens.push(Token::create(TokenType::QuestionDot, "?.".to_string()));
+                }
+                Some('?') => {
+                    src_chars.drain(..2);
+                    tokens.push(Token::create(
+                        TokenType::DoubleQuestionMark,
+                        "??".to_string(),
+                    ));
+                }
+                _ => tokens.push(Token::create(
+                    TokenType::QuestionMark,
+                    src_chars.remove(0).to_string(),
+                )),
+            },
+            '[' => tokens.push(Token::create(
+                TokenType::OpenBracket,
+                src_chars.remove(0).to_string(),
+            )),
+            ']' => tokens.push(Token::create(
+                TokenType::ClosedBracket,
+                src_chars.remove(0).to_string(),
+            )),
+            '`' => {
+                tokens.push(Token::create(
+                    TokenType::TemplateStart,
+                    src_chars.remove(0).to_string(),
+                ));
+                tokenise_template(&mut src_chars, &mut tokens)?;
+            }
+            '"' => {
+                src_chars.remove(0);
+                let mut buff = String::from("");
+                loop {
+                    match src_chars.first() {
+                        Some('"') => break,
+                        Some(_) => buff.push(read_string_character(&mut src_chars)?),
+                        None => return Err("Unterminated string literal".to_string()),
                     }
-                    tokens.push(Token::create(TokenType::Equality, "===".to_string()))
                 }
+                src_chars.remove(0);
+                tokens.push(Token::create(TokenType::StringLiteral, buff))
+            }
+            '\'' => {
+                src_chars.remove(0);
+                let character = match src_chars.first() {
+                    Some('\'') | None => {
+                        return Err("Character literals must contain a character".to_string())
+                    }
+                    Some(_) => read_string_character(&mut src_chars)?,
+                };
+                if src_chars.first() != Some(&'\'') {
+                    return Err("Character literals must contain exactly one character".to_string());
+                }
+                src_chars.remove(0);
                 tokens.push(Token::create(
+                    TokenType::CharacterLiteral,
+                    character.to_string(),
+                ))
+            }
+            '.' => {
+                if src_chars.get(1) != Some(&'.') {
+                    tokens.push(Token::create(
+                        TokenType::Dot,
+                        src_chars.remove(0).to_string(),
+                    ));
+                    continue;
+                }
+                if src_chars.get(2) == Some(&'=') {
+                    src_chars.drain(..3);
+                    tokens.push(Token::create(TokenType::DoubleDotEquals, "..=".to_string()));
+                    continue;
+                }
+                src_chars.drain(..2);
+                tokens.push(Token::create(TokenType::DoubleDot, "..".to_string()))
+            }
+            '#' if src_chars.get(1) == Some(&'!') => {
+                src_chars.drain(..2);
+                tokens.push(Token::create(TokenType::HashBang, "#!".to_string()))
+            }
+            '!' => match (src_chars.get(1), src_chars.get(2)) {
+                (Some('='), Some('=')) => {
+                    src_chars.drain(..3);
+                    tokens.push(Token::create(TokenType::Inequality, "!==".to_string()))
+                }
+                (Some('='), _) => {
+                    src_chars.drain(..2);
+                    tokens.push(Token::create(TokenType::Inequality, "!=".to_string()))
+                }
+                _ => tokens.push(Token::create(
+                    TokenType::Not,
+                    src_chars.remove(0).to_string(),
+                )),
+            },
+            '=' => match (src_chars.get(1), src_chars.get(2)) {
+                (Some('='), Some('=')) => {
+                    src_chars.drain(..3);
+                    tokens.push(Token::create(TokenType::Equality, "===".to_string()))
+                }
+                (Some('='), _) => {
+                    src_chars.drain(..2);
+                    tokens.push(Token::create(TokenType::Equality, "==".to_string()))
+                }
+                (Some('>'), _) => {
+                    src_chars.drain(..2);
+                    tokens.push(Token::create(TokenType::FatArrow, "=>".to_string()))
+                }
+                _ => tokens.push(Token::create(
                     TokenType::Equals,
                     src_chars.remove(0).to_string(),
-                ))
+                )),
+            },
+            '<' if src_chars.get(1) == Some(&'<') => {
+                src_chars.drain(..2);
+                tokens.push(Token::create(TokenType::BinaryOperator, "<<".to_string()))
+            }
+            // `>>` closing nested type arguments, e.g. `Option<Option<i32>>`,
+            // is split back up by the parser
+            '>' if src_chars.get(1) == Some(&'>') => {
+                src_chars.drain(..2);
+                tokens.push(Token::create(TokenType::BinaryOperator, ">>".to_string()))
+            }
+            '<' | '>' => {
+                let mut operator = src_chars.remove(0).to_string();
+                if src_chars.first() == Some(&'=') {
+                    operator.push(src_chars.remove(0));
+                }
+                tokens.push(Token::create(TokenType::ComparisonOperator, operator))
             }
-            '+' | '-' => match src_chars[1] {
-                '+' => {
+            '-' if src_chars.get(1) == Some(&'>') => {
+                src_chars.drain(..2);
+                tokens.push(Token::create(TokenType::Arrow, "->".to_string()))
+            }
+            '+' | '-' => match src_chars.get(1) {
+                Some('+') => {
                     for _ in 1..=2 {
                         src_chars.remove(0);
                     }
                     tokens.push(Token::create(TokenType::Increment, "++".to_string()))
                 }
-                '-' => {
+                Some('-') => {
                     for _ in 1..=2 {
                         src_chars.remove(0);
                     }
@@ -148,7 +530,11 @@ pub fn tokenise(source_code: &str) -> Result<Vec<Token>, String> {
                     src_chars.remove(0).to_string(),
                 )),
             },
-            '*' | '/' | '%' => tokens.push(Token::create(
+            '*' if src_chars.get(1) == Some(&'*') => {
+                src_chars.drain(..2);
+                tokens.push(Token::create(TokenType::BinaryOperator, "**".to_string()))
+            }
+            '*' | '/' | '%' | '&' | '|' | '^' => tokens.push(Token::create(
                 TokenType::BinaryOperator,
                 src_chars.remove(0).to_string(),
             )),
@@ -159,7 +545,9 @@ pub fn tokenise(source_code: &str) -> Result<Vec<Token>, String> {
                     let mut buff = String::from("");
 
                     let mut allow_dot = true;
-                    while src_chars.len() > 0 && (is_integer(&src_chars[0]) || src_chars[0] == '.')
+                    while src_chars.len() > 0
+                        && (is_integer(&src_chars[0])
+                            || (src_chars[0] == '.' && src_chars.get(1).is_some_and(is_integer)))
                     {
                         if src_chars[0] == '.' && allow_dot == false {
                             return Err("Unexpected \".\" found in number".to_string());
@@ -169,14 +557,30 @@ pub fn tokenise(source_code: &str) -> Result<Vec<Token>, String> {
                         buff.push(src_chars.remove(0));
                     }
 
+                    // Integers ending in `n` are bigints, e.g. `10n`, and
+                    // numbers ending in `d` are decimals, e.g. `12.50d`
+                    let suffix = match src_chars.first() {
+                        Some('n') if allow_dot => true,
+                        Some('d') => true,
+                        _ => false,
+                    };
+                    let ends_number = !src_chars
+                        .get(1)
+                        .is_some_and(|next| next.is_ascii_alphanumeric() || *next == '_');
+                    if suffix && ends_number {
+                        buff.push(src_chars.remove(0));
+                    }
+
                     tokens.push(Token::create(TokenType::Number, buff));
                     continue;
                 }
 
-                if src_chars[0].is_ascii_alphabetic() {
+                if src_chars[0].is_ascii_alphabetic() || src_chars[0] == '_' {
                     let mut buff = String::from("");
                     while src_chars.len() > 0
-                        && (src_chars[0].is_ascii_alphabetic() || is_integer(&src_chars[0]))
+                        && (src_chars[0].is_ascii_alphabetic()
+                            || src_chars[0] == '_'
+                            || is_integer(&src_chars[0]))
                     {
                         buff.push(src_chars.remove(0))
                     }
@@ -206,7 +610,10 @@ pub fn tokenise(source_code: &str) -> Result<Vec<Token>, String> {
         };
     }
 
-    tokens.push(Token::create(TokenType::EOF, "EndOfFile".to_string()));
+    locate_tokens(&mut tokens[located..], token_start);
+    let mut eof = Token::create(TokenType::EOF, "EndOfFile".to_string());
+    eof.location = locations[locations.len() - 1];
+    tokens.push(eof);
 
     Ok(tokens)
 }
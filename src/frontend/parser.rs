@@ -1,14 +1,21 @@
 use super::ast;
 use super::lexer;
 use super::lexer::TokenType;
+use super::prelude;
 
 pub struct Parser {
     tokens: Vec<lexer::Token>,
+    // Set while parsing a match subject, where `{` opens the match arms
+    // rather than a struct-like enum variant payload
+    no_struct_payload: bool,
 }
 
 impl Parser {
     pub fn new() -> Self {
-        Parser { tokens: Vec::new() }
+        Parser {
+            tokens: Vec::new(),
+            no_struct_payload: false,
+        }
     }
 
     fn not_eof(&mut self) -> bool {
@@ -22,6 +29,13 @@ impl Parser {
         self.tokens[0].clone()
     }
 
+    fn peek(&mut self, offset: usize) -> lexer::Token {
+        match self.tokens.get(offset) {
+            Some(token) => token.clone(),
+            None => self.tokens[self.tokens.len() - 1].clone(),
+        }
+    }
+
     fn eat(&mut self) -> lexer::Token {
         self.tokens.remove(0)
     }
@@ -34,6 +48,21 @@ impl Parser {
         Err(token.token_type)
     }
 
+    /// Eats the name of a declaration. `expected` describes it for the
+    /// error, e.g. "identifier name following the fn keyword".
+    fn expect_identifier(&mut self, expected: &str) -> Result<String, String> {
+        let token = self.eat();
+        match token.token_type {
+            TokenType::Identifier => Ok(token.value),
+            // The float literals `inf` and `nan` are lexed as numbers
+            TokenType::Number if matches!(token.value.as_str(), "inf" | "nan") => Err(format!(
+                "{} is a reserved float literal and cannot be used as a name",
+                token.value
+            )),
+            token_type => Err(format!("Expected {}, got: {:#?}", expected, token_type)),
+        }
+    }
+
     // fn expect_many(&mut self, types: Vec<TokenType>) -> Result<lexer::Token, lexer::TokenType> {
     //     let token = self.eat();
     //     if types.contains(&token.token_type) {
@@ -49,22 +78,91 @@ impl Parser {
         }
         let mut program = ast::ProgramStatement::create();
 
+        while self.at().token_type == TokenType::HashBang {
+            self.parse_inner_attribute(&mut program)?;
+        }
+
         while self.not_eof() == true {
-            match self.parse_statement() {
-                Ok(statement) => program.body.push(statement),
-                Err(m) => return Err(m),
-            }
+            program.body.push(self.parse_located_statement()?);
         }
 
         Ok(ast::Statement::Program(program))
     }
 
+    /// Parses `#![name = "value"]`, which configures the whole file.
+    fn parse_inner_attribute(&mut self, program: &mut ast::ProgramStatement) -> Result<(), String> {
+        self.eat();
+        if let Err(token_type) = self.expect(TokenType::OpenBracket) {
+            return Err(format!(
+                "Expected open bracket following #!, got: {:#?}",
+                token_type
+            ));
+        }
+        let name = match self.expect(TokenType::Identifier) {
+            Ok(token) => token.value,
+            Err(token_type) => {
+                return Err(format!(
+                    "Expected attribute name following #![, got: {:#?}",
+                    token_type
+                ))
+            }
+        };
+        if let Err(token_type) = self.expect(TokenType::Equals) {
+            return Err(format!(
+                "Expected equals token following attribute name, got: {:#?}",
+                token_type
+            ));
+        }
+        let value = match self.expect(TokenType::StringLiteral) {
+            Ok(token) => token.value,
+            Err(token_type) => {
+                return Err(format!(
+                    "Expected string value for attribute {}, got: {:#?}",
+                    name, token_type
+                ))
+            }
+        };
+        self.expect_closing(TokenType::ClosedBracket)?;
+
+        match name.as_str() {
+            "mutability" => program.mutability = Some(ast::Mutability::parse(&value)?),
+            _ => return Err(format!("Unknown attribute: {}", name)),
+        }
+        Ok(())
+    }
+
+    fn parse_located_statement(&mut self) -> Result<ast::LocatedStatement, String> {
+        let location = self.at().location;
+        Ok(ast::LocatedStatement::create(
+            location,
+            self.parse_statement()?,
+        ))
+    }
+
     fn parse_statement(&mut self) -> Result<ast::Statement, String> {
         match self.at().token_type {
+            TokenType::HashBang => return Err(
+                "Inner attributes such as #![mutability = \"rust\"] must come before any statement"
+                    .to_string(),
+            ),
+            TokenType::Try => return self.parse_try_statement(),
+            TokenType::Throw => return self.parse_throw_statement(),
             TokenType::Let | TokenType::Const => match self.parse_variable_declaration() {
                 Ok(statement) => return Ok(statement),
                 Err(m) => return Err(m),
             },
+            TokenType::Enum => return self.parse_enum_declaration(),
+            TokenType::Fn => return self.parse_function_declaration(),
+            TokenType::Struct => return self.parse_struct_declaration(),
+            TokenType::Type => return self.parse_type_alias(),
+            TokenType::Newtype => return self.parse_newtype_declaration(),
+            TokenType::Import => return self.parse_import_statement(),
+            TokenType::Export => return self.parse_export_statement(),
+            TokenType::OpenParen | TokenType::OpenBracket | TokenType::OpenBrace => {
+                if let Some(expression) = self.try_parse_destructuring_assignment()? {
+                    return Ok(ast::Statement::Expression(expression));
+                }
+            }
             _ => (),
         }
         match self.parse_expression() {
@@ -73,46 +171,220 @@ impl Parser {
         }
     }
 
-    fn parse_variable_declaration(&mut self) -> Result<ast::Statement, String> {
-        let is_constant = self.eat().token_type == TokenType::Const;
-        let identifier = match self.expect(TokenType::Identifier) {
+    fn parse_block_statement(&mut self, context: &str) -> Result<ast::BlockStatement, String> {
+        if let Err(token_type) = self.expect(TokenType::OpenBrace) {
+            return Err(format!(
+                "Expected opening brace to start the {} block, got: {:#?}",
+                context, token_type
+            ));
+        }
+
+        let mut body = Vec::new();
+        while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+            body.push(self.parse_located_statement()?);
+        }
+
+        if let Err(token_type) = self.expect(TokenType::ClosedBrace) {
+            return Err(format!(
+                "Expected closing brace at the end of the {} block, got: {:#?}",
+                context, token_type
+            ));
+        }
+
+        Ok(ast::BlockStatement::create(body))
+    }
+
+    fn parse_try_statement(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+        let body = self.parse_block_statement("try")?;
+
+        let mut catch_clause: Option<ast::CatchClause> = None;
+        if self.at().token_type == TokenType::Catch {
+            self.eat();
+            let mut identifier: Option<String> = None;
+            if self.at().token_type == TokenType::OpenParen {
+                self.eat();
+                identifier = Some(self.expect_identifier("identifier for the caught error")?);
+                self.expect_closing(TokenType::ClosedParen)?;
+            }
+            catch_clause = Some(ast::CatchClause {
+                identifier,
+                body: self.parse_block_statement("catch")?,
+            });
+        }
+
+        let mut finally_body: Option<ast::BlockStatement> = None;
+        if self.at().token_type == TokenType::Finally {
+            self.eat();
+            finally_body = Some(self.parse_block_statement("finally")?);
+        }
+
+        if catch_clause.is_none() && finally_body.is_none() {
+            return Err("Expected catch or finally following the try block".to_string());
+        }
+
+        Ok(ast::Statement::Try(ast::TryStatement::create(
+            body,
+            catch_clause,
+            finally_body,
+        )))
+    }
+
+    fn parse_throw_statement(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+        let value = self.parse_expression()?;
+
+        if let Err(token_type) = self.expect(TokenType::SemiColon) {
+            return Err(format!(
+                "Throw statements must end with a Semicolon, got: {:#?}",
+                token_type
+            ));
+        }
+
+        Ok(ast::Statement::Throw(ast::ThrowStatement::create(value)))
+    }
+
+    fn parse_import_statement(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+
+        let bindings = match self.at().token_type {
+            TokenType::StringLiteral => ast::ImportBindings::None,
+            TokenType::OpenBrace => {
+                self.eat();
+                let mut names: Vec<(String, Option<String>)> = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+                    let name = self.expect_import_identifier("import name")?;
+                    let mut alias: Option<String> = None;
+                    if self.at().token_type == TokenType::Identifier && self.at().value == "as" {
+                        self.eat();
+                        alias = Some(self.expect_import_identifier("alias following as")?);
+                    }
+                    names.push((name, alias));
+                    if self.at().token_type != TokenType::ClosedBrace {
+                        self.expect_comma("imported names")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedBrace)?;
+                self.expect_from()?;
+                ast::ImportBindings::Named(names)
+            }
+            TokenType::BinaryOperator if self.at().value == "*" => {
+                self.eat();
+                match self.eat() {
+                    token if token.token_type == TokenType::Identifier && token.value == "as" => (),
+                    token => {
+                        return Err(format!(
+                            "Expected as following import *, got: {:#?}",
+                            token.token_type
+                        ))
+                    }
+                }
+                let namespace = self.expect_import_identifier("namespace name")?;
+                self.expect_from()?;
+                ast::ImportBindings::Namespace(namespace)
+            }
+            token_type => {
+                return Err(format!(
+                    "Expected {{, * or a module path following import, got: {:#?}",
+                    token_type
+                ))
+            }
+        };
+
+        let specifier = match self.expect(TokenType::StringLiteral) {
             Ok(token) => token.value,
             Err(token_type) => {
                 return Err(format!(
-                    "Expected identifier name following the let | const keywords, got: {:#?}",
+                    "Expected module path string in import statement, got: {:#?}",
                     token_type
                 ))
             }
         };
 
-        let mut value_type: Option<ast::IdentifierExpression> = None;
+        if let Err(token_type) = self.expect(TokenType::SemiColon) {
+            return Err(format!(
+                "Import statements must end with a Semicolon, got: {:#?}",
+                token_type
+            ));
+        }
+
+        Ok(ast::Statement::Import(ast::ImportStatement::create(
+            specifier, bindings,
+        )))
+    }
 
-        match self.at().token_type {
-            TokenType::SemiColon => {
-                self.eat();
-                if is_constant {
-                    return Err("Must assign a value to a constant expression".to_string());
-                }
+    fn expect_import_identifier(&mut self, context: &str) -> Result<String, String> {
+        self.expect_identifier(&format!("{} in import statement", context))
+    }
 
-                return Ok(ast::Statement::VariableDeclaration(
-                    ast::VariableDeclarationStatement::create(false, identifier, None, None),
-                ));
+    fn expect_from(&mut self) -> Result<(), String> {
+        match self.eat() {
+            token if token.token_type == TokenType::Identifier && token.value == "from" => Ok(()),
+            token => Err(format!(
+                "Expected from in import statement, got: {:#?}",
+                token.token_type
+            )),
+        }
+    }
+
+    fn parse_export_statement(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+
+        let declaration = match self.at().token_type {
+            TokenType::Let | TokenType::Const => self.parse_variable_declaration()?,
+            TokenType::Enum => self.parse_enum_declaration()?,
+            TokenType::Fn => self.parse_function_declaration()?,
+            TokenType::Struct => self.parse_struct_declaration()?,
+            TokenType::Type => self.parse_type_alias()?,
+            TokenType::Newtype => self.parse_newtype_declaration()?,
+            token_type => {
+                return Err(format!(
+                    "Expected let, const, enum, fn, struct, type or newtype following export, got: {:#?}",
+                    token_type
+                ))
             }
-            TokenType::Colon => {
-                self.eat();
+        };
 
-                value_type = match self.expect(TokenType::Identifier) {
-                    Ok(token) => match token.value.as_str() {
-                        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64" | "u128" | "f32" | "f64" | "char" | "str" | "bool" => Some(ast::IdentifierExpression::create(token.value)),
-                        _ => return Err(format!("Unexpected value type found during variable declaration parsing, got: {}", token.value))
-                    },
-                    Err(token_type) => {
-                        return Err(format!("Expected identifier name following the Semicolon (:) token, got: {:#?}", token_type));
-                    }
-                };
+        Ok(ast::Statement::Export(ast::ExportStatement::create(
+            declaration,
+        )))
+    }
+
+    fn parse_variable_declaration(&mut self) -> Result<ast::Statement, String> {
+        let pattern_start = match self.peek(1).token_type {
+            TokenType::Mut => self.peek(2),
+            _ => self.peek(1),
+        };
+        match pattern_start.token_type {
+            TokenType::OpenParen | TokenType::OpenBracket | TokenType::OpenBrace => {
+                return self.parse_destructuring_declaration()
             }
             _ => (),
-        };
+        }
+
+        let (is_constant, is_mutable) = self.parse_declaration_keywords()?;
+        let identifier =
+            self.expect_identifier("identifier name following the let | const keywords")?;
+
+        let mut value_type: Option<ast::TypeExpr> = None;
+        if self.at().token_type == TokenType::Colon {
+            self.eat();
+            value_type = Some(self.parse_type_expr()?);
+        }
+
+        // `let x;` and `let x: T;` are given a value later on
+        if self.at().token_type == TokenType::SemiColon {
+            self.eat();
+            if is_constant {
+                return Err("Must assign a value to a constant expression".to_string());
+            }
+
+            return Ok(ast::Statement::VariableDeclaration(
+                ast::VariableDeclarationStatement::create(
+                    false, is_mutable, identifier, None, value_type,
+                ),
+            ));
+        }
 
         match self.expect(TokenType::Equals) {
             Ok(_) => (),
@@ -142,6 +414,7 @@ impl Parser {
         Ok(ast::Statement::VariableDeclaration(
             ast::VariableDeclarationStatement::create(
                 is_constant,
+                is_mutable,
                 identifier,
                 Some(value),
                 value_type,
@@ -149,120 +422,1796 @@ impl Parser {
         ))
     }
 
-    fn parse_expression(&mut self) -> Result<ast::Expression, String> {
-        match self.parse_assignment_expression() {
-            Ok(expression) => Ok(expression),
-            Err(m) => return Err(m),
+    /// Parses a type annotation:
+    ///
+    /// ```text
+    /// type := base "?"*
+    /// base := "[" type "]"
+    ///       | "(" (type ("," type)* ","?)? ")"
+    ///       | "fn" "(" (type ("," type)*)? ")" "->" type
+    ///       | name ("<" type ("," type)* ">")?
+    /// ```
+    fn parse_type_expr(&mut self) -> Result<ast::TypeExpr, String> {
+        let mut type_expr = self.parse_base_type_expr()?;
+        while self.at().token_type == TokenType::QuestionMark {
+            self.eat();
+            type_expr = ast::TypeExpr::Optional(Box::new(type_expr));
         }
+        Ok(type_expr)
     }
 
-    fn parse_assignment_expression(&mut self) -> Result<ast::Expression, String> {
-        let left = match self.parse_additive_expression() {
-            Ok(expression) => expression,
-            Err(m) => return Err(m),
-        };
-
-        if self.at().token_type == TokenType::Equals {
-            self.eat();
-            let value = match self.parse_additive_expression() {
-                Ok(expression) => expression,
-                Err(m) => return Err(m),
-            };
-            match self.expect(TokenType::SemiColon) {
-                Ok(_) => (),
-                Err(token_type) => {
+    fn parse_base_type_expr(&mut self) -> Result<ast::TypeExpr, String> {
+        let token = self.eat();
+        match token.token_type {
+            TokenType::OpenBracket => {
+                let element_type = self.parse_type_expr()?;
+                self.expect_closing(TokenType::ClosedBracket)?;
+                Ok(ast::TypeExpr::Array(Box::new(element_type)))
+            }
+            TokenType::OpenParen => {
+                let mut element_types = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedParen {
+                    element_types.push(self.parse_type_expr()?);
+                    if self.at().token_type != TokenType::ClosedParen {
+                        self.expect_comma("tuple types")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedParen)?;
+                Ok(ast::TypeExpr::Tuple(element_types))
+            }
+            TokenType::Fn => {
+                if let Err(token_type) = self.expect(TokenType::OpenParen) {
                     return Err(format!(
-                        "Assignment expressions must end with a Semicolon, got: {:#?}",
+                        "Expected open parenthesis following fn in function type, got: {:#?}",
                         token_type
-                    )
-                    .to_string())
+                    ));
+                }
+                let mut parameters = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedParen {
+                    parameters.push(self.parse_type_expr()?);
+                    if self.at().token_type != TokenType::ClosedParen {
+                        self.expect_comma("function parameter types")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedParen)?;
+                if let Err(token_type) = self.expect(TokenType::Arrow) {
+                    return Err(format!(
+                        "Expected -> following the parameters of a function type, got: {:#?}",
+                        token_type
+                    ));
                 }
+                Ok(ast::TypeExpr::Function {
+                    parameters,
+                    return_type: Box::new(self.parse_type_expr()?),
+                })
             }
+            TokenType::Identifier => {
+                let type_arguments = match self.at() {
+                    next if next.token_type == TokenType::ComparisonOperator
+                        && next.value == "<" =>
+                    {
+                        self.eat();
+                        self.parse_type_arguments(&token.value)?
+                    }
+                    _ => Vec::new(),
+                };
 
-            return Ok(ast::Expression::VariableAssignment(Box::new(
-                ast::VariableAssignmentExpression::create(left, value),
-            )));
-        }
+                let expected = match token.value.as_str() {
+                    "Option" => Some(1),
+                    "Result" => Some(2),
+                    _ => None,
+                };
+                match expected {
+                    None => (),
+                    Some(_) if type_arguments.is_empty() => {
+                        return Err(format!(
+                            "Expected type arguments following {}, e.g. {}",
+                            token.value,
+                            match token.value.as_str() {
+                                "Option" => "Option<i32>",
+                                _ => "Result<i32, str>",
+                            }
+                        ))
+                    }
+                    Some(expected) if type_arguments.len() != expected => {
+                        return Err(format!(
+                            "{} expects {} type argument(s), got {}",
+                            token.value,
+                            expected,
+                            type_arguments.len()
+                        ))
+                    }
+                    Some(_) => (),
+                }
 
-        Ok(left)
+                Ok(ast::TypeExpr::Named {
+                    identifier: token.value,
+                    type_arguments,
+                })
+            }
+            // `str`, `char`, `bool` and `null` are lexed as keywords
+            TokenType::String
+            | TokenType::Character
+            | TokenType::Boolean
+            | TokenType::NullLiteral => Ok(ast::TypeExpr::named(&token.value)),
+            token_type => Err(format!(
+                "Expected a type following the Colon (:) token, got: {:#?}",
+                token_type
+            )),
+        }
     }
 
-    fn parse_additive_expression(&mut self) -> Result<ast::Expression, String> {
-        let mut left = match self.parse_multiplicative_expression() {
-            Ok(expression) => expression,
-            Err(m) => return Err(m),
-        };
+    /// Parses `let`, `let mut` or `const`, returning whether the variable is
+    /// constant and whether it was declared `mut`.
+    fn parse_declaration_keywords(&mut self) -> Result<(bool, bool), String> {
+        let is_constant = self.eat().token_type == TokenType::Const;
+        if self.at().token_type != TokenType::Mut {
+            return Ok((is_constant, false));
+        }
+        self.eat();
+        if is_constant {
+            return Err("Constants cannot be declared mut, use let mut instead".to_string());
+        }
+        Ok((false, true))
+    }
 
-        while self.at().value == "+" || self.at().value == "-" {
-            let operator = self.eat().value;
-            let right = match self.parse_multiplicative_expression() {
-                Ok(expression) => expression,
-                Err(m) => return Err(m),
-            };
-            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
-                left, right, operator,
-            )))
+    fn parse_destructuring_declaration(&mut self) -> Result<ast::Statement, String> {
+        let (is_constant, is_mutable) = self.parse_declaration_keywords()?;
+        let pattern = self.parse_pattern(true)?;
+        if is_constant && pattern.has_mutable_binding() {
+            return Err("Constants cannot be declared mut, use let instead".to_string());
         }
 
-        Ok(left)
-    }
+        if let Err(token_type) = self.expect(TokenType::Equals) {
+            return Err(format!(
+                "Expected equals token following destructuring pattern in variable declaration, got: {:#?}",
+                token_type
+            ));
+        }
 
-    fn parse_multiplicative_expression(&mut self) -> Result<ast::Expression, String> {
-        let mut left = match self.parse_primary_expression() {
-            Ok(expression) => expression,
-            Err(m) => return Err(m),
-        };
+        let value = self.parse_expression()?;
 
-        while self.at().value == "*" || self.at().value == "/" || self.at().value == "%" {
-            let operator = self.eat().value;
-            let right = match self.parse_primary_expression() {
-                Ok(expression) => expression,
-                Err(m) => return Err(m),
-            };
-            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
-                left, right, operator,
-            )))
+        if let Err(token_type) = self.expect(TokenType::SemiColon) {
+            return Err(format!(
+                "Variable declaration statements must end with a Semicolon, got: {:#?}",
+                token_type
+            ));
         }
 
-        Ok(left)
+        Ok(ast::Statement::DestructuringDeclaration(
+            ast::DestructuringDeclarationStatement::create(is_constant, is_mutable, pattern, value),
+        ))
     }
 
-    fn parse_primary_expression(&mut self) -> Result<ast::Expression, String> {
-        let token_type = self.at().token_type;
+    /// Statements starting with `(`, `[` or `{` may either be an expression or
+    /// the pattern of a destructuring assignment, which can only be told apart
+    /// once the `=` following the pattern has been found.
+    fn try_parse_destructuring_assignment(&mut self) -> Result<Option<ast::Expression>, String> {
+        let tokens = self.tokens.clone();
 
-        match token_type {
-            lexer::TokenType::Identifier => Ok(ast::Expression::Identifier(
-                ast::IdentifierExpression::create(self.eat().value),
-            )),
-            lexer::TokenType::Number => {
-                let token = self.eat();
-                if token.value.contains('.') {
-                    return Ok(ast::Expression::Float(ast::FloatLiteral::create(
-                        token.value,
-                    )));
+        match self.parse_pattern(false) {
+            Ok(pattern) if self.at().token_type == TokenType::Equals => {
+                if pattern.has_mutable_binding() {
+                    return Err(
+                        "Destructuring assignments cannot declare variables mut, use let instead"
+                            .to_string(),
+                    );
                 }
-                Ok(ast::Expression::Integer(ast::IntegerLiteral::create(
-                    token.value,
-                )))
-            }
-            lexer::TokenType::OpenParen => {
                 self.eat();
-                let value = match self.parse_expression() {
-                    Ok(expression) => expression,
-                    Err(m) => return Err(m),
-                };
-                match self.expect(TokenType::ClosedParen) {
-                    Ok(_) => Ok(value),
-                    Err(token_type) => Err(format!(
-                        "Expected closing parenthesis, got: {:#?}",
+                let value = self.parse_coalescing_expression()?;
+                if let Err(token_type) = self.expect(TokenType::SemiColon) {
+                    return Err(format!(
+                        "Assignment expressions must end with a Semicolon, got: {:#?}",
                         token_type
-                    )),
+                    ));
                 }
+                Ok(Some(ast::Expression::DestructuringAssignment(Box::new(
+                    ast::DestructuringAssignmentExpression::create(pattern, value),
+                ))))
             }
-            _ => Err(format!(
-                "Unexpected token found during parsing: {:#?}",
-                self.at()
-            )),
+            _ => {
+                self.tokens = tokens;
+                Ok(None)
+            }
+        }
+    }
+
+    fn parse_enum_declaration(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+        let identifier = self.expect_identifier("identifier name following the enum keyword")?;
+
+        let context = format!("enum {}", identifier);
+        let mut type_parameters = Vec::new();
+        for type_parameter in self.parse_type_parameters(&context)? {
+            if !type_parameter.bounds.is_empty() {
+                return Err(format!(
+                    "Type parameter {} of {} cannot have trait bounds",
+                    type_parameter.identifier, context
+                ));
+            }
+            type_parameters.push(type_parameter.identifier);
+        }
+
+        if let Err(token_type) = self.expect(TokenType::OpenBrace) {
+            return Err(format!(
+                "Expected opening brace following enum {}, got: {:#?}",
+                identifier, token_type
+            ));
         }
+
+        let variants = self.parse_enum_variants(&identifier)?;
+
+        Ok(ast::Statement::EnumDeclaration(
+            ast::EnumDeclarationStatement::create(identifier, type_parameters, variants),
+        ))
+    }
+
+    fn parse_enum_variants(
+        &mut self,
+        identifier: &str,
+    ) -> Result<Vec<ast::EnumVariantDeclaration>, String> {
+        let mut variants: Vec<ast::EnumVariantDeclaration> = Vec::new();
+
+        while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+            let variant_identifier = match self.expect(TokenType::Identifier) {
+                Ok(token) => token.value,
+                Err(token_type) => {
+                    return Err(format!(
+                        "Expected variant name in enum {}, got: {:#?}",
+                        identifier, token_type
+                    ))
+                }
+            };
+
+            if variants
+                .iter()
+                .any(|variant| variant.identifier == variant_identifier)
+            {
+                return Err(format!(
+                    "Variant {} has already been declared in enum {}",
+                    variant_identifier, identifier
+                ));
+            }
+
+            let payload = match self.at().token_type {
+                TokenType::OpenParen => {
+                    self.eat();
+                    let mut value_types = Vec::new();
+                    while self.not_eof() && self.at().token_type != TokenType::ClosedParen {
+                        value_types.push(self.parse_type_expr()?);
+                        if self.at().token_type != TokenType::ClosedParen {
+                            self.expect_comma("enum variant payload")?;
+                        }
+                    }
+                    self.expect_closing(TokenType::ClosedParen)?;
+                    ast::EnumPayload::Tuple(value_types)
+                }
+                TokenType::OpenBrace => {
+                    self.eat();
+                    let mut fields = Vec::new();
+                    while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+                        let field = match self.expect(TokenType::Identifier) {
+                            Ok(token) => token.value,
+                            Err(token_type) => {
+                                return Err(format!(
+                                    "Expected field name in variant {}::{}, got: {:#?}",
+                                    identifier, variant_identifier, token_type
+                                ))
+                            }
+                        };
+                        if let Err(token_type) = self.expect(TokenType::Colon) {
+                            return Err(format!(
+                                "Expected colon following field {} in variant {}::{}, got: {:#?}",
+                                field, identifier, variant_identifier, token_type
+                            ));
+                        }
+                        fields.push((field, self.parse_type_expr()?));
+                        if self.at().token_type != TokenType::ClosedBrace {
+                            self.expect_comma("enum variant fields")?;
+                        }
+                    }
+                    self.expect_closing(TokenType::ClosedBrace)?;
+                    ast::EnumPayload::Struct(fields)
+                }
+                _ => ast::EnumPayload::Unit,
+            };
+
+            variants.push(ast::EnumVariantDeclaration {
+                identifier: variant_identifier,
+                payload,
+            });
+
+            if self.at().token_type != TokenType::ClosedBrace {
+                self.expect_comma("enum variants")?;
+            }
+        }
+
+        if let Err(token_type) = self.expect(TokenType::ClosedBrace) {
+            return Err(format!(
+                "Expected closing brace at the end of enum {}, got: {:#?}",
+                identifier, token_type
+            ));
+        }
+
+        Ok(variants)
+    }
+
+    fn parse_function_declaration(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+        let identifier = self.expect_identifier("identifier name following the fn keyword")?;
+
+        let type_parameters = self.parse_type_parameters(&identifier)?;
+
+        if let Err(token_type) = self.expect(TokenType::OpenParen) {
+            return Err(format!(
+                "Expected opening parenthesis for the parameters of {}, got: {:#?}",
+                identifier, token_type
+            ));
+        }
+        let parameters =
+            self.parse_typed_fields(TokenType::ClosedParen, "parameter", &identifier)?;
+
+        let mut return_type = None;
+        if self.at().token_type == TokenType::Arrow {
+            self.eat();
+            return_type = Some(self.parse_type_expr()?);
+        }
+
+        let body = self.parse_block_statement("function")?;
+
+        Ok(ast::Statement::FunctionDeclaration(
+            ast::FunctionDeclarationStatement::create(
+                identifier,
+                type_parameters,
+                parameters,
+                return_type,
+                body,
+            ),
+        ))
+    }
+
+    fn parse_struct_declaration(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+        let identifier = self.expect_identifier("identifier name following the struct keyword")?;
+
+        let type_parameters = self.parse_type_parameters(&identifier)?;
+
+        if let Err(token_type) = self.expect(TokenType::OpenBrace) {
+            return Err(format!(
+                "Expected opening brace following struct {}, got: {:#?}",
+                identifier, token_type
+            ));
+        }
+        let fields = self.parse_typed_fields(TokenType::ClosedBrace, "field", &identifier)?;
+
+        Ok(ast::Statement::StructDeclaration(
+            ast::StructDeclarationStatement::create(identifier, type_parameters, fields),
+        ))
+    }
+
+    fn parse_type_alias(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+        let identifier = self.expect_identifier("identifier name following the type keyword")?;
+
+        if let Err(token_type) = self.expect(TokenType::Equals) {
+            return Err(format!(
+                "Expected equals following type {}, got: {:#?}",
+                identifier, token_type
+            ));
+        }
+        let value_type = self.parse_type_expr()?;
+
+        if let Err(token_type) = self.expect(TokenType::SemiColon) {
+            return Err(format!(
+                "Type aliases must end with a Semicolon, got: {:#?}",
+                token_type
+            ));
+        }
+
+        Ok(ast::Statement::TypeAlias(ast::TypeAliasStatement::create(
+            identifier, value_type,
+        )))
+    }
+
+    fn parse_newtype_declaration(&mut self) -> Result<ast::Statement, String> {
+        self.eat();
+        let identifier = self.expect_identifier("identifier name following the newtype keyword")?;
+
+        if let Err(token_type) = self.expect(TokenType::OpenParen) {
+            return Err(format!(
+                "Expected opening parenthesis following newtype {}, got: {:#?}",
+                identifier, token_type
+            ));
+        }
+        let value_type = self.parse_type_expr()?;
+        self.expect_closing(TokenType::ClosedParen)?;
+
+        if let Err(token_type) = self.expect(TokenType::SemiColon) {
+            return Err(format!(
+                "Newtype declarations must end with a Semicolon, got: {:#?}",
+                token_type
+            ));
+        }
+
+        Ok(ast::Statement::NewtypeDeclaration(
+            ast::NewtypeDeclarationStatement::create(identifier, value_type),
+        ))
+    }
+
+    /// Parses `<A, B: Display + Eq>` following the name of a function or
+    /// struct, if present.
+    fn parse_type_parameters(&mut self, context: &str) -> Result<Vec<ast::TypeParameter>, String> {
+        let mut type_parameters: Vec<ast::TypeParameter> = Vec::new();
+        if self.at().value != "<" {
+            return Ok(type_parameters);
+        }
+
+        self.eat();
+        while self.not_eof() && self.at().value != ">" {
+            let identifier = match self.expect(TokenType::Identifier) {
+                Ok(token) => token.value,
+                Err(token_type) => {
+                    return Err(format!(
+                        "Expected type parameter name in {}, got: {:#?}",
+                        context, token_type
+                    ))
+                }
+            };
+            if type_parameters
+                .iter()
+                .any(|type_parameter| type_parameter.identifier == identifier)
+            {
+                return Err(format!(
+                    "Type parameter {} has already been declared in {}",
+                    identifier, context
+                ));
+            }
+
+            let mut bounds = Vec::new();
+            if self.at().token_type == TokenType::Colon {
+                self.eat();
+                loop {
+                    let bound = self.eat();
+                    if bound.token_type != TokenType::Identifier
+                        || !prelude::TRAITS.contains(&bound.value.as_str())
+                    {
+                        return Err(format!(
+                            "Unknown trait {} bounding {} in {}, expected one of: {}",
+                            bound.value,
+                            identifier,
+                            context,
+                            prelude::TRAITS.join(", ")
+                        ));
+                    }
+                    bounds.push(bound.value);
+                    if self.at().value != "+" {
+                        break;
+                    }
+                    self.eat();
+                }
+            }
+
+            type_parameters.push(ast::TypeParameter { identifier, bounds });
+            if self.at().value != ">" {
+                self.expect_comma("type parameters")?;
+            }
+        }
+        if !self.not_eof() {
+            return Err(format!(
+                "Expected > closing the type parameters of {}, got: EOF",
+                context
+            ));
+        }
+        self.eat();
+
+        Ok(type_parameters)
+    }
+
+    /// Parses `name: Type` pairs up to the given closing token, used by
+    /// function parameters and struct fields.
+    fn parse_typed_fields(
+        &mut self,
+        closing: TokenType,
+        kind: &str,
+        context: &str,
+    ) -> Result<Vec<(String, ast::TypeExpr)>, String> {
+        let mut fields: Vec<(String, ast::TypeExpr)> = Vec::new();
+        while self.not_eof() && self.at().token_type != closing {
+            let identifier = self.expect_identifier(&format!("{} name in {}", kind, context))?;
+            if fields.iter().any(|(field, _)| *field == identifier) {
+                return Err(format!(
+                    "The {} {} has already been declared in {}",
+                    kind, identifier, context
+                ));
+            }
+            if let Err(token_type) = self.expect(TokenType::Colon) {
+                return Err(format!(
+                    "Expected a type following the {} {} in {}, got: {:#?}",
+                    kind, identifier, context, token_type
+                ));
+            }
+            fields.push((identifier, self.parse_type_expr()?));
+            if self.at().token_type != closing {
+                self.expect_comma(&format!("{}s", kind))?;
+            }
+        }
+        self.expect_closing(closing)?;
+
+        Ok(fields)
+    }
+
+    fn parse_template_literal(&mut self) -> Result<ast::Expression, String> {
+        self.eat();
+        let mut parts: Vec<ast::TemplatePart> = Vec::new();
+
+        loop {
+            let token = self.eat();
+            match token.token_type {
+                TokenType::TemplateEnd => break,
+                TokenType::TemplateText => parts.push(ast::TemplatePart::Text(token.value)),
+                TokenType::InterpolationStart => {
+                    let expression = self.parse_expression()?;
+                    let mut format: Option<ast::FormatSpec> = None;
+                    if self.at().token_type == TokenType::FormatSpecifier {
+                        format = Some(parse_format_specifier(&self.eat().value)?);
+                    }
+                    if let Err(token_type) = self.expect(TokenType::InterpolationEnd) {
+                        return Err(format!(
+                            "Expected the end of the interpolation in template string, got: {:#?}",
+                            token_type
+                        ));
+                    }
+                    parts.push(ast::TemplatePart::Interpolation { expression, format });
+                }
+                token_type => {
+                    return Err(format!(
+                        "Unexpected token found in template string: {:#?}",
+                        token_type
+                    ))
+                }
+            }
+        }
+
+        Ok(ast::Expression::Template(ast::TemplateLiteral::create(
+            parts,
+        )))
+    }
+
+    fn expect_closing(&mut self, token_type: TokenType) -> Result<(), String> {
+        match self.expect(token_type.to_owned()) {
+            Ok(_) => Ok(()),
+            Err(found) => Err(format!("Expected {:#?}, got: {:#?}", token_type, found)),
+        }
+    }
+
+    fn expect_comma(&mut self, context: &str) -> Result<(), String> {
+        match self.expect(TokenType::Comma) {
+            Ok(_) => Ok(()),
+            Err(token_type) => Err(format!(
+                "Expected comma between {}, got: {:#?}",
+                context, token_type
+            )),
+        }
+    }
+
+    fn parse_expression(&mut self) -> Result<ast::Expression, String> {
+        match self.parse_assignment_expression() {
+            Ok(expression) => Ok(expression),
+            Err(m) => return Err(m),
+        }
+    }
+
+    fn parse_assignment_expression(&mut self) -> Result<ast::Expression, String> {
+        let left = match self.parse_coalescing_expression() {
+            Ok(expression) => expression,
+            Err(m) => return Err(m),
+        };
+
+        if self.at().token_type == TokenType::Equals {
+            self.eat();
+            let value = match self.parse_coalescing_expression() {
+                Ok(expression) => expression,
+                Err(m) => return Err(m),
+            };
+            match self.expect(TokenType::SemiColon) {
+                Ok(_) => (),
+                Err(token_type) => {
+                    return Err(format!(
+                        "Assignment expressions must end with a Semicolon, got: {:#?}",
+                        token_type
+                    )
+                    .to_string())
+                }
+            }
+
+            return Ok(ast::Expression::VariableAssignment(Box::new(
+                ast::VariableAssignmentExpression::create(left, value),
+            )));
+        }
+
+        Ok(left)
+    }
+
+    /// `a ?? b`, `b` when `a` is null
+    fn parse_coalescing_expression(&mut self) -> Result<ast::Expression, String> {
+        let mut left = self.parse_comparison_expression()?;
+
+        while self.at().token_type == TokenType::DoubleQuestionMark {
+            let operator = self.eat().value;
+            let right = self.parse_comparison_expression()?;
+            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
+                left, right, operator,
+            )))
+        }
+
+        Ok(left)
+    }
+
+    fn parse_comparison_expression(&mut self) -> Result<ast::Expression, String> {
+        let mut left = self.parse_bitwise_or_expression()?;
+
+        while matches!(
+            self.at().token_type,
+            TokenType::Equality | TokenType::Inequality | TokenType::ComparisonOperator
+        ) {
+            let operator = self.eat().value;
+            let right = self.parse_bitwise_or_expression()?;
+            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
+                left, right, operator,
+            )))
+        }
+
+        Ok(left)
+    }
+
+    /// Bitwise operators bind tighter than comparisons, `|` loosest, then
+    /// `^`, `&` and the shifts.
+    fn parse_bitwise_or_expression(&mut self) -> Result<ast::Expression, String> {
+        let mut left = self.parse_bitwise_xor_expression()?;
+
+        while self.at().token_type == TokenType::BinaryOperator && self.at().value == "|" {
+            let operator = self.eat().value;
+            let right = self.parse_bitwise_xor_expression()?;
+            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
+                left, right, operator,
+            )))
+        }
+
+        Ok(left)
+    }
+
+    fn parse_bitwise_xor_expression(&mut self) -> Result<ast::Expression, String> {
+        let mut left = self.parse_bitwise_and_expression()?;
+
+        while self.at().token_type == TokenType::BinaryOperator && self.at().value == "^" {
+            let operator = self.eat().value;
+            let right = self.parse_bitwise_and_expression()?;
+            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
+                left, right, operator,
+            )))
+        }
+
+        Ok(left)
+    }
+
+    fn parse_bitwise_and_expression(&mut self) -> Result<ast::Expression, String> {
+        let mut left = self.parse_shift_expression()?;
+
+        while self.at().token_type == TokenType::BinaryOperator && self.at().value == "&" {
+            let operator = self.eat().value;
+            let right = self.parse_shift_expression()?;
+            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
+                left, right, operator,
+            )))
+        }
+
+        Ok(left)
+    }
+
+    fn parse_shift_expression(&mut self) -> Result<ast::Expression, String> {
+        let mut left = self.parse_additive_expression()?;
+
+        while self.at().token_type == TokenType::BinaryOperator
+            && matches!(self.at().value.as_str(), "<<" | ">>")
+        {
+            let operator = self.eat().value;
+            let right = self.parse_additive_expression()?;
+            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
+                left, right, operator,
+            )))
+        }
+
+        Ok(left)
+    }
+
+    fn parse_additive_expression(&mut self) -> Result<ast::Expression, String> {
+        let mut left = match self.parse_multiplicative_expression() {
+            Ok(expression) => expression,
+            Err(m) => return Err(m),
+        };
+
+        while self.at().value == "+" || self.at().value == "-" {
+            let operator = self.eat().value;
+            let right = match self.parse_multiplicative_expression() {
+                Ok(expression) => expression,
+                Err(m) => return Err(m),
+            };
+            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
+                left, right, operator,
+            )))
+        }
+
+        Ok(left)
+    }
+
+    fn parse_multiplicative_expression(&mut self) -> Result<ast::Expression, String> {
+        let mut left = match self.parse_exponent_expression() {
+            Ok(expression) => expression,
+            Err(m) => return Err(m),
+        };
+
+        while self.at().value == "*" || self.at().value == "/" || self.at().value == "%" {
+            let operator = self.eat().value;
+            let right = match self.parse_exponent_expression() {
+                Ok(expression) => expression,
+                Err(m) => return Err(m),
+            };
+            left = ast::Expression::Binary(Box::new(ast::BinaryExpression::create(
+                left, right, operator,
+            )))
+        }
+
+        Ok(left)
+    }
+
+    /// `**` is right associative, `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+    fn parse_exponent_expression(&mut self) -> Result<ast::Expression, String> {
+        let left = self.parse_member_expression()?;
+
+        if self.at().token_type != TokenType::BinaryOperator || self.at().value != "**" {
+            return Ok(left);
+        }
+        let operator = self.eat().value;
+        let right = self.parse_exponent_expression()?;
+        Ok(ast::Expression::Binary(Box::new(
+            ast::BinaryExpression::create(left, right, operator),
+        )))
+    }
+
+    /// Parses the types following an opening `<` up to and including the
+    /// closing `>`.
+    fn parse_type_arguments(&mut self, context: &str) -> Result<Vec<ast::TypeExpr>, String> {
+        let mut type_arguments = Vec::new();
+        loop {
+            type_arguments.push(self.parse_type_expr()?);
+            // The first `>` of `>>` closes these type arguments, the second
+            // closes the enclosing ones
+            if self.at().value == ">>" {
+                self.tokens[0].token_type = TokenType::ComparisonOperator;
+                self.tokens[0].value = ">".to_string();
+                self.tokens[0].location.column += 1;
+                break;
+            }
+            match self.eat() {
+                closing if closing.value == ">" => break,
+                comma if comma.token_type == TokenType::Comma => (),
+                unexpected => {
+                    return Err(format!(
+                        "Expected comma or > in type arguments of {}, got: {:#?}",
+                        context, unexpected.token_type
+                    ))
+                }
+            }
+        }
+        Ok(type_arguments)
+    }
+
+    /// Parses the arguments of a call following its opening parenthesis.
+    fn parse_arguments(&mut self) -> Result<Vec<ast::Expression>, String> {
+        let mut arguments = Vec::new();
+        while self.not_eof() && self.at().token_type != TokenType::ClosedParen {
+            arguments.push(self.parse_expression()?);
+            if self.at().token_type != TokenType::ClosedParen {
+                self.expect_comma("arguments")?;
+            }
+        }
+        self.expect_closing(TokenType::ClosedParen)?;
+        Ok(arguments)
+    }
+
+    /// Parses `.method(arguments)` or `.method::<T>(arguments)` once the
+    /// method name has been read.
+    fn parse_method_call(
+        &mut self,
+        object: ast::Expression,
+        method: String,
+        optional: bool,
+    ) -> Result<ast::Expression, String> {
+        let mut type_arguments = Vec::new();
+        if self.at().token_type == TokenType::DoubleColon {
+            self.eat();
+            match self.expect(TokenType::ComparisonOperator) {
+                Ok(operator) if operator.value == "<" => (),
+                _ => {
+                    return Err(format!(
+                        "Expected type arguments following {}::, e.g. {}::<i32>()",
+                        method, method
+                    ))
+                }
+            }
+            type_arguments = self.parse_type_arguments(&method)?;
+        }
+
+        if let Err(token_type) = self.expect(TokenType::OpenParen) {
+            return Err(format!(
+                "Expected opening parenthesis for the arguments of {}, got: {:#?}",
+                method, token_type
+            ));
+        }
+        let arguments = self.parse_arguments()?;
+
+        Ok(ast::Expression::MethodCall(Box::new(
+            ast::MethodCallExpression::create(object, method, type_arguments, arguments, optional),
+        )))
+    }
+
+    /// Parses `(arguments)` or `::<T>(arguments)` following a callee.
+    fn parse_call(&mut self, callee: ast::Expression) -> Result<ast::Expression, String> {
+        let mut type_arguments = Vec::new();
+        if self.at().token_type == TokenType::DoubleColon {
+            self.eat();
+            self.eat();
+            type_arguments = self.parse_type_arguments("function call")?;
+        }
+
+        if let Err(token_type) = self.expect(TokenType::OpenParen) {
+            return Err(format!(
+                "Expected opening parenthesis for the arguments of the function call, got: {:#?}",
+                token_type
+            ));
+        }
+        let arguments = self.parse_arguments()?;
+
+        Ok(ast::Expression::Call(Box::new(
+            ast::CallExpression::create(callee, type_arguments, arguments),
+        )))
+    }
+
+    fn parse_member_expression(&mut self) -> Result<ast::Expression, String> {
+        let line = self.at().location.line;
+        let mut object = self.parse_primary_expression()?;
+
+        loop {
+            match self.at().token_type {
+                TokenType::Dot | TokenType::QuestionDot | TokenType::QuestionMark => (),
+                // A parenthesis on the next line starts a new expression
+                // rather than calling the previous one
+                TokenType::OpenParen if self.at().location.line == line => {
+                    object = self.parse_call(object)?;
+                    continue;
+                }
+                TokenType::DoubleColon if self.peek(1).value == "<" => {
+                    object = self.parse_call(object)?;
+                    continue;
+                }
+                _ => break,
+            }
+            let optional = match self.eat().token_type {
+                TokenType::QuestionMark => {
+                    object = ast::Expression::Propagation(Box::new(
+                        ast::PropagationExpression::create(object),
+                    ));
+                    continue;
+                }
+                token_type => token_type == TokenType::QuestionDot,
+            };
+            let token = self.eat();
+            let properties = match token.token_type {
+                TokenType::Identifier
+                    if matches!(
+                        self.at().token_type,
+                        TokenType::OpenParen | TokenType::DoubleColon
+                    ) =>
+                {
+                    object = self.parse_method_call(object, token.value, optional)?;
+                    continue;
+                }
+                TokenType::Identifier => vec![ast::MemberProperty::Name(token.value)],
+                // `t.0.1` is lexed as `t`, `.` and the float `0.1`
+                TokenType::Number => {
+                    let mut indexes = Vec::new();
+                    for index in token.value.split('.') {
+                        match index.parse::<usize>() {
+                            Ok(index) => indexes.push(ast::MemberProperty::Index(index)),
+                            Err(_) => return Err(format!("Invalid tuple index: {}", index)),
+                        }
+                    }
+                    indexes
+                }
+                token_type => {
+                    return Err(format!(
+                    "Expected property name or tuple index following \".\" or \"?.\", got: {:#?}",
+                    token_type
+                ))
+                }
+            };
+            // Only the first index of `t?.0.1` is optional
+            for (index, property) in properties.into_iter().enumerate() {
+                object = ast::Expression::Member(Box::new(ast::MemberExpression::create(
+                    object,
+                    property,
+                    optional && index == 0,
+                )));
+            }
+        }
+
+        Ok(object)
+    }
+
+    fn parse_primary_expression(&mut self) -> Result<ast::Expression, String> {
+        let token_type = self.at().token_type;
+
+        match token_type {
+            lexer::TokenType::Identifier => {
+                if self.peek(1).token_type == TokenType::DoubleColon && self.peek(2).value != "<" {
+                    return self.parse_enum_variant_expression();
+                }
+                if self.is_struct_literal() {
+                    return self.parse_struct_literal();
+                }
+                if let Some(enum_identifier) = self.resolve_prelude_variant() {
+                    let variant_identifier = self.eat().value;
+                    return self.parse_enum_variant_payload(
+                        enum_identifier.to_string(),
+                        variant_identifier,
+                    );
+                }
+                Ok(ast::Expression::Identifier(
+                    ast::IdentifierExpression::create(self.eat().value),
+                ))
+            }
+            lexer::TokenType::Match => self.parse_match_expression(),
+            lexer::TokenType::Typeof => {
+                let line = self.eat().location.line;
+                match self.eat() {
+                    // Only a variable has a declared type, so `typeof o.a` or
+                    // `typeof f(x)` would describe the wrong thing
+                    token
+                        if token.token_type == TokenType::Identifier
+                            && (matches!(
+                                self.at().token_type,
+                                TokenType::Dot | TokenType::QuestionDot | TokenType::DoubleColon
+                            ) || (self.at().token_type == TokenType::OpenParen
+                                && self.at().location.line == line)) =>
+                    {
+                        Err(format!(
+                            "typeof takes a variable name, not an expression such as {}{}..., wrap typeof {} in parentheses to use its result",
+                            token.value,
+                            self.at().value,
+                            token.value
+                        ))
+                    }
+                    token if token.token_type == TokenType::Identifier => Ok(
+                        ast::Expression::Typeof(ast::TypeofExpression::create(token.value)),
+                    ),
+                    token => Err(format!(
+                        "Expected a variable name following typeof, got: {:#?}",
+                        token
+                    )),
+                }
+            }
+            lexer::TokenType::Number => Ok(number_literal(self.eat().value)),
+            lexer::TokenType::BooleanLiteral => Ok(ast::Expression::Boolean(
+                ast::BooleanLiteral::create(self.eat().value == "true"),
+            )),
+            lexer::TokenType::NullLiteral => {
+                self.eat();
+                Ok(ast::Expression::Null(ast::NullLiteral::create()))
+            }
+            lexer::TokenType::StringLiteral => Ok(ast::Expression::String(
+                ast::StringLiteral::create(self.eat().value),
+            )),
+            lexer::TokenType::TemplateStart => self.parse_template_literal(),
+            lexer::TokenType::CharacterLiteral => {
+                let token = self.eat();
+                match token.value.chars().next() {
+                    Some(character) => Ok(ast::Expression::Character(
+                        ast::CharacterLiteral::create(character),
+                    )),
+                    None => Err("Character literals must contain a character".to_string()),
+                }
+            }
+            lexer::TokenType::OpenParen => {
+                self.eat();
+                if self.at().token_type == TokenType::ClosedParen {
+                    self.eat();
+                    return Ok(ast::Expression::Tuple(
+                        ast::TupleLiteral::create(Vec::new()),
+                    ));
+                }
+                let value = match self.parse_expression() {
+                    Ok(expression) => expression,
+                    Err(m) => return Err(m),
+                };
+                if self.at().token_type == TokenType::Comma {
+                    return self.parse_tuple_literal(value);
+                }
+                match self.expect(TokenType::ClosedParen) {
+                    Ok(_) => Ok(value),
+                    Err(token_type) => Err(format!(
+                        "Expected closing parenthesis, got: {:#?}",
+                        token_type
+                    )),
+                }
+            }
+            lexer::TokenType::OpenBracket => {
+                self.eat();
+                let mut elements = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedBracket {
+                    elements.push(self.parse_expression()?);
+                    if self.at().token_type != TokenType::ClosedBracket {
+                        self.expect_comma("array elements")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedBracket)?;
+                Ok(ast::Expression::Array(ast::ArrayLiteral::create(elements)))
+            }
+            lexer::TokenType::OpenBrace if !self.no_struct_payload => self.parse_object_literal(),
+            _ => Err(format!(
+                "Unexpected token found during parsing: {:#?}",
+                self.at()
+            )),
+        }
+    }
+
+    /// Whether the current identifier starts a struct literal such as
+    /// `Pair { a: 1 }` rather than being followed by a block.
+    fn is_struct_literal(&mut self) -> bool {
+        if self.no_struct_payload || self.peek(1).token_type != TokenType::OpenBrace {
+            return false;
+        }
+        match self.peek(2).token_type {
+            TokenType::ClosedBrace => true,
+            TokenType::Identifier => matches!(
+                self.peek(3).token_type,
+                TokenType::Colon | TokenType::Comma | TokenType::ClosedBrace
+            ),
+            _ => false,
+        }
+    }
+
+    fn parse_struct_literal(&mut self) -> Result<ast::Expression, String> {
+        let identifier = self.eat().value;
+        self.eat();
+
+        let mut fields: Vec<(String, ast::Expression)> = Vec::new();
+        while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+            let field = match self.expect(TokenType::Identifier) {
+                Ok(token) => token.value,
+                Err(token_type) => {
+                    return Err(format!(
+                        "Expected field name in struct {}, got: {:#?}",
+                        identifier, token_type
+                    ))
+                }
+            };
+            if fields.iter().any(|(existing, _)| *existing == field) {
+                return Err(format!(
+                    "Field {} has already been given in struct {}",
+                    field, identifier
+                ));
+            }
+            let value = match self.at().token_type {
+                TokenType::Colon => {
+                    self.eat();
+                    self.parse_expression()?
+                }
+                // `Point { x }` is shorthand for `Point { x: x }`
+                _ => {
+                    ast::Expression::Identifier(ast::IdentifierExpression::create(field.to_owned()))
+                }
+            };
+            fields.push((field, value));
+            if self.at().token_type != TokenType::ClosedBrace {
+                self.expect_comma("struct fields")?;
+            }
+        }
+        self.expect_closing(TokenType::ClosedBrace)?;
+
+        Ok(ast::Expression::Struct(ast::StructLiteral::create(
+            identifier, fields,
+        )))
+    }
+
+    fn parse_enum_path(&mut self) -> Result<(String, String), String> {
+        let enum_identifier = self.eat().value;
+        self.eat();
+        match self.expect(TokenType::Identifier) {
+            Ok(token) => Ok((enum_identifier, token.value)),
+            Err(token_type) => Err(format!(
+                "Expected variant name following {}::, got: {:#?}",
+                enum_identifier, token_type
+            )),
+        }
+    }
+
+    /// Returns the enum of a prelude variant such as `Some(..)` or `None` when
+    /// the current token refers to one.
+    fn resolve_prelude_variant(&mut self) -> Option<&'static str> {
+        let token = self.at();
+        let enum_identifier = prelude::resolve_variant(&token.value)?;
+        match token.value.as_str() {
+            "None" => Some(enum_identifier),
+            _ if self.peek(1).token_type == TokenType::OpenParen => Some(enum_identifier),
+            _ => None,
+        }
+    }
+
+    fn parse_enum_variant_expression(&mut self) -> Result<ast::Expression, String> {
+        let (enum_identifier, variant_identifier) = self.parse_enum_path()?;
+        self.parse_enum_variant_payload(enum_identifier, variant_identifier)
+    }
+
+    fn parse_enum_variant_payload(
+        &mut self,
+        enum_identifier: String,
+        variant_identifier: String,
+    ) -> Result<ast::Expression, String> {
+        let payload = match self.at().token_type {
+            TokenType::OpenParen => {
+                self.eat();
+                let mut values = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedParen {
+                    values.push(self.parse_expression()?);
+                    if self.at().token_type != TokenType::ClosedParen {
+                        self.expect_comma("enum variant values")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedParen)?;
+                ast::EnumPayload::Tuple(values)
+            }
+            TokenType::OpenBrace if !self.no_struct_payload => {
+                self.eat();
+                let mut fields = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+                    let field = match self.expect(TokenType::Identifier) {
+                        Ok(token) => token.value,
+                        Err(token_type) => {
+                            return Err(format!(
+                                "Expected field name in {}::{}, got: {:#?}",
+                                enum_identifier, variant_identifier, token_type
+                            ))
+                        }
+                    };
+                    if let Err(token_type) = self.expect(TokenType::Colon) {
+                        return Err(format!(
+                            "Expected colon following field {} in {}::{}, got: {:#?}",
+                            field, enum_identifier, variant_identifier, token_type
+                        ));
+                    }
+                    fields.push((field, self.parse_expression()?));
+                    if self.at().token_type != TokenType::ClosedBrace {
+                        self.expect_comma("enum variant fields")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedBrace)?;
+                ast::EnumPayload::Struct(fields)
+            }
+            _ => ast::EnumPayload::Unit,
+        };
+
+        Ok(ast::Expression::EnumVariant(Box::new(
+            ast::EnumVariantExpression::create(enum_identifier, variant_identifier, payload),
+        )))
+    }
+
+    fn parse_match_expression(&mut self) -> Result<ast::Expression, String> {
+        self.eat();
+
+        self.no_struct_payload = true;
+        let subject = self.parse_expression();
+        self.no_struct_payload = false;
+        let subject = subject?;
+
+        if let Err(token_type) = self.expect(TokenType::OpenBrace) {
+            return Err(format!(
+                "Expected opening brace following the match subject, got: {:#?}",
+                token_type
+            ));
+        }
+
+        let mut arms: Vec<ast::MatchArm> = Vec::new();
+
+        while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+            let pattern = self.parse_pattern(false)?;
+
+            let guard = match self.at().token_type {
+                TokenType::If => {
+                    self.eat();
+                    Some(self.parse_expression()?)
+                }
+                _ => None,
+            };
+
+            if let Err(token_type) = self.expect(TokenType::FatArrow) {
+                return Err(format!(
+                    "Expected => following match arm pattern, got: {:#?}",
+                    token_type
+                ));
+            }
+
+            let body = self.parse_expression()?;
+            arms.push(ast::MatchArm {
+                pattern,
+                guard,
+                body,
+            });
+
+            if self.at().token_type != TokenType::ClosedBrace {
+                self.expect_comma("match arms")?;
+            }
+        }
+
+        if let Err(token_type) = self.expect(TokenType::ClosedBrace) {
+            return Err(format!(
+                "Expected closing brace at the end of match expression, got: {:#?}",
+                token_type
+            ));
+        }
+
+        Ok(ast::Expression::Match(Box::new(
+            ast::MatchExpression::create(subject, arms),
+        )))
+    }
+
+    /// Parses a pattern for a match arm or, when `allow_types` is set, for a
+    /// destructuring declaration where bindings may carry a type annotation.
+    fn parse_pattern(&mut self, allow_types: bool) -> Result<ast::Pattern, String> {
+        let token = self.at();
+
+        match token.token_type {
+            TokenType::Identifier => {
+                if self.peek(1).token_type == TokenType::DoubleColon {
+                    return self.parse_enum_variant_pattern();
+                }
+                if let Some(enum_identifier) = self.resolve_prelude_variant() {
+                    let variant_identifier = self.eat().value;
+                    return self.parse_enum_variant_pattern_payload(
+                        enum_identifier.to_string(),
+                        variant_identifier,
+                    );
+                }
+                self.eat();
+                match token.value.as_str() {
+                    "_" => Ok(ast::Pattern::Wildcard),
+                    _ => {
+                        let value_type = match self.at().token_type {
+                            TokenType::Colon if allow_types => {
+                                self.eat();
+                                Some(self.parse_type_expr()?)
+                            }
+                            _ => None,
+                        };
+                        Ok(ast::Pattern::Binding {
+                            identifier: token.value,
+                            value_type,
+                            mutable: false,
+                        })
+                    }
+                }
+            }
+            TokenType::Mut => {
+                self.eat();
+                match self.parse_pattern(allow_types)? {
+                    ast::Pattern::Binding {
+                        identifier,
+                        value_type,
+                        mutable: false,
+                    } => Ok(ast::Pattern::Binding {
+                        identifier,
+                        value_type,
+                        mutable: true,
+                    }),
+                    _ => Err("Expected a variable name following mut in a pattern".to_string()),
+                }
+            }
+            TokenType::BooleanLiteral | TokenType::NullLiteral => {
+                Ok(ast::Pattern::Literal(self.parse_primary_expression()?))
+            }
+            TokenType::Number
+            | TokenType::BinaryOperator
+            | TokenType::StringLiteral
+            | TokenType::CharacterLiteral => {
+                let start = self.parse_pattern_literal()?;
+                let inclusive = match self.at().token_type {
+                    TokenType::DoubleDot => false,
+                    TokenType::DoubleDotEquals => true,
+                    _ => return Ok(ast::Pattern::Literal(start)),
+                };
+                self.eat();
+                let end = self.parse_pattern_literal()?;
+                Ok(ast::Pattern::Range {
+                    start,
+                    end,
+                    inclusive,
+                })
+            }
+            TokenType::OpenParen => {
+                self.eat();
+                let mut patterns = Vec::new();
+                let mut trailing_comma = false;
+                while self.not_eof() && self.at().token_type != TokenType::ClosedParen {
+                    patterns.push(self.parse_pattern(allow_types)?);
+                    trailing_comma = false;
+                    if self.at().token_type != TokenType::ClosedParen {
+                        self.expect_comma("tuple patterns")?;
+                        trailing_comma = true;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedParen)?;
+
+                // `(a)` only groups a pattern, `(a,)` is a single element tuple
+                if patterns.len() == 1 && !trailing_comma {
+                    return Ok(patterns.remove(0));
+                }
+                Ok(ast::Pattern::Tuple(patterns))
+            }
+            TokenType::OpenBracket => {
+                self.eat();
+                let mut head = Vec::new();
+                let mut rest: Option<Box<ast::Pattern>> = None;
+                let mut tail = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedBracket {
+                    if self.at().token_type == TokenType::DoubleDot {
+                        self.eat();
+                        if rest.is_some() {
+                            return Err(
+                                "Array patterns can only contain a single rest pattern".to_string()
+                            );
+                        }
+                        rest = match self.at().token_type {
+                            TokenType::Identifier => {
+                                Some(Box::new(self.parse_pattern(allow_types)?))
+                            }
+                            _ => Some(Box::new(ast::Pattern::Wildcard)),
+                        };
+                    } else if rest.is_some() {
+                        tail.push(self.parse_pattern(allow_types)?);
+                    } else {
+                        head.push(self.parse_pattern(allow_types)?);
+                    }
+                    if self.at().token_type != TokenType::ClosedBracket {
+                        self.expect_comma("array patterns")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedBracket)?;
+                Ok(ast::Pattern::Array { head, rest, tail })
+            }
+            TokenType::OpenBrace => {
+                self.eat();
+                let mut fields = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+                    // `{ mut x }` makes the shorthand binding `x` mutable
+                    let mutable = self.at().token_type == TokenType::Mut;
+                    if mutable {
+                        self.eat();
+                    }
+                    let field = match self.expect(TokenType::Identifier) {
+                        Ok(token) => token.value,
+                        Err(token_type) => {
+                            return Err(format!(
+                                "Expected property name in object pattern, got: {:#?}",
+                                token_type
+                            ))
+                        }
+                    };
+                    let pattern = match self.at().token_type {
+                        TokenType::Colon => {
+                            self.eat();
+                            match self.at().token_type {
+                                // `{ x: i32 }` annotates the shorthand binding `x`
+                                TokenType::String | TokenType::Character | TokenType::Boolean
+                                    if allow_types =>
+                                {
+                                    ast::Pattern::Binding {
+                                        identifier: field.to_owned(),
+                                        value_type: Some(self.parse_type_expr()?),
+                                        mutable: false,
+                                    }
+                                }
+                                TokenType::Identifier
+                                    if allow_types && is_numeric_type(&self.at().value) =>
+                                {
+                                    ast::Pattern::Binding {
+                                        identifier: field.to_owned(),
+                                        value_type: Some(self.parse_type_expr()?),
+                                        mutable: false,
+                                    }
+                                }
+                                _ => self.parse_pattern(allow_types)?,
+                            }
+                        }
+                        _ => ast::Pattern::Binding {
+                            identifier: field.to_owned(),
+                            value_type: None,
+                            mutable: false,
+                        },
+                    };
+                    let pattern = match pattern {
+                        ast::Pattern::Binding {
+                            identifier,
+                            value_type,
+                            ..
+                        } if mutable && identifier == field => ast::Pattern::Binding {
+                            identifier,
+                            value_type,
+                            mutable,
+                        },
+                        _ if mutable => {
+                            return Err(format!(
+                                "mut {} cannot be followed by a pattern, use {}: mut name instead",
+                                field, field
+                            ))
+                        }
+                        pattern => pattern,
+                    };
+                    fields.push((field, pattern));
+                    if self.at().token_type != TokenType::ClosedBrace {
+                        self.expect_comma("object patterns")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedBrace)?;
+                Ok(ast::Pattern::Object(fields))
+            }
+            _ => Err(format!(
+                "Unexpected token found while parsing pattern: {:#?}",
+                token
+            )),
+        }
+    }
+
+    fn parse_pattern_literal(&mut self) -> Result<ast::Expression, String> {
+        match self.at().token_type {
+            TokenType::StringLiteral | TokenType::CharacterLiteral => {
+                return self.parse_primary_expression()
+            }
+            _ => (),
+        }
+
+        let negative = match self.at().value.as_str() {
+            "-" => {
+                self.eat();
+                true
+            }
+            _ => false,
+        };
+
+        let token = match self.expect(TokenType::Number) {
+            Ok(token) => token,
+            Err(token_type) => {
+                return Err(format!(
+                    "Expected number literal in match pattern, got: {:#?}",
+                    token_type
+                ))
+            }
+        };
+
+        if token.value == "nan" {
+            return Err(
+                "nan never matches a pattern as it is not equal to itself, use math.is_nan instead"
+                    .to_string(),
+            );
+        }
+
+        let value = match negative {
+            true => format!("-{}", token.value),
+            false => token.value,
+        };
+
+        Ok(number_literal(value))
+    }
+
+    fn parse_enum_variant_pattern(&mut self) -> Result<ast::Pattern, String> {
+        let (enum_identifier, variant_identifier) = self.parse_enum_path()?;
+        self.parse_enum_variant_pattern_payload(enum_identifier, variant_identifier)
+    }
+
+    fn parse_enum_variant_pattern_payload(
+        &mut self,
+        enum_identifier: String,
+        variant_identifier: String,
+    ) -> Result<ast::Pattern, String> {
+        let payload = match self.at().token_type {
+            TokenType::OpenParen => {
+                self.eat();
+                let mut patterns = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedParen {
+                    patterns.push(self.parse_pattern(false)?);
+                    if self.at().token_type != TokenType::ClosedParen {
+                        self.expect_comma("enum variant patterns")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedParen)?;
+                ast::EnumPayload::Tuple(patterns)
+            }
+            TokenType::OpenBrace => {
+                self.eat();
+                let mut fields = Vec::new();
+                while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+                    // `..` ignores any fields that have not been listed
+                    if self.at().token_type == TokenType::DoubleDot {
+                        self.eat();
+                        continue;
+                    }
+                    let field = match self.expect(TokenType::Identifier) {
+                        Ok(token) => token.value,
+                        Err(token_type) => {
+                            return Err(format!(
+                                "Expected field name in {}::{} pattern, got: {:#?}",
+                                enum_identifier, variant_identifier, token_type
+                            ))
+                        }
+                    };
+                    let pattern = match self.at().token_type {
+                        TokenType::Colon => {
+                            self.eat();
+                            self.parse_pattern(false)?
+                        }
+                        _ => ast::Pattern::Binding {
+                            identifier: field.to_owned(),
+                            value_type: None,
+                            mutable: false,
+                        },
+                    };
+                    fields.push((field, pattern));
+                    if self.at().token_type != TokenType::ClosedBrace {
+                        self.expect_comma("enum variant field patterns")?;
+                    }
+                }
+                self.expect_closing(TokenType::ClosedBrace)?;
+                ast::EnumPayload::Struct(fields)
+            }
+            _ => ast::EnumPayload::Unit,
+        };
+
+        Ok(ast::Pattern::EnumVariant {
+            enum_identifier,
+            variant_identifier,
+            payload,
+        })
+    }
+
+    fn parse_tuple_literal(&mut self, first: ast::Expression) -> Result<ast::Expression, String> {
+        let mut elements = vec![first];
+        while self.at().token_type == TokenType::Comma {
+            self.eat();
+            if self.at().token_type == TokenType::ClosedParen {
+                break;
+            }
+            elements.push(self.parse_expression()?);
+        }
+        self.expect_closing(TokenType::ClosedParen)?;
+
+        Ok(ast::Expression::Tuple(ast::TupleLiteral::create(elements)))
+    }
+
+    fn parse_object_literal(&mut self) -> Result<ast::Expression, String> {
+        self.eat();
+        let mut properties: Vec<(String, ast::Expression)> = Vec::new();
+        while self.not_eof() && self.at().token_type != TokenType::ClosedBrace {
+            let key = match self.expect(TokenType::Identifier) {
+                Ok(token) => token.value,
+                Err(token_type) => {
+                    return Err(format!(
+                        "Expected property name in object literal, got: {:#?}",
+                        token_type
+                    ))
+                }
+            };
+            if properties.iter().any(|(existing, _)| *existing == key) {
+                return Err(format!(
+                    "Property {} has already been defined in object literal",
+                    key
+                ));
+            }
+            let value = match self.at().token_type {
+                TokenType::Colon => {
+                    self.eat();
+                    self.parse_expression()?
+                }
+                // `{ x }` is shorthand for `{ x: x }`
+                _ => ast::Expression::Identifier(ast::IdentifierExpression::create(key.to_owned())),
+            };
+            properties.push((key, value));
+            if self.at().token_type != TokenType::ClosedBrace {
+                self.expect_comma("object properties")?;
+            }
+        }
+        self.expect_closing(TokenType::ClosedBrace)?;
+
+        Ok(ast::Expression::Object(ast::ObjectLiteral::create(
+            properties,
+        )))
+    }
+}
+
+fn is_numeric_type(symbol: &str) -> bool {
+    matches!(
+        symbol,
+        "i8" | "i16"
+            | "i32"
+            | "i64"
+            | "i128"
+            | "u8"
+            | "u16"
+            | "u32"
+            | "u64"
+            | "u128"
+            | "bigint"
+            | "decimal"
+            | "f32"
+            | "f64"
+    )
+}
+
+/// Parses a format specifier such as `>8.2`, `+08` or `*^10`.
+fn parse_format_specifier(specifier: &str) -> Result<ast::FormatSpec, String> {
+    let characters: Vec<char> = specifier.chars().collect();
+    let mut format = ast::FormatSpec {
+        fill: ' ',
+        align: None,
+        sign_plus: false,
+        zero_pad: false,
+        width: None,
+        precision: None,
+    };
+
+    let alignment = |character: Option<&char>| match character {
+        Some('<') => Some(ast::FormatAlignment::Left),
+        Some('^') => Some(ast::FormatAlignment::Center),
+        Some('>') => Some(ast::FormatAlignment::Right),
+        _ => None,
+    };
+
+    let mut index = 0;
+    if let Some(align) = alignment(characters.get(1)) {
+        format.fill = characters[0];
+        format.align = Some(align);
+        index = 2;
+    } else if let Some(align) = alignment(characters.first()) {
+        format.align = Some(align);
+        index = 1;
+    }
+
+    if characters.get(index) == Some(&'+') {
+        format.sign_plus = true;
+        index += 1;
+    }
+    if characters.get(index) == Some(&'0') {
+        format.zero_pad = true;
+        index += 1;
+    }
+
+    let (width, next) = read_format_number(&characters, index);
+    format.width = width;
+    index = next;
+
+    if characters.get(index) == Some(&'.') {
+        let (precision, next) = read_format_number(&characters, index + 1);
+        if precision.is_none() {
+            return Err(format!(
+                "Expected a precision following \".\" in format specifier \"{}\"",
+                specifier
+            ));
+        }
+        format.precision = precision;
+        index = next;
+    }
+
+    if index != characters.len() {
+        return Err(format!("Invalid format specifier \"{}\"", specifier));
+    }
+
+    Ok(format)
+}
+
+fn read_format_number(characters: &[char], start: usize) -> (Option<usize>, usize) {
+    let mut end = start;
+    while end < characters.len() && characters[end].is_ascii_digit() {
+        end += 1;
+    }
+
+    match characters[start..end]
+        .iter()
+        .collect::<String>()
+        .parse::<usize>()
+    {
+        Ok(number) => (Some(number), end),
+        Err(_) => (None, start),
+    }
+}
+
+/// Number literals ending in `d` are decimals and those ending in `n` are
+/// bigints. Otherwise numbers with a decimal point, `inf` and `nan` are
+/// floats.
+fn number_literal(value: String) -> ast::Expression {
+    if let Some(digits) = value.strip_suffix('d') {
+        return ast::Expression::Decimal(ast::DecimalLiteral::create(digits.to_string()));
+    }
+    if value.contains('.') || value.ends_with("inf") || value == "nan" {
+        return ast::Expression::Float(ast::FloatLiteral::create(value));
+    }
+    if let Some(digits) = value.strip_suffix('n') {
+        return ast::Expression::BigInt(ast::BigIntLiteral::create(digits.to_string()));
+    }
+    ast::Expression::Integer(ast::IntegerLiteral::create(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<ast::Statement, String> {
+        Parser::new().produce_ast(source)
+    }
+
+    #[test]
+    fn reports_type_parameters_cut_off_by_the_end_of_the_file() {
+        for source in [
+            "fn f<",
+            "struct S<",
+            "fn f<T,",
+            "fn f<T: Eq +",
+            "enum E<",
+            "enum E<T",
+        ] {
+            assert!(parse(source).is_err(), "{}", source);
+        }
+        let error = parse("fn f<T,").unwrap_err();
+        assert_eq!(
+            error,
+            "Expected > closing the type parameters of f, got: EOF"
+        );
+    }
+
+    #[test]
+    fn rejects_member_and_call_expressions_after_typeof() {
+        let member = parse("let o = {a: 1};\ntypeof o.a").unwrap_err();
+        assert!(member.contains("typeof takes a variable name"));
+        assert!(parse("typeof math.abs(1)").is_err());
+        assert!(parse("typeof f(1)").is_err());
+        assert!(parse("(typeof x).declared").is_ok());
+        assert!(parse("typeof x\n(1)").is_ok());
+    }
+
+    #[test]
+    fn parses_enum_type_parameters() {
+        assert!(parse("enum Pair<A, B> { Both(A, B) }").is_ok());
+        assert!(parse("enum Pair<A: Eq> { One(A) }").is_err());
+    }
+
+    #[test]
+    fn parses_mut_on_single_bindings() {
+        let program = match parse("let (mut a, b) = (1, 2);") {
+            Ok(ast::Statement::Program(program)) => program,
+            _ => panic!("expected a program"),
+        };
+        let pattern = match &program.body[0].statement {
+            ast::Statement::DestructuringDeclaration(declaration) => &declaration.pattern,
+            _ => panic!("expected a destructuring declaration"),
+        };
+        let mutable: Vec<bool> = match pattern {
+            ast::Pattern::Tuple(patterns) => patterns
+                .iter()
+                .map(|pattern| matches!(pattern, ast::Pattern::Binding { mutable: true, .. }))
+                .collect(),
+            _ => panic!("expected a tuple pattern"),
+        };
+        assert_eq!(mutable, vec![true, false]);
+        assert!(parse("let { mut x, y } = o;").is_ok());
+        assert!(parse("let [mut h, ..t] = a;").is_ok());
+        assert!(parse("let (mut _, b) = t;").is_err());
+        assert!(parse("const (mut a, b) = t;").is_err());
+        assert!(parse("(mut a, b) = t;").is_err());
     }
 }
@@ -0,0 +1,124 @@
+use super::ast;
+use super::parser;
+
+/// Declarations that are available to every Jarlang program.
+pub const SOURCE: &str = "
+enum Option<T> { Some(T), None }
+enum Result<T, E> { Ok(T), Err(E) }
+";
+
+pub fn enums() -> Vec<ast::EnumDeclarationStatement> {
+    let program = match parser::Parser::new().produce_ast(SOURCE) {
+        Ok(ast::Statement::Program(program)) => program,
+        _ => return Vec::new(),
+    };
+
+    program
+        .body
+        .into_iter()
+        .filter_map(|located| match located.statement {
+            ast::Statement::EnumDeclaration(enum_declaration) => Some(enum_declaration),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Prelude variants can be used without naming their enum, e.g. `Some(1)`
+/// rather than `Option::Some(1)`.
+pub fn resolve_variant(variant_identifier: &str) -> Option<&'static str> {
+    match variant_identifier {
+        "Some" | "None" => Some("Option"),
+        "Ok" | "Err" => Some("Result"),
+        _ => None,
+    }
+}
+
+/// The index of the type argument that types the payload of a prelude
+/// variant, e.g. `Err(e)` in `Result<T, E>` is typed by `E`.
+pub fn payload_type_argument(enum_identifier: &str, variant_identifier: &str) -> Option<usize> {
+    match (enum_identifier, variant_identifier) {
+        ("Option", "Some") | ("Result", "Ok") => Some(0),
+        ("Result", "Err") => Some(1),
+        _ => None,
+    }
+}
+
+/// Traits that can bound a type parameter, e.g. `fn show<T: Display>(x: T)`.
+pub const TRAITS: [&str; 6] = ["Display", "Eq", "Ord", "Num", "Integer", "Float"];
+
+const INTEGER_TYPES: [&str; 11] = [
+    "i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128", "bigint",
+];
+
+/// Whether values of the given type implement a trait from `TRAITS`.
+/// Functions can neither be displayed nor compared.
+pub fn implements_trait(trait_identifier: &str, value_type: &ast::TypeExpr) -> bool {
+    let identifier = match value_type {
+        ast::TypeExpr::Named { identifier, .. } => identifier.as_str(),
+        ast::TypeExpr::Function { .. } => return false,
+        _ => return matches!(trait_identifier, "Display" | "Eq"),
+    };
+    let is_integer = INTEGER_TYPES.contains(&identifier);
+    let is_float = matches!(identifier, "f32" | "f64");
+    let is_number = is_integer || is_float || identifier == "decimal";
+
+    match trait_identifier {
+        "Display" | "Eq" => identifier != "fn",
+        "Ord" => is_number || matches!(identifier, "str" | "char" | "bool"),
+        "Num" => is_number,
+        "Integer" => is_integer,
+        "Float" => is_float,
+        _ => false,
+    }
+}
+
+/// Whether values of the given type can be map keys or set values.
+fn is_hashable(value_type: &ast::TypeExpr) -> bool {
+    match value_type {
+        ast::TypeExpr::Named {
+            identifier,
+            type_arguments,
+        } => {
+            type_arguments.is_empty()
+                && (INTEGER_TYPES.contains(&identifier.as_str())
+                    || matches!(identifier.as_str(), "str" | "char" | "bool"))
+        }
+        ast::TypeExpr::Tuple(element_types) => element_types.iter().all(is_hashable),
+        _ => false,
+    }
+}
+
+/// Checks that every `Map` and `Set` in the given type has keys or values
+/// that can be hashed, e.g. rejecting `Set<[i32]>`.
+pub fn check_hashable(value_type: &ast::TypeExpr) -> Result<(), String> {
+    let nested: Vec<&ast::TypeExpr> = match value_type {
+        ast::TypeExpr::Named {
+            identifier,
+            type_arguments,
+        } => {
+            let key_type = match (identifier.as_str(), type_arguments.as_slice()) {
+                ("Map", [key_type, _]) | ("Set", [key_type]) => Some(key_type),
+                _ => None,
+            };
+            if let Some(key_type) = key_type.filter(|key_type| !is_hashable(key_type)) {
+                return Err(format!(
+                    "Invalid type {}, a value of type {} cannot be used as a key, keys must be integers, str, char, bool or tuples of them",
+                    value_type, key_type
+                ));
+            }
+            type_arguments.iter().collect()
+        }
+        ast::TypeExpr::Array(element_type) | ast::TypeExpr::Optional(element_type) => {
+            vec![element_type.as_ref()]
+        }
+        ast::TypeExpr::Tuple(element_types) => element_types.iter().collect(),
+        ast::TypeExpr::Function {
+            parameters,
+            return_type,
+        } => parameters
+            .iter()
+            .chain(std::iter::once(return_type.as_ref()))
+            .collect(),
+    };
+    nested.into_iter().try_for_each(check_hashable)
+}
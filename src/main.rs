@@ -1,51 +1,60 @@
+mod analysis;
 mod command;
 mod frontend;
+mod manifest;
+mod modules;
 mod repl;
 mod runtime;
 
-use command::CLI;
+use command::{Command, CLI};
+use manifest::Manifest;
+use modules::ModuleLoader;
 
 use clap::Parser;
-use frontend::parser;
-use runtime::environment::Environment;
-use runtime::interpreter;
-use std::borrow::BorrowMut;
-use std::fs;
+use std::path::Path;
 use std::process::exit;
 
-fn run_file(path: &str) -> Result<(), String> {
-    println!("Running {}", path);
-    let buff: String;
-    match fs::read_to_string(path) {
-        Ok(source) => buff = source,
-        Err(_) => {
-            println!("Failed to read {}", path);
-            exit(1);
+fn run_file(
+    path: &str,
+    strict_shadowing: bool,
+    trap_nan: bool,
+    print_result: bool,
+) -> Result<(), String> {
+    let mut module_loader = ModuleLoader::new();
+    module_loader.set_strict_shadowing(strict_shadowing);
+    module_loader.set_trap_nan(trap_nan);
+    match module_loader.run(Path::new(path)) {
+        Ok(runtime_val) => {
+            if print_result {
+                println!("{}", runtime_val.to_debug_string());
+            }
+            Ok(())
         }
-    }
-
-    let mut parser = parser::Parser::new();
-    let mut environment = Environment::create(None);
-
-    match environment.init_global_scope() {
-        Ok(_) => (),
         Err(m) => {
             println!("{}", m);
-            exit(1);
+            exit(1)
         }
     }
+}
 
-    let program = match parser.produce_ast(buff.as_str()) {
-        Ok(program_statement) => program_statement,
-        Err(m) => {
-            println!("{}", m);
-            exit(1);
-        }
-    };
+fn run_package(
+    directory: &str,
+    strict_shadowing: bool,
+    trap_nan: bool,
+    print_result: bool,
+) -> Result<(), String> {
+    let manifest = Manifest::read(Path::new(directory))?;
+    let mut module_loader = ModuleLoader::new();
+    module_loader.set_strict_shadowing(strict_shadowing || manifest.strict_shadowing);
+    module_loader.set_trap_nan(trap_nan);
+    module_loader.set_mutability(manifest.mutability);
+    module_loader.add_dependencies(&manifest)?;
 
-    match interpreter::evaluate(program, environment.borrow_mut()) {
+    match module_loader.run(&manifest.entry) {
         Ok(runtime_val) => {
-            println!("{:#?}", runtime_val);
+            if print_result {
+                println!("{}", runtime_val.to_debug_string());
+            }
             Ok(())
         }
         Err(m) => {
@@ -58,8 +67,40 @@ fn run_file(path: &str) -> Result<(), String> {
 fn main() {
     let cli = CLI::parse();
 
+    match cli.command {
+        Some(Command::Run { directory }) => {
+            match run_package(
+                directory.as_deref().unwrap_or("."),
+                cli.strict_shadowing,
+                cli.trap_nan,
+                cli.print_result,
+            ) {
+                Ok(_) => exit(0),
+                Err(m) => {
+                    println!("{}", m);
+                    exit(1)
+                }
+            }
+        }
+        Some(Command::Check { path }) => {
+            let mut module_loader = ModuleLoader::new();
+            module_loader.set_strict_shadowing(cli.strict_shadowing);
+            match module_loader.check(Path::new(&path)) {
+                Ok(_) => {
+                    println!("No errors found in {}", path);
+                    exit(0)
+                }
+                Err(m) => {
+                    println!("{}", m);
+                    exit(1)
+                }
+            }
+        }
+        None => (),
+    }
+
     if let Some(path) = cli.path.as_deref() {
-        match run_file(path) {
+        match run_file(path, cli.strict_shadowing, cli.trap_nan, cli.print_result) {
             Ok(_) => exit(0),
             Err(m) => {
                 println!("{}", m);
@@ -68,7 +109,7 @@ fn main() {
         }
     }
 
-    match repl::start_session() {
+    match repl::start_session(cli.strict_shadowing, cli.trap_nan) {
         Ok(_) => (),
         Err(m) => {
             println!("{}", m);
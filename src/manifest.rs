@@ -0,0 +1,185 @@
+use super::frontend::ast;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "jarlang.toml";
+const DEFAULT_ENTRY: &str = "main.jrl";
+
+/// A package described by a `jarlang.toml` file:
+///
+/// ```toml
+/// [package]
+/// name = "app"
+/// entry = "src/main.jrl"
+/// shadowing = "strict"
+/// mutability = "rust"
+///
+/// [dependencies]
+/// collections = { path = "../collections" }
+/// ```
+///
+/// Only the subset of TOML used above is understood. Paths are resolved
+/// against the directory holding the manifest.
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub name: String,
+    pub entry: PathBuf,
+    /// Package names along with the path to their directory or module file
+    pub dependencies: Vec<(String, PathBuf)>,
+    /// Set by `shadowing = "strict"`, disallows redeclaring a variable in
+    /// the same scope instead of shadowing it
+    pub strict_shadowing: bool,
+    /// The mutability of files without a `#![mutability]` attribute
+    pub mutability: ast::Mutability,
+}
+
+enum Section {
+    None,
+    Package,
+    Dependencies,
+}
+
+impl Manifest {
+    /// Reads the manifest in the given package directory.
+    pub fn read(directory: &Path) -> Result<Self, String> {
+        let path = directory.join(MANIFEST_FILE);
+        match fs::read_to_string(&path) {
+            Ok(source) => Manifest::parse(&source, directory)
+                .map_err(|m| format!("{}: {}", path.display(), m)),
+            Err(_) => Err(format!("Failed to read {}", path.display())),
+        }
+    }
+
+    fn parse(source: &str, directory: &Path) -> Result<Self, String> {
+        let mut section = Section::None;
+        let mut name: Option<String> = None;
+        let mut entry: Option<String> = None;
+        let mut strict_shadowing = false;
+        let mut mutability = ast::Mutability::default();
+        let mut dependencies: Vec<(String, PathBuf)> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line_error = |message: String| format!("line {}: {}", index + 1, message);
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                section = match line {
+                    "[package]" => Section::Package,
+                    "[dependencies]" => Section::Dependencies,
+                    _ => return Err(line_error(format!("Unknown section {}", line))),
+                };
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(line_error(format!("Expected key = value, got: {}", line))),
+            };
+
+            match section {
+                Section::None => {
+                    return Err(line_error(format!(
+                        "Key {} must be inside a [package] or [dependencies] section",
+                        key
+                    )))
+                }
+                Section::Package => {
+                    let value = parse_string(value).map_err(line_error)?;
+                    match key {
+                        "name" => name = Some(value),
+                        "entry" => entry = Some(value),
+                        "mutability" => {
+                            mutability = ast::Mutability::parse(&value).map_err(line_error)?
+                        }
+                        "shadowing" => {
+                            strict_shadowing = match value.as_str() {
+                                "strict" => true,
+                                "allow" => false,
+                                _ => {
+                                    return Err(line_error(format!(
+                                        "Expected shadowing to be \"allow\" or \"strict\", got: {}",
+                                        value
+                                    )))
+                                }
+                            }
+                        }
+                        _ => return Err(line_error(format!("Unknown package key {}", key))),
+                    }
+                }
+                Section::Dependencies => {
+                    let path = parse_dependency(value).map_err(line_error)?;
+                    if dependencies.iter().any(|(dependency, _)| dependency == key) {
+                        return Err(line_error(format!("Duplicate dependency {}", key)));
+                    }
+                    dependencies.push((key.to_string(), directory.join(path)));
+                }
+            }
+        }
+
+        let name = match name {
+            Some(name) => name,
+            None => return Err("Missing name in the [package] section".to_string()),
+        };
+
+        Ok(Manifest {
+            name,
+            entry: directory.join(entry.unwrap_or(DEFAULT_ENTRY.to_string())),
+            dependencies,
+            strict_shadowing,
+            mutability,
+        })
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (index, character) in line.char_indices() {
+        match character {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => (),
+        }
+    }
+    line
+}
+
+fn parse_string(value: &str) -> Result<String, String> {
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(string) if !string.contains('"') => Ok(string.to_string()),
+        _ => Err(format!("Expected a string value, got: {}", value)),
+    }
+}
+
+/// Dependencies are either a path string or an inline table with a path key.
+fn parse_dependency(value: &str) -> Result<String, String> {
+    if value.starts_with('"') {
+        return parse_string(value);
+    }
+
+    let table = match value
+        .strip_prefix('{')
+        .and_then(|value| value.strip_suffix('}'))
+    {
+        Some(table) => table.trim(),
+        None => {
+            return Err(format!(
+                "Expected a path or {{ path = \"...\" }}, got: {}",
+                value
+            ))
+        }
+    };
+
+    match table.split_once('=') {
+        Some((key, value)) if key.trim() == "path" => parse_string(value.trim()),
+        _ => Err(format!(
+            "Only local path dependencies are supported, got: {{ {} }}",
+            table
+        )),
+    }
+}
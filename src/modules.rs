@@ -0,0 +1,698 @@
+use super::analysis::{exhaustiveness::ExhaustivenessChecker, typecheck::TypeChecker};
+use super::frontend::{ast, parser};
+use super::manifest::{Manifest, MANIFEST_FILE};
+use super::runtime::{
+    environment::Environment,
+    interpreter,
+    values::{self, ValueType},
+};
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// A value, enum or struct made available to importing modules with
+/// `export`.
+#[derive(Clone)]
+enum Export {
+    Variable(values::RuntimeValue),
+    /// A variable known only by its type, exported by a module that has been
+    /// checked but not evaluated. `None` when the type is only known at
+    /// runtime.
+    Declared(Option<ValueType>),
+    Enum(ast::EnumDeclarationStatement),
+    Struct(Rc<ast::StructDeclarationStatement>),
+    TypeAlias(ast::TypeExpr),
+    Newtype(ast::NewtypeDeclarationStatement),
+}
+
+struct Module {
+    exports: Vec<(String, Export)>,
+}
+
+impl Module {
+    fn lookup(&self, name: &str) -> Option<&Export> {
+        self.exports
+            .iter()
+            .find(|(identifier, _)| identifier == name)
+            .map(|(_, export)| export)
+    }
+}
+
+/// Loads modules from disk, evaluating each file once in its own global
+/// environment no matter how many times it is imported. Every module a
+/// program imports is checked before any of them is evaluated, so type errors
+/// are reported before side effects happen.
+///
+/// Specifiers starting with `./`, `../` or `/` are paths relative to the
+/// importing module. Any other specifier names a package, looked up in the
+/// dependencies of the manifest being run and then in the directories listed
+/// by the `JARLANG_PATH` environment variable.
+pub struct ModuleLoader {
+    modules: HashMap<PathBuf, Module>,
+    /// The exports of checked modules, with variables known by their type
+    interfaces: HashMap<PathBuf, Module>,
+    /// The chain of modules currently being loaded, used to detect cycles
+    loading: Vec<PathBuf>,
+    /// Package names along with the module file they resolve to
+    packages: HashMap<String, PathBuf>,
+    /// Whether modules are loaded with same-scope shadowing disallowed
+    strict_shadowing: bool,
+    /// Whether modules are run with float operations producing NaN trapped
+    trap_nan: bool,
+    /// The mutability of modules without a `#![mutability]` attribute
+    mutability: ast::Mutability,
+    /// The directories of dependency packages along with the mutability set
+    /// by their manifest, which applies to their modules instead
+    package_mutability: Vec<(PathBuf, ast::Mutability)>,
+}
+
+impl ModuleLoader {
+    pub fn new() -> Self {
+        ModuleLoader {
+            modules: HashMap::new(),
+            interfaces: HashMap::new(),
+            loading: Vec::new(),
+            packages: HashMap::new(),
+            strict_shadowing: false,
+            trap_nan: false,
+            mutability: ast::Mutability::default(),
+            package_mutability: Vec::new(),
+        }
+    }
+
+    pub fn set_mutability(&mut self, mutability: ast::Mutability) {
+        self.mutability = mutability;
+    }
+
+    pub fn set_strict_shadowing(&mut self, strict_shadowing: bool) {
+        self.strict_shadowing = strict_shadowing;
+    }
+
+    pub fn set_trap_nan(&mut self, trap_nan: bool) {
+        self.trap_nan = trap_nan;
+    }
+
+    /// Makes the dependencies of a manifest, and their own dependencies,
+    /// importable by name.
+    pub fn add_dependencies(&mut self, manifest: &Manifest) -> Result<(), String> {
+        for (name, path) in &manifest.dependencies {
+            if self.packages.contains_key(name) {
+                continue;
+            }
+            let entry = self
+                .package_entry(path)
+                .map_err(|m| format!("Dependency {} of {}: {}", name, manifest.name, m))?;
+            self.packages.insert(name.to_owned(), entry);
+        }
+
+        Ok(())
+    }
+
+    /// The module file of a package, given either the module file itself or
+    /// a directory holding a manifest.
+    fn package_entry(&mut self, path: &Path) -> Result<PathBuf, String> {
+        if !path.is_dir() {
+            return canonicalize(path);
+        }
+
+        let manifest = Manifest::read(path)?;
+        self.package_mutability
+            .push((canonicalize(path)?, manifest.mutability));
+        self.add_dependencies(&manifest)?;
+        canonicalize(&manifest.entry)
+    }
+
+    /// The mutability of a module without a `#![mutability]` attribute,
+    /// taken from the manifest of the innermost package holding it.
+    fn module_mutability(&self, path: &Path) -> ast::Mutability {
+        self.package_mutability
+            .iter()
+            .filter(|(directory, _)| path.starts_with(directory))
+            .max_by_key(|(directory, _)| directory.components().count())
+            .map(|(_, mutability)| *mutability)
+            .unwrap_or(self.mutability)
+    }
+
+    fn resolve_specifier(&mut self, specifier: &str, directory: &Path) -> Result<PathBuf, String> {
+        if specifier.starts_with("./") || specifier.starts_with("../") || specifier.starts_with('/')
+        {
+            return canonicalize(&directory.join(specifier));
+        }
+
+        if let Some(entry) = self.packages.get(specifier) {
+            return Ok(entry.to_owned());
+        }
+
+        if let Some(search_path) = env::var_os("JARLANG_PATH") {
+            for search_directory in env::split_paths(&search_path) {
+                let module = search_directory.join(format!("{}.jrl", specifier));
+                if module.is_file() {
+                    return canonicalize(&module);
+                }
+                let package = search_directory.join(specifier);
+                if package.join(MANIFEST_FILE).is_file() {
+                    return self.package_entry(&package);
+                }
+            }
+        }
+
+        Err(format!(
+            "Cannot find module \"{}\" in the manifest dependencies or JARLANG_PATH",
+            specifier
+        ))
+    }
+
+    /// Checks the file at `path` and every module it imports, then
+    /// evaluates it as the entry module, returning the value of its last
+    /// statement.
+    pub fn run(&mut self, path: &Path) -> Result<values::RuntimeValue, String> {
+        let path = canonicalize(path)?;
+        self.check_module(&path)?;
+        self.evaluate_module(path)
+    }
+
+    /// Checks the file at `path` and the modules it imports without
+    /// evaluating any of them.
+    pub fn check(&mut self, path: &Path) -> Result<(), String> {
+        let path = canonicalize(path)?;
+        self.check_module(&path)
+    }
+
+    /// Checks and loads the modules imported by the top level of `program`
+    /// and binds the imported names in `environment`. Relative specifiers
+    /// are resolved against `directory`.
+    pub fn bind_imports(
+        &mut self,
+        program: &ast::Statement,
+        directory: &Path,
+        environment: &mut Environment,
+        checker: &mut ExhaustivenessChecker,
+        type_checker: &mut TypeChecker,
+    ) -> Result<(), String> {
+        for import_statement in import_statements(program) {
+            let path = self
+                .resolve_specifier(&import_statement.specifier, directory)
+                .map_err(|m| self.module_error(m))?;
+            self.check_module(&path)?;
+            if !self.modules.contains_key(&path) {
+                self.evaluate_module(path.to_owned())?;
+            }
+            bind_import(
+                &self.modules[&path],
+                import_statement,
+                environment,
+                checker,
+                type_checker,
+            )
+            .map_err(|m| self.module_error(m))?;
+        }
+
+        Ok(())
+    }
+
+    /// The error for importing a module that is still being loaded.
+    fn circular_import(&self, path: &Path) -> String {
+        let chain = self
+            .loading
+            .iter()
+            .map(PathBuf::as_path)
+            .skip_while(|loading| *loading != path)
+            .chain(std::iter::once(path))
+            .map(display_path)
+            .collect::<Vec<String>>();
+        format!("Circular import: {}", chain.join(" -> "))
+    }
+
+    /// Type checks a module against the exports of the modules it imports,
+    /// which are checked first, and remembers its own exports. Nothing is
+    /// evaluated apart from type declarations.
+    fn check_module(&mut self, path: &Path) -> Result<(), String> {
+        if self.interfaces.contains_key(path) {
+            return Ok(());
+        }
+        if self.loading.iter().any(|loading| loading == path) {
+            return Err(self.circular_import(path));
+        }
+
+        self.loading.push(path.to_owned());
+        let result = self.check_module_source(path);
+        self.loading.pop();
+
+        self.interfaces.insert(path.to_owned(), result?);
+        Ok(())
+    }
+
+    fn check_module_source(&mut self, path: &Path) -> Result<Module, String> {
+        let (program, mut environment) = self.prepare_module(path)?;
+
+        let mut checker = ExhaustivenessChecker::new();
+        let mut type_checker = TypeChecker::new();
+        let directory = path.parent().unwrap_or(Path::new("."));
+        for import_statement in import_statements(&program) {
+            let path = self
+                .resolve_specifier(&import_statement.specifier, directory)
+                .map_err(|m| self.module_error(m))?;
+            self.check_module(&path)?;
+            bind_import(
+                &self.interfaces[&path],
+                import_statement,
+                &mut environment,
+                &mut checker,
+                &mut type_checker,
+            )
+            .map_err(|m| self.module_error(m))?;
+        }
+
+        let errors: Vec<String> = [
+            checker.check_program(&program),
+            type_checker.check_program(&program, &environment),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+        if !errors.is_empty() {
+            return Err(self.module_error(errors.join("\n")));
+        }
+        for warning in type_checker.warnings() {
+            eprintln!("{}", self.module_error(warning.to_owned()));
+        }
+
+        // Type declarations are evaluated so their exports match the ones of
+        // the evaluated module
+        for statement in program_statements(&program) {
+            let declaration = match statement {
+                ast::Statement::Export(export_statement) => export_statement.declaration.as_ref(),
+                statement => statement,
+            };
+            if matches!(
+                declaration,
+                ast::Statement::EnumDeclaration(_)
+                    | ast::Statement::StructDeclaration(_)
+                    | ast::Statement::TypeAlias(_)
+                    | ast::Statement::NewtypeDeclaration(_)
+            ) {
+                interpreter::evaluate(declaration.to_owned(), &mut environment)
+                    .map_err(|error| self.module_error(error.to_string()))?;
+            }
+        }
+
+        Ok(module_exports(
+            export_statements(&program),
+            &mut environment,
+            |_, identifier| type_checker.global_type(identifier).map(Export::Declared),
+        ))
+    }
+
+    fn evaluate_module(&mut self, path: PathBuf) -> Result<values::RuntimeValue, String> {
+        if self.loading.contains(&path) {
+            return Err(self.circular_import(&path));
+        }
+
+        self.loading.push(path.to_owned());
+        let result = self.evaluate_module_source(&path);
+        self.loading.pop();
+
+        let (module, runtime_val) = result?;
+        self.modules.insert(path, module);
+
+        Ok(runtime_val)
+    }
+
+    /// Parses a module, returning its program along with the global
+    /// environment it runs in, before any imports are bound.
+    fn prepare_module(&mut self, path: &Path) -> Result<(ast::Statement, Environment), String> {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(_) => {
+                return Err(self.module_error(format!("Failed to read {}", display_path(path))))
+            }
+        };
+
+        let program = match parser::Parser::new().produce_ast(source.as_str()) {
+            Ok(program) => program,
+            Err(m) => return Err(self.module_error(m)),
+        };
+
+        let mut environment = Environment::create(None);
+        environment.set_strict_shadowing(self.strict_shadowing);
+        environment.set_trap_nan(self.trap_nan);
+        let mutability = match &program {
+            ast::Statement::Program(program) => program.mutability,
+            _ => None,
+        };
+        environment.set_mutability(mutability.unwrap_or(self.module_mutability(path)));
+        if let Err(m) = environment.init_global_scope() {
+            return Err(self.module_error(m));
+        }
+
+        Ok((program, environment))
+    }
+
+    fn evaluate_module_source(
+        &mut self,
+        path: &Path,
+    ) -> Result<(Module, values::RuntimeValue), String> {
+        let (program, mut environment) = self.prepare_module(path)?;
+        // The module and its imports have already been checked
+        let directory = path.parent().unwrap_or(Path::new("."));
+        self.bind_imports(
+            &program,
+            directory,
+            &mut environment,
+            &mut ExhaustivenessChecker::new(),
+            &mut TypeChecker::new(),
+        )?;
+
+        let export_statements = export_statements(&program);
+        let runtime_val = match interpreter::evaluate(program, &mut environment) {
+            Ok(runtime_val) => runtime_val,
+            Err(error) => return Err(self.module_error(error.to_string())),
+        };
+
+        // Exported functions keep running against the globals of this module
+        let globals = Rc::new(environment.to_owned());
+        let module = module_exports(
+            export_statements,
+            &mut environment,
+            |environment, identifier| match environment.lookup_variable(identifier.to_owned())? {
+                values::RuntimeValue::Function(mut function) if function.module.is_none() => {
+                    function.module = Some(globals.to_owned());
+                    Some(Export::Variable(function.as_raw()))
+                }
+                value => Some(Export::Variable(value)),
+            },
+        );
+
+        Ok((module, runtime_val))
+    }
+
+    /// Names the module an error was raised in along with the chain of
+    /// imports that led to it. Errors in the entry module are left as is.
+    fn module_error(&self, message: String) -> String {
+        if self.loading.len() <= 1 {
+            return message;
+        }
+
+        format!(
+            "{}\n  in module {} (imported by {})",
+            message,
+            display_path(&self.loading[self.loading.len() - 1]),
+            self.loading[..self.loading.len() - 1]
+                .iter()
+                .map(|path| display_path(path))
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        )
+    }
+}
+
+/// The export statements at the top level of a program.
+fn export_statements(program: &ast::Statement) -> Vec<ast::ExportStatement> {
+    program_statements(program)
+        .filter_map(|statement| match statement {
+            ast::Statement::Export(export_statement) => Some(export_statement.to_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The import statements at the top level of a program.
+fn import_statements(program: &ast::Statement) -> Vec<&ast::ImportStatement> {
+    program_statements(program)
+        .filter_map(|statement| match statement {
+            ast::Statement::Import(import_statement) => Some(import_statement),
+            _ => None,
+        })
+        .collect()
+}
+
+fn program_statements(program: &ast::Statement) -> impl Iterator<Item = &ast::Statement> {
+    let body = match program {
+        ast::Statement::Program(program) => program.body.as_slice(),
+        _ => &[],
+    };
+    body.iter().map(|located| &located.statement)
+}
+
+/// The exports of a module whose declarations have been evaluated in
+/// `environment`. Exported variables are given by `variable`, `None` when
+/// the module never declared them.
+fn module_exports(
+    export_statements: Vec<ast::ExportStatement>,
+    environment: &mut Environment,
+    variable: impl Fn(&mut Environment, &str) -> Option<Export>,
+) -> Module {
+    let mut module = Module {
+        exports: Vec::new(),
+    };
+    for export_statement in export_statements {
+        match export_statement.declaration.as_ref() {
+            // Declarations are exported with the type aliases of this
+            // module expanded
+            ast::Statement::EnumDeclaration(enum_declaration) => {
+                if let Some(declaration) = environment.lookup_enum(&enum_declaration.identifier) {
+                    module.exports.push((
+                        enum_declaration.identifier.to_owned(),
+                        Export::Enum(declaration.to_owned()),
+                    ));
+                }
+                continue;
+            }
+            ast::Statement::TypeAlias(type_alias) => {
+                if let Some(value_type) = environment.type_aliases().remove(&type_alias.identifier)
+                {
+                    module.exports.push((
+                        type_alias.identifier.to_owned(),
+                        Export::TypeAlias(value_type),
+                    ));
+                }
+                continue;
+            }
+            ast::Statement::NewtypeDeclaration(newtype_declaration) => {
+                if let Some(declaration) =
+                    environment.lookup_newtype(&newtype_declaration.identifier)
+                {
+                    module.exports.push((
+                        newtype_declaration.identifier.to_owned(),
+                        Export::Newtype(declaration.to_owned()),
+                    ));
+                }
+                continue;
+            }
+            ast::Statement::StructDeclaration(struct_declaration) => {
+                if let Some(declaration) = environment.lookup_struct(&struct_declaration.identifier)
+                {
+                    module.exports.push((
+                        struct_declaration.identifier.to_owned(),
+                        Export::Struct(declaration),
+                    ));
+                }
+                continue;
+            }
+            _ => (),
+        }
+        for identifier in export_statement.identifiers() {
+            if let Some(export) = variable(environment, &identifier) {
+                module.exports.push((identifier, export));
+            }
+        }
+    }
+
+    module
+}
+
+/// Binds the names given by an import statement to the exports of `module`.
+/// Variables known only by their type are declared to `type_checker`.
+fn bind_import(
+    module: &Module,
+    import_statement: &ast::ImportStatement,
+    environment: &mut Environment,
+    checker: &mut ExhaustivenessChecker,
+    type_checker: &mut TypeChecker,
+) -> Result<(), String> {
+    match &import_statement.bindings {
+        ast::ImportBindings::None => (),
+        ast::ImportBindings::Named(names) => {
+            for (name, alias) in names {
+                match (module.lookup(name), alias) {
+                    (Some(Export::Variable(value)), alias) => environment.declare_variable(
+                        alias.to_owned().unwrap_or(name.to_owned()),
+                        value.to_owned(),
+                        true,
+                    )?,
+                    (Some(Export::Declared(value_type)), alias) => type_checker.declare_import(
+                        alias.to_owned().unwrap_or(name.to_owned()),
+                        value_type.to_owned(),
+                    ),
+                    (Some(Export::Enum(enum_declaration)), None) => {
+                        environment.declare_enum(enum_declaration.to_owned())?;
+                        checker.register_enum(enum_declaration.to_owned());
+                    }
+                    (Some(Export::Enum(_)), Some(_)) => {
+                        return Err(format!(
+                            "Cannot rename the enum {} imported from \"{}\"",
+                            name, import_statement.specifier
+                        ))
+                    }
+                    (Some(Export::Struct(struct_declaration)), None) => {
+                        environment.declare_struct(struct_declaration.to_owned())?
+                    }
+                    (Some(Export::Struct(_)), Some(_)) => {
+                        return Err(format!(
+                            "Cannot rename the struct {} imported from \"{}\"",
+                            name, import_statement.specifier
+                        ))
+                    }
+                    (Some(Export::TypeAlias(value_type)), alias) => environment
+                        .declare_type_alias(
+                            alias.to_owned().unwrap_or(name.to_owned()),
+                            value_type.to_owned(),
+                        )?,
+                    (Some(Export::Newtype(newtype_declaration)), None) => {
+                        environment.declare_newtype(newtype_declaration.to_owned())?
+                    }
+                    (Some(Export::Newtype(_)), Some(_)) => {
+                        return Err(format!(
+                            "Cannot rename the newtype {} imported from \"{}\"",
+                            name, import_statement.specifier
+                        ))
+                    }
+                    (None, _) => {
+                        return Err(format!(
+                            "Module \"{}\" has no export named {}",
+                            import_statement.specifier, name
+                        ))
+                    }
+                }
+            }
+        }
+        // Only variables are reachable through a namespace, types have to
+        // be imported by name
+        ast::ImportBindings::Namespace(namespace) => {
+            let properties = module
+                .exports
+                .iter()
+                .filter_map(|(identifier, export)| match export {
+                    Export::Variable(value) => Some((identifier.to_owned(), value.to_owned())),
+                    Export::Declared(_)
+                    | Export::Enum(_)
+                    | Export::Struct(_)
+                    | Export::TypeAlias(_)
+                    | Export::Newtype(_) => None,
+                })
+                .collect();
+            environment.declare_variable(
+                namespace.to_owned(),
+                values::ObjectValue::create(properties).as_raw(),
+                true,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf, String> {
+    match path.canonicalize() {
+        Ok(path) => Ok(path),
+        Err(_) => Err(format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Module paths relative to the working directory where possible.
+fn display_path(path: &Path) -> String {
+    match std::env::current_dir() {
+        Ok(current_dir) => match path.strip_prefix(current_dir) {
+            Ok(relative) => relative.display().to_string(),
+            Err(_) => path.display().to_string(),
+        },
+        Err(_) => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes `files` to a fresh directory, returning its path.
+    fn write_modules(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let directory = env::temp_dir().join(format!("jarlang-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        for (file, source) in files {
+            fs::write(directory.join(file), source).unwrap();
+        }
+        directory
+    }
+
+    #[test]
+    fn runs_imported_values_enums_and_namespaces() {
+        let directory = write_modules(
+            "imports",
+            &[
+                (
+                    "util.jrl",
+                    "export const base = 10;\nexport enum Shape { Circle(i32), Square(i32) }\nexport const doubled = base * 2;\n",
+                ),
+                (
+                    "main.jrl",
+                    "import { base, doubled as d, Shape } from \"./util.jrl\";\nimport * as util from \"./util.jrl\";\nlet area = match Shape::Circle(3) { Shape::Circle(r) => r * r, Shape::Square(w) => w };\n(base, d, util.doubled, area)\n",
+                ),
+            ],
+        );
+        let result = ModuleLoader::new().run(&directory.join("main.jrl"));
+        assert_eq!(result.unwrap().to_string(), "(10, 20, 20, 9)");
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn reports_circular_imports_and_missing_exports() {
+        let directory = write_modules(
+            "errors",
+            &[
+                (
+                    "a.jrl",
+                    "import { y } from \"./b.jrl\";\nexport const x = 1;\n",
+                ),
+                (
+                    "b.jrl",
+                    "import { x } from \"./a.jrl\";\nexport const y = 2;\n",
+                ),
+                ("c.jrl", "export const z = 3;\n"),
+                ("missing.jrl", "import { nope } from \"./c.jrl\";\n"),
+            ],
+        );
+        let circular = ModuleLoader::new()
+            .run(&directory.join("a.jrl"))
+            .unwrap_err();
+        let a = display_path(&canonicalize(&directory.join("a.jrl")).unwrap());
+        let b = display_path(&canonicalize(&directory.join("b.jrl")).unwrap());
+        assert!(circular.contains(&format!("Circular import: {} -> {} -> {}", a, b, a)));
+        let missing = ModuleLoader::new().check(&directory.join("missing.jrl"));
+        assert!(missing
+            .unwrap_err()
+            .contains("Module \"./c.jrl\" has no export named nope"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn checks_imported_modules_before_running_them() {
+        let directory = write_modules(
+            "check",
+            &[
+                ("typed.jrl", "export const n = 1;\nlet s: i32 = \"a\";\n"),
+                ("main.jrl", "import { n } from \"./typed.jrl\";\nn\n"),
+            ],
+        );
+        let error = ModuleLoader::new()
+            .check(&directory.join("main.jrl"))
+            .unwrap_err();
+        assert!(error.contains("Incorrect value for s, expected: i32, got: str"));
+        let typed = display_path(&canonicalize(&directory.join("typed.jrl")).unwrap());
+        assert!(error.contains(&format!("in module {}", typed)));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,16 +1,26 @@
-use super::frontend::parser;
+use super::analysis::{exhaustiveness::ExhaustivenessChecker, typecheck::TypeChecker};
+use super::frontend::{ast, parser};
+use super::modules::ModuleLoader;
 use super::runtime::environment::Environment;
 use super::runtime::interpreter;
 
 use std::borrow::BorrowMut;
 use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
 use std::process::exit;
 
-pub fn start_session() -> Result<(), String> {
+pub fn start_session(strict_shadowing: bool, trap_nan: bool) -> Result<(), String> {
     println!("REPL v0.1");
 
     let mut parser = parser::Parser::new();
     let mut environment = Environment::create(None);
+    let mut exhaustiveness_checker = ExhaustivenessChecker::new();
+    let mut type_checker = TypeChecker::new();
+    let mut module_loader = ModuleLoader::new();
+    module_loader.set_strict_shadowing(strict_shadowing);
+    environment.set_strict_shadowing(strict_shadowing);
+    environment.set_trap_nan(trap_nan);
+    let current_dir = std::env::current_dir().unwrap_or(PathBuf::from("."));
 
     match environment.init_global_scope() {
         Ok(_) => (),
@@ -48,8 +58,53 @@ pub fn start_session() -> Result<(), String> {
             }
         };
 
+        if let ast::Statement::Program(ast::ProgramStatement {
+            mutability: Some(mutability),
+            ..
+        }) = &program
+        {
+            environment.set_mutability(*mutability);
+        }
+
+        match module_loader.bind_imports(
+            &program,
+            &current_dir,
+            environment.borrow_mut(),
+            &mut exhaustiveness_checker,
+            &mut type_checker,
+        ) {
+            Ok(_) => (),
+            Err(m) => {
+                println!("{}", m);
+                continue;
+            }
+        }
+
+        match exhaustiveness_checker.check_program(&program) {
+            Ok(_) => (),
+            Err(m) => {
+                println!("{}", m);
+                continue;
+            }
+        }
+
+        match type_checker.check_program(&program, &environment) {
+            Ok(_) => (),
+            Err(m) => {
+                println!("{}", m);
+                continue;
+            }
+        }
+        for warning in type_checker.warnings() {
+            eprintln!("{}", warning);
+        }
+
         match interpreter::evaluate(program, environment.borrow_mut()) {
-            Ok(runtime_val) => println!("{:#?}", runtime_val),
+            Ok(runtime_val) => println!(
+                "{}: {}",
+                runtime_val.to_debug_string(),
+                runtime_val.to_owned().as_value_type().as_string()
+            ),
             Err(m) => {
                 println!("{}", m);
                 exit(1)
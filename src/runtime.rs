@@ -1,5 +1,10 @@
+pub mod collections;
+pub mod decimal;
 pub mod environment;
+pub mod errors;
 pub mod evaluation;
+pub mod generics;
 pub mod helpers;
 pub mod interpreter;
+pub mod natives;
 pub mod values;
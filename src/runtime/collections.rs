@@ -0,0 +1,206 @@
+use num_bigint::BigInt;
+use std::collections::HashMap;
+
+use super::helpers;
+use super::values::{NullValue, RuntimeValue};
+
+/// The hashable form of a key. Integers are keyed by their value rather than
+/// their type, so a `u8` key can be looked up with an `i32`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Integer(BigInt),
+    String(String),
+    Character(char),
+    Boolean(bool),
+    Tuple(Vec<Key>),
+}
+
+impl Key {
+    fn create(runtime_val: &RuntimeValue) -> Result<Self, String> {
+        match runtime_val {
+            _ if helpers::runtime_value_is_integer(runtime_val) => {
+                Ok(Key::Integer(runtime_val.to_owned().as_bigint()?.value))
+            }
+            RuntimeValue::String(string) => Ok(Key::String(string.value.to_owned())),
+            RuntimeValue::Character(character) => Ok(Key::Character(character.value)),
+            RuntimeValue::Boolean(boolean) => Ok(Key::Boolean(boolean.value)),
+            RuntimeValue::Tuple(tuple) => Ok(Key::Tuple(
+                tuple
+                    .value
+                    .iter()
+                    .map(Key::create)
+                    .collect::<Result<Vec<Key>, String>>()?,
+            )),
+            _ => Err(format!(
+                "A value of type {} cannot be used as a key, keys must be integers, str, char, bool or tuples of them",
+                runtime_val.to_owned().as_value_type().as_string()
+            )),
+        }
+    }
+}
+
+/// Entries keyed by runtime values, iterated in the order their keys were
+/// first inserted.
+#[derive(Debug, Clone, Default)]
+pub struct OrderedMap {
+    entries: Vec<(RuntimeValue, RuntimeValue)>,
+    indexes: HashMap<Key, usize>,
+}
+
+impl OrderedMap {
+    pub fn create() -> Self {
+        OrderedMap::default()
+    }
+
+    pub fn from_entries(entries: Vec<(RuntimeValue, RuntimeValue)>) -> Result<Self, String> {
+        let mut map = OrderedMap::create();
+        for (key, value) in entries {
+            map.insert(key, value)?;
+        }
+        Ok(map)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Replacing the value of a key keeps its place in the iteration order
+    /// and gives back the value it replaced.
+    pub fn insert(
+        &mut self,
+        key: RuntimeValue,
+        value: RuntimeValue,
+    ) -> Result<Option<RuntimeValue>, String> {
+        let hashed = Key::create(&key)?;
+        match self.indexes.get(&hashed) {
+            Some(&index) => Ok(Some(std::mem::replace(&mut self.entries[index].1, value))),
+            None => {
+                self.indexes.insert(hashed, self.entries.len());
+                self.entries.push((key, value));
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn get(&self, key: &RuntimeValue) -> Result<Option<&RuntimeValue>, String> {
+        Ok(self
+            .indexes
+            .get(&Key::create(key)?)
+            .map(|&index| &self.entries[index].1))
+    }
+
+    pub fn contains_key(&self, key: &RuntimeValue) -> Result<bool, String> {
+        Ok(self.indexes.contains_key(&Key::create(key)?))
+    }
+
+    pub fn remove(&mut self, key: &RuntimeValue) -> Result<Option<RuntimeValue>, String> {
+        let index = match self.indexes.remove(&Key::create(key)?) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let (_, value) = self.entries.remove(index);
+        // Entries after the removed one have moved back a place
+        for later in self.indexes.values_mut() {
+            if *later > index {
+                *later -= 1;
+            }
+        }
+        Ok(Some(value))
+    }
+
+    pub fn entries(&self) -> &[(RuntimeValue, RuntimeValue)] {
+        &self.entries
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &RuntimeValue> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &RuntimeValue> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
+
+/// Values that can be used as map keys, iterated in the order they were
+/// first inserted.
+#[derive(Debug, Clone, Default)]
+pub struct OrderedSet {
+    map: OrderedMap,
+}
+
+impl OrderedSet {
+    pub fn create() -> Self {
+        OrderedSet::default()
+    }
+
+    pub fn from_values(values: Vec<RuntimeValue>) -> Result<Self, String> {
+        let mut set = OrderedSet::create();
+        for value in values {
+            set.insert(value)?;
+        }
+        Ok(set)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Inserting a value that is already present keeps the one inserted
+    /// first, only a value that was not present gives `true`.
+    pub fn insert(&mut self, value: RuntimeValue) -> Result<bool, String> {
+        if self.map.contains_key(&value)? {
+            return Ok(false);
+        }
+        self.map.insert(value, NullValue::create().as_raw())?;
+        Ok(true)
+    }
+
+    pub fn contains(&self, value: &RuntimeValue) -> Result<bool, String> {
+        self.map.contains_key(value)
+    }
+
+    pub fn remove(&mut self, value: &RuntimeValue) -> Result<bool, String> {
+        Ok(self.map.remove(value)?.is_some())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &RuntimeValue> {
+        self.map.keys()
+    }
+
+    /// The values of both sets, those of `self` first.
+    pub fn union(&self, other: &OrderedSet) -> Result<Self, String> {
+        let mut set = self.to_owned();
+        for value in other.values() {
+            set.insert(value.to_owned())?;
+        }
+        Ok(set)
+    }
+
+    /// The values of `self` that are also in `other`, in the order of `self`.
+    pub fn intersection(&self, other: &OrderedSet) -> Result<Self, String> {
+        self.filter(|value| other.contains(value))
+    }
+
+    /// The values of `self` that are not in `other`, in the order of `self`.
+    pub fn difference(&self, other: &OrderedSet) -> Result<Self, String> {
+        self.filter(|value| Ok(!other.contains(value)?))
+    }
+
+    fn filter(&self, keep: impl Fn(&RuntimeValue) -> Result<bool, String>) -> Result<Self, String> {
+        let mut set = OrderedSet::create();
+        for value in self.values() {
+            if keep(value)? {
+                set.insert(value.to_owned())?;
+            }
+        }
+        Ok(set)
+    }
+}
@@ -0,0 +1,372 @@
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{Float, One, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// How a decimal is rounded when digits are dropped from it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundingMode {
+    /// Ties go to the even neighbour, the default as it does not bias sums
+    HalfEven,
+    /// Ties go away from zero
+    HalfUp,
+    /// Ties go towards zero
+    HalfDown,
+    /// Away from zero
+    Up,
+    /// Towards zero
+    Down,
+    Ceiling,
+    Floor,
+}
+
+impl RoundingMode {
+    pub fn parse(mode: &str) -> Result<Self, String> {
+        match mode {
+            "half_even" => Ok(RoundingMode::HalfEven),
+            "half_up" => Ok(RoundingMode::HalfUp),
+            "half_down" => Ok(RoundingMode::HalfDown),
+            "up" => Ok(RoundingMode::Up),
+            "down" => Ok(RoundingMode::Down),
+            "ceiling" => Ok(RoundingMode::Ceiling),
+            "floor" => Ok(RoundingMode::Floor),
+            _ => Err(format!(
+                "Expected rounding mode to be one of \"half_even\", \"half_up\", \"half_down\", \"up\", \"down\", \"ceiling\" or \"floor\", got: {}",
+                mode
+            )),
+        }
+    }
+}
+
+/// An exact base 10 number, stored as an integer along with how many of its
+/// digits follow the decimal point. The scale is kept as written, so `12.50d`
+/// and `12.5d` are equal but display differently.
+#[derive(Debug, Clone)]
+pub struct Decimal {
+    unscaled: BigInt,
+    scale: u32,
+}
+
+fn power_of_ten(exponent: u32) -> BigInt {
+    BigInt::from(10).pow(exponent)
+}
+
+/// Divides two integers, rounding the quotient with `mode`.
+fn divide_rounded(numerator: &BigInt, denominator: &BigInt, mode: RoundingMode) -> BigInt {
+    let (quotient, remainder) = numerator.div_rem(denominator);
+    if remainder.is_zero() {
+        return quotient;
+    }
+
+    let negative = (numerator.sign() == Sign::Minus) != (denominator.sign() == Sign::Minus);
+    let away_from_zero = match negative {
+        true => &quotient - 1,
+        false => &quotient + 1,
+    };
+    // How the dropped part compares to a half
+    let half = (remainder.abs() * BigInt::from(2)).cmp(&denominator.abs());
+
+    let round_away = match mode {
+        RoundingMode::Down => false,
+        RoundingMode::Up => true,
+        RoundingMode::Ceiling => !negative,
+        RoundingMode::Floor => negative,
+        RoundingMode::HalfUp => half.is_ge(),
+        RoundingMode::HalfDown => half.is_gt(),
+        RoundingMode::HalfEven => half.is_gt() || (half.is_eq() && quotient.is_odd()),
+    };
+    match round_away {
+        true => away_from_zero,
+        false => quotient,
+    }
+}
+
+impl Decimal {
+    pub fn create(unscaled: BigInt, scale: u32) -> Self {
+        Decimal { unscaled, scale }
+    }
+
+    pub fn from_integer(value: BigInt) -> Self {
+        Decimal::create(value, 0)
+    }
+
+    /// Converts a float through the shortest representation of its own
+    /// type, so `0.1` is `0.1` rather than the binary value closest to it,
+    /// whether it is an `f32` or an `f64`.
+    pub fn from_float<T: Float + fmt::Display>(value: T) -> Result<Self, String> {
+        if !value.is_finite() {
+            return Err(format!("Cannot represent {} as a decimal", value));
+        }
+        Decimal::parse(&value.to_string())
+    }
+
+    /// Parses text such as `12`, `-0.50` or `3.`, keeping every digit
+    /// written after the decimal point.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid decimal literal: {}", text);
+        let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let digits = integer.strip_prefix(['-', '+']).unwrap_or(integer);
+        if (digits.is_empty() && fraction.is_empty())
+            || !digits.chars().all(|digit| digit.is_ascii_digit())
+            || !fraction.chars().all(|digit| digit.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let unscaled = format!("{}{}", integer, fraction)
+            .parse::<BigInt>()
+            .map_err(|_| invalid())?;
+        Ok(Decimal::create(unscaled, fraction.len() as u32))
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.unscaled.is_zero()
+    }
+
+    pub fn abs(&self) -> Self {
+        Decimal::create(self.unscaled.abs(), self.scale)
+    }
+
+    /// Gives the decimal `scale` digits after the decimal point, rounding
+    /// with `mode` when digits are dropped.
+    pub fn rescale(&self, scale: u32, mode: RoundingMode) -> Self {
+        match scale.cmp(&self.scale) {
+            Ordering::Equal => self.to_owned(),
+            Ordering::Greater => {
+                Decimal::create(&self.unscaled * power_of_ten(scale - self.scale), scale)
+            }
+            Ordering::Less => Decimal::create(
+                divide_rounded(&self.unscaled, &power_of_ten(self.scale - scale), mode),
+                scale,
+            ),
+        }
+    }
+
+    /// Both unscaled values at the larger of the two scales.
+    fn aligned(&self, other: &Decimal) -> (BigInt, BigInt, u32) {
+        let scale = self.scale.max(other.scale);
+        (
+            self.rescale(scale, RoundingMode::Down).unscaled,
+            other.rescale(scale, RoundingMode::Down).unscaled,
+            scale,
+        )
+    }
+
+    pub fn add(&self, other: &Decimal) -> Self {
+        let (lhs, rhs, scale) = self.aligned(other);
+        Decimal::create(lhs + rhs, scale)
+    }
+
+    pub fn sub(&self, other: &Decimal) -> Self {
+        let (lhs, rhs, scale) = self.aligned(other);
+        Decimal::create(lhs - rhs, scale)
+    }
+
+    /// The product keeps every digit, its scale is the sum of both scales.
+    pub fn mul(&self, other: &Decimal) -> Self {
+        Decimal::create(&self.unscaled * &other.unscaled, self.scale + other.scale)
+    }
+
+    /// Divides to `scale` digits after the decimal point. The divisor must
+    /// not be zero.
+    pub fn div(&self, other: &Decimal, scale: u32, mode: RoundingMode) -> Self {
+        let numerator = &self.unscaled * power_of_ten(other.scale + scale);
+        let denominator = &other.unscaled * power_of_ten(self.scale);
+        Decimal::create(divide_rounded(&numerator, &denominator, mode), scale)
+    }
+
+    /// The exact quotient, at the smallest scale no less than either
+    /// operand's that holds every digit of it. `None` when the quotient has
+    /// no end, such as one third. The divisor must not be zero.
+    pub fn div_exact(&self, other: &Decimal) -> Option<Self> {
+        let numerator = &self.unscaled * power_of_ten(other.scale);
+        let denominator = &other.unscaled * power_of_ten(self.scale);
+        // A fraction in lowest terms ends when its denominator only has
+        // factors of 2 and 5, it then needs as many digits as the larger
+        // count of either
+        let mut reduced = (&denominator / numerator.gcd(&denominator)).abs();
+        let mut digits = [0u32; 2];
+        for (count, factor) in digits.iter_mut().zip([2u32, 5]) {
+            while (&reduced % factor).is_zero() {
+                reduced /= factor;
+                *count += 1;
+            }
+        }
+        if !reduced.is_one() {
+            return None;
+        }
+
+        let scale = digits[0].max(digits[1]).max(self.scale).max(other.scale);
+        Some(self.div(other, scale, RoundingMode::Down))
+    }
+
+    /// The remainder of truncating division, which is always exact. The
+    /// divisor must not be zero.
+    pub fn rem(&self, other: &Decimal) -> Self {
+        let (lhs, rhs, scale) = self.aligned(other);
+        Decimal::create(lhs % rhs, scale)
+    }
+
+    pub fn pow(&self, exponent: u32) -> Self {
+        Decimal::create(self.unscaled.pow(exponent), self.scale * exponent)
+    }
+
+    /// The integer value of the decimal, erroring when it has a fractional
+    /// part.
+    pub fn to_integer(&self) -> Result<BigInt, String> {
+        let integer = self.rescale(0, RoundingMode::Down);
+        match integer.cmp(self) {
+            Ordering::Equal => Ok(integer.unscaled),
+            _ => Err(format!(
+                "Cannot convert {} into an integer without losing its fractional part, round it first",
+                self
+            )),
+        }
+    }
+
+    pub fn to_f64(&self) -> Option<f64> {
+        self.to_string().parse::<f64>().ok()
+    }
+
+    pub fn to_f32(&self) -> Option<f32> {
+        self.to_f64().and_then(|float| float.to_f32())
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (lhs, rhs, _) = self.aligned(other);
+        lhs.cmp(&rhs)
+    }
+}
+
+/// Writes every digit of the scale, including trailing zeros.
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.unscaled.abs().to_string();
+        let sign = match self.unscaled.sign() {
+            Sign::Minus => "-",
+            _ => "",
+        };
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(text: &str) -> Decimal {
+        Decimal::parse(text).unwrap()
+    }
+
+    fn rounded(text: &str, mode: RoundingMode) -> String {
+        decimal(text).rescale(0, mode).to_string()
+    }
+
+    #[test]
+    fn rounds_with_every_mode() {
+        let cases = [
+            (RoundingMode::HalfEven, ["2", "2", "3", "-2", "-2", "-3"]),
+            (RoundingMode::HalfUp, ["3", "2", "3", "-3", "-2", "-3"]),
+            (RoundingMode::HalfDown, ["2", "2", "3", "-2", "-2", "-3"]),
+            (RoundingMode::Up, ["3", "3", "3", "-3", "-3", "-3"]),
+            (RoundingMode::Down, ["2", "2", "2", "-2", "-2", "-2"]),
+            (RoundingMode::Ceiling, ["3", "3", "3", "-2", "-2", "-2"]),
+            (RoundingMode::Floor, ["2", "2", "2", "-3", "-3", "-3"]),
+        ];
+        for (mode, expected) in cases {
+            let inputs = ["2.5", "2.1", "2.9", "-2.5", "-2.1", "-2.9"];
+            for (input, expected) in inputs.iter().zip(expected) {
+                assert_eq!(rounded(input, mode), expected, "{} with {:?}", input, mode);
+            }
+        }
+    }
+
+    #[test]
+    fn half_even_rounds_ties_to_the_even_neighbour() {
+        assert_eq!(rounded("3.5", RoundingMode::HalfEven), "4");
+        assert_eq!(rounded("-3.5", RoundingMode::HalfEven), "-4");
+        assert_eq!(
+            decimal("1.005")
+                .rescale(2, RoundingMode::HalfEven)
+                .to_string(),
+            "1.00"
+        );
+    }
+
+    #[test]
+    fn displays_every_digit_of_the_scale() {
+        assert_eq!(decimal("12.50").to_string(), "12.50");
+        assert_eq!(decimal("0.05").to_string(), "0.05");
+        assert_eq!(decimal("-0.05").to_string(), "-0.05");
+        assert_eq!(decimal("-3").to_string(), "-3");
+        assert_eq!(
+            decimal("7").rescale(3, RoundingMode::Down).to_string(),
+            "7.000"
+        );
+        assert_eq!(decimal("12.50"), decimal("12.5"));
+    }
+
+    #[test]
+    fn keeps_scales_through_arithmetic() {
+        assert_eq!(decimal("1.10").add(&decimal("2.2")).to_string(), "3.30");
+        assert_eq!(decimal("1.5").sub(&decimal("4")).to_string(), "-2.5");
+        assert_eq!(decimal("1.5").mul(&decimal("-0.20")).to_string(), "-0.300");
+        assert_eq!(decimal("-7.5").rem(&decimal("2")).to_string(), "-1.5");
+    }
+
+    #[test]
+    fn divides_exactly_or_not_at_all() {
+        let quotient = |lhs: &str, rhs: &str| {
+            decimal(lhs)
+                .div_exact(&decimal(rhs))
+                .map(|quotient| quotient.to_string())
+        };
+        assert_eq!(quotient("10", "4").as_deref(), Some("2.5"));
+        assert_eq!(quotient("-1", "8").as_deref(), Some("-0.125"));
+        assert_eq!(quotient("6.00", "2").as_deref(), Some("3.00"));
+        assert_eq!(quotient("0", "3").as_deref(), Some("0"));
+        assert_eq!(quotient("1", "3"), None);
+        assert_eq!(quotient("1.00", "-6"), None);
+        assert_eq!(
+            decimal("1")
+                .div(&decimal("-3"), 2, RoundingMode::Floor)
+                .to_string(),
+            "-0.34"
+        );
+    }
+
+    #[test]
+    fn converts_floats_through_their_own_width() {
+        assert_eq!(Decimal::from_float(0.1f32).unwrap().to_string(), "0.1");
+        assert_eq!(Decimal::from_float(-2.5f64).unwrap().to_string(), "-2.5");
+        assert!(Decimal::from_float(f64::NAN).is_err());
+    }
+}
@@ -1,11 +1,40 @@
-use super::values;
+use super::super::frontend::{ast, prelude};
+use super::{natives, values};
 use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A variable along with the way it was declared.
+#[derive(Clone)]
+pub struct Binding {
+    pub value: values::RuntimeValue,
+    /// The type annotation every assignment must match, `None` for
+    /// dynamically typed variables which may be given a value of any type
+    pub declared_type: Option<ast::TypeExpr>,
+    pub constant: bool,
+    /// False for variables declared without a value until one is assigned
+    pub assigned: bool,
+}
 
 #[derive(Clone)]
 pub struct Environment {
     parent: Option<Box<Environment>>,
-    variables: HashMap<String, values::RuntimeValue>,
-    constants: Vec<String>,
+    variables: HashMap<String, Binding>,
+    enums: HashMap<String, ast::EnumDeclarationStatement>,
+    structs: HashMap<String, Rc<ast::StructDeclarationStatement>>,
+    /// Type aliases, stored with the aliases they refer to already expanded
+    type_aliases: HashMap<String, ast::TypeExpr>,
+    newtypes: HashMap<String, ast::NewtypeDeclarationStatement>,
+    /// The type arguments of the generic function being called, `None` for
+    /// type parameters that could not be inferred
+    type_arguments: HashMap<String, Option<ast::TypeExpr>>,
+    /// Whether redeclaring a variable in the same scope is an error rather
+    /// than shadowing it, only read from the global scope
+    strict_shadowing: bool,
+    /// Which variables may be reassigned, only read from the global scope
+    mutability: ast::Mutability,
+    /// Whether float operations producing NaN are errors, only read from the
+    /// global scope
+    trap_nan: bool,
 }
 
 impl Environment {
@@ -13,7 +42,93 @@ impl Environment {
         Environment {
             parent,
             variables: HashMap::new(),
-            constants: Vec::new(),
+            enums: HashMap::new(),
+            structs: HashMap::new(),
+            type_aliases: HashMap::new(),
+            newtypes: HashMap::new(),
+            type_arguments: HashMap::new(),
+            strict_shadowing: false,
+            mutability: ast::Mutability::default(),
+            trap_nan: false,
+        }
+    }
+
+    /// Turns this environment into a fresh child scope of itself. Every call
+    /// must be paired with a call to `exit_scope`.
+    pub fn enter_scope(&mut self) {
+        let parent = std::mem::replace(self, Environment::create(None));
+        *self = Environment::create(Some(Box::new(parent)));
+    }
+
+    /// Discards the current scope, restoring its parent.
+    pub fn exit_scope(&mut self) -> Result<(), String> {
+        match self.parent.take() {
+            Some(parent) => {
+                *self = *parent;
+                Ok(())
+            }
+            None => Err("Cannot exit the global scope".to_string()),
+        }
+    }
+
+    /// Enters the scope of a function call. Functions only see the global
+    /// scope, so the local scopes of the caller are set aside and returned to
+    /// be given back to `exit_call_scope`.
+    pub fn enter_call_scope(&mut self) -> Vec<Environment> {
+        let mut locals = Vec::new();
+        while let Some(parent) = self.parent.take() {
+            locals.push(std::mem::replace(self, *parent));
+        }
+        self.enter_scope();
+        locals
+    }
+
+    /// Discards the scope of a function call, restoring the local scopes of
+    /// the caller.
+    pub fn exit_call_scope(&mut self, locals: Vec<Environment>) -> Result<(), String> {
+        self.exit_scope()?;
+        for mut local in locals.into_iter().rev() {
+            let parent = std::mem::replace(self, Environment::create(None));
+            local.parent = Some(Box::new(parent));
+            *self = local;
+        }
+        Ok(())
+    }
+
+    pub fn is_global(&self) -> bool {
+        self.parent.is_none()
+    }
+
+    pub fn set_strict_shadowing(&mut self, strict_shadowing: bool) {
+        self.strict_shadowing = strict_shadowing;
+    }
+
+    pub fn strict_shadowing(&self) -> bool {
+        match &self.parent {
+            Some(parent) => parent.strict_shadowing(),
+            None => self.strict_shadowing,
+        }
+    }
+
+    pub fn set_mutability(&mut self, mutability: ast::Mutability) {
+        self.mutability = mutability;
+    }
+
+    pub fn mutability(&self) -> ast::Mutability {
+        match &self.parent {
+            Some(parent) => parent.mutability(),
+            None => self.mutability,
+        }
+    }
+
+    pub fn set_trap_nan(&mut self, trap_nan: bool) {
+        self.trap_nan = trap_nan;
+    }
+
+    pub fn trap_nan(&self) -> bool {
+        match &self.parent {
+            Some(parent) => parent.trap_nan(),
+            None => self.trap_nan,
         }
     }
 
@@ -23,21 +138,14 @@ impl Environment {
             None => (),
         }
 
-        self.declare_variable(
-            String::from("true"),
-            values::BooleanValue::create(true).as_raw(),
-            true,
-        )?;
-        self.declare_variable(
-            String::from("false"),
-            values::BooleanValue::create(false).as_raw(),
-            true,
-        )?;
-        self.declare_variable(
-            String::from("null"),
-            values::NullValue::create().as_raw(),
-            true,
-        )?;
+        for function in natives::functions() {
+            self.declare_variable(function.identifier.to_string(), function.as_raw(), true)?;
+        }
+        self.declare_variable("math".to_string(), natives::math::module(), true)?;
+
+        for enum_declaration in prelude::enums() {
+            self.declare_enum(enum_declaration)?;
+        }
 
         Ok(())
     }
@@ -48,57 +156,122 @@ impl Environment {
         value: values::RuntimeValue,
         constant: bool,
     ) -> Result<(), String> {
-        match self.variables.get(&name) {
-            Some(_) => {
-                return Err(format!(
-                    "Cannot declare variable {} as it has already been defined",
-                    name
-                ))
-            }
-            None => (),
-        }
+        self.declare_binding(
+            name,
+            Binding {
+                value,
+                declared_type: None,
+                constant,
+                assigned: true,
+            },
+        )
+    }
 
-        self.variables.insert(name.to_owned(), value);
+    /// Declares a variable with the type it was annotated with.
+    pub fn declare_typed_variable(
+        &mut self,
+        name: String,
+        value: values::RuntimeValue,
+        value_type: ast::TypeExpr,
+        constant: bool,
+    ) -> Result<(), String> {
+        self.declare_binding(
+            name,
+            Binding {
+                value,
+                declared_type: Some(value_type),
+                constant,
+                assigned: true,
+            },
+        )
+    }
 
-        if constant {
-            self.constants.push(name)
+    /// Declares `let x;` or `let x: T;`, which cannot be read until a value
+    /// has been assigned to it. Immutable variables may still be assigned
+    /// once.
+    pub fn declare_unassigned_variable(
+        &mut self,
+        name: String,
+        value_type: Option<ast::TypeExpr>,
+        constant: bool,
+    ) -> Result<(), String> {
+        self.declare_binding(
+            name,
+            Binding {
+                value: values::NullValue::create().as_raw(),
+                declared_type: value_type,
+                constant,
+                assigned: false,
+            },
+        )
+    }
+
+    /// Declaring a variable that already exists in this scope shadows it,
+    /// unless shadowing is strict.
+    fn declare_binding(&mut self, name: String, binding: Binding) -> Result<(), String> {
+        if self.variables.contains_key(&name) && self.strict_shadowing() {
+            return Err(format!(
+                "Cannot declare variable {} as it has already been defined",
+                name
+            ));
         }
 
+        self.variables.insert(name, binding);
+
         Ok(())
     }
 
+    pub fn lookup_binding(&mut self, name: &str) -> Option<&Binding> {
+        let environment = self.resolve_variable(name.to_string()).ok()?;
+        environment.variables.get(name)
+    }
+
+    /// The type annotation of a variable, `None` if it is dynamically typed.
+    pub fn declared_type(&mut self, name: &str) -> Option<ast::TypeExpr> {
+        self.lookup_binding(name)?.declared_type.to_owned()
+    }
+
+    /// Whether a variable declared without a value is still waiting for one.
+    pub fn is_unassigned(&mut self, name: &str) -> bool {
+        match self.lookup_binding(name) {
+            Some(binding) => !binding.assigned,
+            None => false,
+        }
+    }
+
+    /// The variables declared in this scope.
+    pub fn bindings(&self) -> impl Iterator<Item = (&String, &Binding)> {
+        self.variables.iter()
+    }
+
     pub fn assign_variable(
         &mut self,
         name: String,
         value: values::RuntimeValue,
     ) -> Result<(), String> {
+        let mutability = self.mutability();
         let environment = match self.resolve_variable(name.to_string()) {
             Ok(env) => env,
             Err(m) => return Err(m),
         };
+        let binding = environment
+            .variables
+            .get_mut(&name)
+            .expect("resolved environments declare the variable");
 
-        if environment.constants.contains(&name) {
-            return Err(format!(
-                "Cannot reasign to variable {} as it has been declared constant",
-                name
-            ));
+        if binding.constant && binding.assigned {
+            return Err(reassignment_error(&name, mutability));
         }
 
-        environment.variables.insert(name, value);
+        binding.value = value;
+        binding.assigned = true;
 
         Ok(())
     }
 
     pub fn lookup_variable(&mut self, name: String) -> Option<values::RuntimeValue> {
-        let environment = match self.resolve_variable(name.to_string()) {
-            Ok(env) => env,
-            Err(_) => return None,
-        };
-
-        match environment.variables.get(&name) {
-            Some(v) => Some(v.to_owned()),
-            None => None,
-        }
+        self.lookup_binding(&name)
+            .map(|binding| binding.value.to_owned())
     }
 
     pub fn resolve_variable(&mut self, variable_name: String) -> Result<&mut Environment, String> {
@@ -126,4 +299,201 @@ impl Environment {
             Err(m) => Err(m),
         }
     }
+
+    pub fn declare_enum(
+        &mut self,
+        declaration: ast::EnumDeclarationStatement,
+    ) -> Result<(), String> {
+        if self.enums.contains_key(&declaration.identifier) {
+            return Err(format!(
+                "Cannot declare enum {} as it has already been defined",
+                declaration.identifier
+            ));
+        }
+
+        self.enums
+            .insert(declaration.identifier.to_owned(), declaration);
+
+        Ok(())
+    }
+
+    /// The names of the enums declared in this scope.
+    pub fn enum_identifiers(&self) -> Vec<String> {
+        self.enums.keys().cloned().collect()
+    }
+
+    pub fn lookup_enum(&self, name: &str) -> Option<&ast::EnumDeclarationStatement> {
+        match self.enums.get(name) {
+            Some(declaration) => Some(declaration),
+            None => match &self.parent {
+                Some(parent) => parent.lookup_enum(name),
+                None => None,
+            },
+        }
+    }
+
+    pub fn declare_struct(
+        &mut self,
+        declaration: Rc<ast::StructDeclarationStatement>,
+    ) -> Result<(), String> {
+        if self.structs.contains_key(&declaration.identifier) {
+            return Err(format!(
+                "Cannot declare struct {} as it has already been defined",
+                declaration.identifier
+            ));
+        }
+
+        self.structs
+            .insert(declaration.identifier.to_owned(), declaration);
+
+        Ok(())
+    }
+
+    /// The structs declared in this scope.
+    pub fn struct_declarations(&self) -> Vec<ast::StructDeclarationStatement> {
+        self.structs
+            .values()
+            .map(|declaration| declaration.as_ref().to_owned())
+            .collect()
+    }
+
+    pub fn lookup_struct(&self, name: &str) -> Option<Rc<ast::StructDeclarationStatement>> {
+        match self.structs.get(name) {
+            Some(declaration) => Some(declaration.to_owned()),
+            None => match &self.parent {
+                Some(parent) => parent.lookup_struct(name),
+                None => None,
+            },
+        }
+    }
+
+    /// Whether a type with the given name has been declared in this scope.
+    fn declares_type(&self, identifier: &str) -> bool {
+        self.enums.contains_key(identifier)
+            || self.structs.contains_key(identifier)
+            || self.type_aliases.contains_key(identifier)
+            || self.newtypes.contains_key(identifier)
+    }
+
+    pub fn declare_type_alias(
+        &mut self,
+        identifier: String,
+        value_type: ast::TypeExpr,
+    ) -> Result<(), String> {
+        if self.declares_type(&identifier) {
+            return Err(format!(
+                "Cannot declare type {} as it has already been defined",
+                identifier
+            ));
+        }
+
+        let value_type = self.expand_type_aliases(&value_type, &[]);
+        self.type_aliases.insert(identifier, value_type);
+
+        Ok(())
+    }
+
+    /// The type aliases visible from this scope.
+    pub fn type_aliases(&self) -> HashMap<String, ast::TypeExpr> {
+        let mut type_aliases = match &self.parent {
+            Some(parent) => parent.type_aliases(),
+            None => HashMap::new(),
+        };
+        type_aliases.extend(self.type_aliases.to_owned());
+        type_aliases
+    }
+
+    /// Replaces the type aliases in a type with the type they stand for.
+    /// Type parameters hide aliases of the same name.
+    pub fn expand_type_aliases(
+        &self,
+        value_type: &ast::TypeExpr,
+        type_parameters: &[String],
+    ) -> ast::TypeExpr {
+        let mut type_aliases = self.type_aliases();
+        type_aliases.retain(|identifier, _| !type_parameters.contains(identifier));
+        value_type.substitute(&type_aliases)
+    }
+
+    pub fn declare_newtype(
+        &mut self,
+        declaration: ast::NewtypeDeclarationStatement,
+    ) -> Result<(), String> {
+        if self.declares_type(&declaration.identifier) {
+            return Err(format!(
+                "Cannot declare newtype {} as it has already been defined",
+                declaration.identifier
+            ));
+        }
+
+        self.newtypes
+            .insert(declaration.identifier.to_owned(), declaration);
+
+        Ok(())
+    }
+
+    /// The newtypes declared in this scope.
+    pub fn newtype_declarations(&self) -> Vec<ast::NewtypeDeclarationStatement> {
+        self.newtypes.values().cloned().collect()
+    }
+
+    pub fn lookup_newtype(&self, name: &str) -> Option<&ast::NewtypeDeclarationStatement> {
+        match self.newtypes.get(name) {
+            Some(declaration) => Some(declaration),
+            None => match &self.parent {
+                Some(parent) => parent.lookup_newtype(name),
+                None => None,
+            },
+        }
+    }
+
+    /// Binds the type parameters of a generic function in the current scope.
+    pub fn bind_type_arguments(&mut self, type_arguments: HashMap<String, Option<ast::TypeExpr>>) {
+        self.type_arguments = type_arguments;
+    }
+
+    /// Expands the type aliases and replaces the type parameters of the
+    /// function being called in a type annotation, `None` when the annotation mentions a type parameter that
+    /// could not be inferred and so accepts any value.
+    pub fn resolve_type(&self, value_type: &ast::TypeExpr) -> Option<ast::TypeExpr> {
+        let mut type_arguments = HashMap::new();
+        let mut unknown = Vec::new();
+        let mut environment = Some(self);
+        while let Some(scope) = environment {
+            for (identifier, type_argument) in &scope.type_arguments {
+                match type_argument {
+                    Some(type_argument) => {
+                        type_arguments
+                            .entry(identifier.to_owned())
+                            .or_insert(type_argument.to_owned());
+                    }
+                    None => unknown.push(identifier.to_owned()),
+                }
+            }
+            environment = scope.parent.as_deref();
+        }
+
+        let mut type_parameters: Vec<String> = type_arguments.keys().cloned().collect();
+        type_parameters.extend(unknown.iter().cloned());
+        let value_type = self.expand_type_aliases(value_type, &type_parameters);
+        if value_type.mentions(&unknown) {
+            return None;
+        }
+        Some(value_type.substitute(&type_arguments))
+    }
+}
+
+/// The error for reassigning a variable that cannot be reassigned, shared
+/// with the typechecker.
+pub fn reassignment_error(name: &str, mutability: ast::Mutability) -> String {
+    match mutability {
+        ast::Mutability::Js => format!(
+            "Cannot reasign to variable {} as it has been declared constant",
+            name
+        ),
+        ast::Mutability::Rust => format!(
+            "Cannot reassign to immutable variable {}, declare it with let mut to allow reassignment",
+            name
+        ),
+    }
 }
@@ -0,0 +1,140 @@
+use super::super::frontend::ast;
+use super::values;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorCategory {
+    /// A value given to `throw`
+    Thrown,
+    /// An `Err`/`None` returned early by the `?` operator
+    Propagated,
+    DivisionByZero,
+    InvalidCast,
+    TypeMismatch,
+    UndefinedVariable,
+    /// A float operation produced NaN while NaN trapping is enabled
+    NotANumber,
+    /// Any other failure raised by the interpreter
+    Runtime,
+}
+
+impl ErrorCategory {
+    pub fn as_string(&self) -> String {
+        match self {
+            ErrorCategory::Thrown => String::from("Thrown"),
+            ErrorCategory::Propagated => String::from("Propagated"),
+            ErrorCategory::DivisionByZero => String::from("DivisionByZero"),
+            ErrorCategory::InvalidCast => String::from("InvalidCast"),
+            ErrorCategory::TypeMismatch => String::from("TypeMismatch"),
+            ErrorCategory::UndefinedVariable => String::from("UndefinedVariable"),
+            ErrorCategory::NotANumber => String::from("NotANumber"),
+            ErrorCategory::Runtime => String::from("RuntimeError"),
+        }
+    }
+}
+
+/// An error raised while evaluating a program. Errors can be caught with
+/// `try`/`catch`, otherwise they end the program.
+#[derive(Debug, Clone)]
+pub struct RuntimeError {
+    pub category: ErrorCategory,
+    pub message: String,
+    /// The innermost statement that was being evaluated when the error was
+    /// raised
+    pub location: Option<ast::SourceLocation>,
+    /// The value given to `throw` or carried by a propagated `Err`
+    pub value: Option<Box<values::RuntimeValue>>,
+}
+
+impl RuntimeError {
+    pub fn create(category: ErrorCategory, message: String) -> Self {
+        RuntimeError {
+            category,
+            message,
+            location: None,
+            value: None,
+        }
+    }
+
+    pub fn with_value(
+        category: ErrorCategory,
+        message: String,
+        value: values::RuntimeValue,
+    ) -> Self {
+        RuntimeError {
+            category,
+            message,
+            location: None,
+            value: Some(Box::new(value)),
+        }
+    }
+
+    /// Records where the error was raised, keeping the location of the
+    /// innermost statement if one is already set.
+    pub fn at(mut self, location: ast::SourceLocation) -> Self {
+        if self.location.is_none() {
+            self.location = Some(location);
+        }
+        self
+    }
+
+    /// The object bound to the identifier of a `catch` clause.
+    pub fn as_object(&self) -> values::RuntimeValue {
+        let location = match self.location {
+            Some(location) => values::ObjectValue::create(vec![
+                (
+                    String::from("line"),
+                    values::Integer32Value::create(location.line as i32).as_raw(),
+                ),
+                (
+                    String::from("column"),
+                    values::Integer32Value::create(location.column as i32).as_raw(),
+                ),
+            ])
+            .as_raw(),
+            None => values::NullValue::create().as_raw(),
+        };
+
+        values::ObjectValue::create(vec![
+            (
+                String::from("message"),
+                values::StringValue::create(self.message.to_owned()).as_raw(),
+            ),
+            (
+                String::from("category"),
+                values::StringValue::create(self.category.as_string()).as_raw(),
+            ),
+            (String::from("location"), location),
+            (
+                String::from("value"),
+                match &self.value {
+                    Some(value) => *value.to_owned(),
+                    None => values::NullValue::create().as_raw(),
+                },
+            ),
+        ])
+        .as_raw()
+    }
+}
+
+impl From<String> for RuntimeError {
+    fn from(message: String) -> Self {
+        RuntimeError::create(ErrorCategory::Runtime, message)
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.location {
+            Some(location) => write!(
+                f,
+                "{}: {} (line {}, column {})",
+                self.category.as_string(),
+                self.message,
+                location.line,
+                location.column
+            ),
+            None => write!(f, "{}: {}", self.category.as_string(), self.message),
+        }
+    }
+}
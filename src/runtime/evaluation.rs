@@ -1,2 +1,5 @@
 pub mod expressions;
+pub mod functions;
+pub mod methods;
+pub mod patterns;
 pub mod statements;
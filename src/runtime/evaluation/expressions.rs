@@ -1,10 +1,14 @@
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use num_traits::{CheckedShl, CheckedShr};
+use std::collections::HashMap;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Sub};
 
 use crate::{
     frontend::ast,
     runtime::{
         environment::Environment,
-        helpers,
+        errors::{ErrorCategory, RuntimeError},
+        evaluation::patterns,
+        generics, helpers,
         interpreter::{self, evaluate},
         values,
     },
@@ -36,11 +40,42 @@ where
     }
 }
 
+/// The error for operands an operator cannot be applied to, worded like the
+/// type checker's.
+fn operator_mismatch(
+    operator: &str,
+    left_hand_side: &values::RuntimeValue,
+    right_hand_side: &values::RuntimeValue,
+) -> RuntimeError {
+    RuntimeError::create(
+        ErrorCategory::TypeMismatch,
+        format!(
+            "Cannot perform the ({}) operator to a {} and a {}",
+            operator,
+            left_hand_side.to_owned().as_value_type().as_string(),
+            right_hand_side.to_owned().as_value_type().as_string()
+        ),
+    )
+}
+
 pub fn evaluate_digit_binary_expression(
     left_hand_side: values::RuntimeValue,
     right_hand_side: values::RuntimeValue,
     operator: String,
-) -> Result<values::RuntimeValue, String> {
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if operator == "**" {
+        return evaluate_exponent_expression(&left_hand_side, &right_hand_side);
+    }
+    if (operator == "/" || operator == "%") && helpers::runtime_integer_is_zero(&right_hand_side) {
+        return Err(RuntimeError::create(
+            ErrorCategory::DivisionByZero,
+            format!(
+                "Cannot perform the ({}) operator with a divisor of zero",
+                operator
+            ),
+        ));
+    }
+
     match (&left_hand_side, &right_hand_side) {
         (values::RuntimeValue::Integer8(lhs), values::RuntimeValue::Integer8(rhs)) => {
             let result = evaluate_digit_binary_expression_result(lhs.value, rhs.value, operator)?;
@@ -97,6 +132,51 @@ pub fn evaluate_digit_binary_expression(
             let result = evaluate_digit_binary_expression_result(lhs.value, rhs.value, operator)?;
             Ok(values::UnsignedInteger128Value::create(result).as_raw())
         }
+        (values::RuntimeValue::BigInt(lhs), values::RuntimeValue::BigInt(rhs)) => {
+            let result = match operator.as_str() {
+                "-" => &lhs.value - &rhs.value,
+                "*" => &lhs.value * &rhs.value,
+                "+" => &lhs.value + &rhs.value,
+                "/" => &lhs.value / &rhs.value,
+                "%" => &lhs.value % &rhs.value,
+                _ => {
+                    return Err(format!(
+                        "Unexpected operator found during binary expression evalutation, got: {}",
+                        operator
+                    )
+                    .into())
+                }
+            };
+            Ok(values::BigIntValue::create(result).as_raw())
+        }
+        // Division never rounds, quotients without an end such as `1d / 3d`
+        // must pick a scale and rounding mode with `div`
+        (values::RuntimeValue::Decimal(lhs), values::RuntimeValue::Decimal(rhs)) => {
+            let result = match operator.as_str() {
+                "-" => lhs.value.sub(&rhs.value),
+                "*" => lhs.value.mul(&rhs.value),
+                "+" => lhs.value.add(&rhs.value),
+                "/" => match lhs.value.div_exact(&rhs.value) {
+                    Some(quotient) => quotient,
+                    None => {
+                        return Err(format!(
+                            "{} / {} has no exact decimal result, use .div(divisor, scale, rounding) to round it",
+                            lhs.value, rhs.value
+                        )
+                        .into())
+                    }
+                },
+                "%" => lhs.value.rem(&rhs.value),
+                _ => {
+                    return Err(format!(
+                        "Unexpected operator found during binary expression evalutation, got: {}",
+                        operator
+                    )
+                    .into())
+                }
+            };
+            Ok(values::DecimalValue::create(result).as_raw())
+        }
         (values::RuntimeValue::Float32(lhs), values::RuntimeValue::Float32(rhs)) => {
             let result = evaluate_digit_binary_expression_result(lhs.value, rhs.value, operator)?;
             Ok(values::Float32Value::create(result).as_raw())
@@ -105,19 +185,335 @@ pub fn evaluate_digit_binary_expression(
             let result = evaluate_digit_binary_expression_result(lhs.value, rhs.value, operator)?;
             Ok(values::Float64Value::create(result).as_raw())
         }
+        _ => Err(operator_mismatch(
+            &operator,
+            &left_hand_side,
+            &right_hand_side,
+        )),
+    }
+}
+
+/// `base ** exponent`, integers are raised to an exponent that fits in a
+/// `u32` and error on overflow, floats accept a float exponent of the same
+/// type or an integer one.
+pub fn evaluate_exponent_expression(
+    base: &values::RuntimeValue,
+    exponent: &values::RuntimeValue,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let mismatch = || -> RuntimeError {
+        RuntimeError::create(
+            ErrorCategory::TypeMismatch,
+            format!(
+                "Cannot raise a {} to the power of a {}",
+                base.to_owned().as_value_type().as_string(),
+                exponent.to_owned().as_value_type().as_string()
+            ),
+        )
+    };
+
+    match base {
+        values::RuntimeValue::Float32(float) => {
+            let exponent = match exponent {
+                values::RuntimeValue::Float32(exponent) => exponent.value,
+                _ if helpers::runtime_value_is_integer(exponent) => {
+                    exponent.to_owned().as_f32()?.value
+                }
+                _ => return Err(mismatch()),
+            };
+            return Ok(values::Float32Value::create(float.value.powf(exponent)).as_raw());
+        }
+        values::RuntimeValue::Float64(float) => {
+            let exponent = match exponent {
+                values::RuntimeValue::Float64(exponent) => exponent.value,
+                _ if helpers::runtime_value_is_integer(exponent) => {
+                    exponent.to_owned().as_f64()?.value
+                }
+                _ => return Err(mismatch()),
+            };
+            return Ok(values::Float64Value::create(float.value.powf(exponent)).as_raw());
+        }
+        _ if !(helpers::runtime_value_is_integer(base)
+            || helpers::runtime_digit_is_decimal(base))
+            || !helpers::runtime_value_is_integer(exponent) =>
+        {
+            return Err(mismatch())
+        }
+        _ => (),
+    }
+
+    let power = match exponent.to_owned().as_u32() {
+        Ok(power) => power.value,
+        Err(_) => {
+            return Err(format!(
+                "Integers can only be raised to a power between 0 and {}, got {}",
+                u32::MAX,
+                exponent
+            )
+            .into())
+        }
+    };
+
+    let result = match base {
+        values::RuntimeValue::Integer8(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::Integer8Value::create(value).as_raw()),
+        values::RuntimeValue::Integer16(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::Integer16Value::create(value).as_raw()),
+        values::RuntimeValue::Integer32(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::Integer32Value::create(value).as_raw()),
+        values::RuntimeValue::Integer64(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::Integer64Value::create(value).as_raw()),
+        values::RuntimeValue::Integer128(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::Integer128Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger8(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::UnsignedInteger8Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger16(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::UnsignedInteger16Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger32(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::UnsignedInteger32Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger64(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::UnsignedInteger64Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger128(integer) => integer
+            .value
+            .checked_pow(power)
+            .map(|value| values::UnsignedInteger128Value::create(value).as_raw()),
+        values::RuntimeValue::BigInt(integer) => {
+            Some(values::BigIntValue::create(integer.value.pow(power)).as_raw())
+        }
+        values::RuntimeValue::Decimal(decimal) => {
+            Some(values::DecimalValue::create(decimal.value.pow(power)).as_raw())
+        }
+        _ => None,
+    };
+    result.ok_or_else(|| {
+        format!(
+            "{} ** {} overflowed {}",
+            base,
+            exponent,
+            base.to_owned().as_value_type().as_string()
+        )
+        .into()
+    })
+}
+
+pub fn evaluate_bitwise_expression_result<T>(
+    left_value: T,
+    right_value: T,
+    operator: &str,
+) -> Result<T, String>
+where
+    T: BitAnd<Output = T> + BitOr<Output = T> + BitXor<Output = T>,
+{
+    match operator {
+        "&" => Ok(left_value & right_value),
+        "|" => Ok(left_value | right_value),
+        "^" => Ok(left_value ^ right_value),
         _ => Err(format!(
-            "Cannot perform the ({}) operator to a {:#?} and a {:#?}",
-            operator,
-            left_hand_side.as_value_type(),
-            right_hand_side.as_value_type()
+            "Unexpected operator found during bitwise expression evalutation, got: {}",
+            operator
+        )),
+    }
+}
+
+/// `&`, `|` and `^` on two integers of the same type, including bigints.
+pub fn evaluate_bitwise_expression(
+    left_hand_side: values::RuntimeValue,
+    right_hand_side: values::RuntimeValue,
+    operator: String,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if operator == "<<" || operator == ">>" {
+        return evaluate_shift_expression(&left_hand_side, &right_hand_side, &operator);
+    }
+
+    match (&left_hand_side, &right_hand_side) {
+        (values::RuntimeValue::Integer8(lhs), values::RuntimeValue::Integer8(rhs)) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::Integer8Value::create(result).as_raw())
+        }
+        (values::RuntimeValue::Integer16(lhs), values::RuntimeValue::Integer16(rhs)) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::Integer16Value::create(result).as_raw())
+        }
+        (values::RuntimeValue::Integer32(lhs), values::RuntimeValue::Integer32(rhs)) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::Integer32Value::create(result).as_raw())
+        }
+        (values::RuntimeValue::Integer64(lhs), values::RuntimeValue::Integer64(rhs)) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::Integer64Value::create(result).as_raw())
+        }
+        (values::RuntimeValue::Integer128(lhs), values::RuntimeValue::Integer128(rhs)) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::Integer128Value::create(result).as_raw())
+        }
+        (
+            values::RuntimeValue::UnsignedInteger8(lhs),
+            values::RuntimeValue::UnsignedInteger8(rhs),
+        ) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::UnsignedInteger8Value::create(result).as_raw())
+        }
+        (
+            values::RuntimeValue::UnsignedInteger16(lhs),
+            values::RuntimeValue::UnsignedInteger16(rhs),
+        ) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::UnsignedInteger16Value::create(result).as_raw())
+        }
+        (
+            values::RuntimeValue::UnsignedInteger32(lhs),
+            values::RuntimeValue::UnsignedInteger32(rhs),
+        ) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::UnsignedInteger32Value::create(result).as_raw())
+        }
+        (
+            values::RuntimeValue::UnsignedInteger64(lhs),
+            values::RuntimeValue::UnsignedInteger64(rhs),
+        ) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::UnsignedInteger64Value::create(result).as_raw())
+        }
+        (
+            values::RuntimeValue::UnsignedInteger128(lhs),
+            values::RuntimeValue::UnsignedInteger128(rhs),
+        ) => {
+            let result = evaluate_bitwise_expression_result(lhs.value, rhs.value, &operator)?;
+            Ok(values::UnsignedInteger128Value::create(result).as_raw())
+        }
+        // Negative bigints behave as if in two's complement
+        (values::RuntimeValue::BigInt(lhs), values::RuntimeValue::BigInt(rhs)) => {
+            let result = evaluate_bitwise_expression_result(
+                lhs.value.to_owned(),
+                rhs.value.to_owned(),
+                &operator,
+            )?;
+            Ok(values::BigIntValue::create(result).as_raw())
+        }
+        _ => Err(operator_mismatch(
+            &operator,
+            &left_hand_side,
+            &right_hand_side,
         )),
     }
 }
 
+fn shift<T: CheckedShl + CheckedShr>(value: T, amount: u32, operator: &str) -> Option<T> {
+    match operator {
+        "<<" => value.checked_shl(amount),
+        _ => value.checked_shr(amount),
+    }
+}
+
+/// `<<` and `>>` shift any integer by an integer amount, which must be less
+/// than the width of fixed size integers. `>>` keeps the sign of signed
+/// integers.
+fn evaluate_shift_expression(
+    left_hand_side: &values::RuntimeValue,
+    right_hand_side: &values::RuntimeValue,
+    operator: &str,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if !helpers::runtime_value_is_integer(left_hand_side)
+        || !helpers::runtime_value_is_integer(right_hand_side)
+    {
+        return Err(operator_mismatch(operator, left_hand_side, right_hand_side));
+    }
+    let amount = match right_hand_side.to_owned().as_u32() {
+        Ok(amount) => amount.value,
+        Err(_) => {
+            return Err(format!(
+                "Integers can only be shifted by an amount between 0 and {}, got {}",
+                u32::MAX,
+                right_hand_side
+            )
+            .into())
+        }
+    };
+
+    let result = match left_hand_side {
+        values::RuntimeValue::Integer8(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::Integer8Value::create(value).as_raw()),
+        values::RuntimeValue::Integer16(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::Integer16Value::create(value).as_raw()),
+        values::RuntimeValue::Integer32(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::Integer32Value::create(value).as_raw()),
+        values::RuntimeValue::Integer64(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::Integer64Value::create(value).as_raw()),
+        values::RuntimeValue::Integer128(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::Integer128Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger8(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::UnsignedInteger8Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger16(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::UnsignedInteger16Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger32(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::UnsignedInteger32Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger64(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::UnsignedInteger64Value::create(value).as_raw()),
+        values::RuntimeValue::UnsignedInteger128(integer) => shift(integer.value, amount, operator)
+            .map(|value| values::UnsignedInteger128Value::create(value).as_raw()),
+        values::RuntimeValue::BigInt(integer) => {
+            let result = match operator {
+                "<<" => &integer.value << amount,
+                _ => &integer.value >> amount,
+            };
+            Some(values::BigIntValue::create(result).as_raw())
+        }
+        _ => None,
+    };
+    result.ok_or_else(|| {
+        format!(
+            "Cannot shift a {} by {} bits, the amount must be less than its width",
+            left_hand_side.to_owned().as_value_type().as_string(),
+            amount
+        )
+        .into()
+    })
+}
+
+/// Errors when NaN trapping is enabled and an operation produced NaN from
+/// operands that were not NaN themselves.
+pub fn check_nan_production(
+    operation: &str,
+    operands: &[values::RuntimeValue],
+    runtime_val: &values::RuntimeValue,
+) -> Result<(), RuntimeError> {
+    if !helpers::runtime_value_is_nan(runtime_val)
+        || operands.iter().any(helpers::runtime_value_is_nan)
+    {
+        return Ok(());
+    }
+
+    let operands: Vec<String> = operands
+        .iter()
+        .map(|operand| operand.to_debug_string())
+        .collect();
+    Err(RuntimeError::create(
+        ErrorCategory::NotANumber,
+        format!("{} produced NaN from {}", operation, operands.join(", ")),
+    ))
+}
+
 pub fn evaluate_binary_expression(
     binary_expression: Box<ast::BinaryExpression>,
     environment: &mut Environment,
-) -> Result<values::RuntimeValue, String> {
+) -> Result<values::RuntimeValue, RuntimeError> {
     let left_hand_side = match interpreter::evaluate(
         ast::Statement::Expression(binary_expression.left),
         environment,
@@ -125,6 +521,16 @@ pub fn evaluate_binary_expression(
         Ok(runtime_val) => runtime_val,
         Err(m) => return Err(m),
     };
+    // `a ?? b` only evaluates `b` when `a` is null
+    if binary_expression.operator == "??" {
+        return match left_hand_side {
+            values::RuntimeValue::Null(_) => interpreter::evaluate(
+                ast::Statement::Expression(binary_expression.right),
+                environment,
+            ),
+            _ => Ok(left_hand_side),
+        };
+    }
     let right_hand_side = match interpreter::evaluate(
         ast::Statement::Expression(binary_expression.right),
         environment,
@@ -132,59 +538,290 @@ pub fn evaluate_binary_expression(
         Ok(runtime_val) => runtime_val,
         Err(m) => return Err(m),
     };
-    if helpers::runtime_value_is_digit(&left_hand_side)
-        && helpers::runtime_value_is_digit(&right_hand_side)
-    {
-        return evaluate_digit_binary_expression(
+    if !environment.trap_nan() {
+        return evaluate_binary_operation(
             left_hand_side,
             right_hand_side,
             binary_expression.operator,
         );
     }
 
-    Ok(values::NullValue::create().as_raw())
+    let operands = [left_hand_side.to_owned(), right_hand_side.to_owned()];
+    let operation = format!("The ({}) operator", binary_expression.operator);
+    let runtime_val =
+        evaluate_binary_operation(left_hand_side, right_hand_side, binary_expression.operator)?;
+    check_nan_production(&operation, &operands, &runtime_val)?;
+    Ok(runtime_val)
+}
+
+fn evaluate_binary_operation(
+    left_hand_side: values::RuntimeValue,
+    right_hand_side: values::RuntimeValue,
+    operator: String,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let is_equality = matches!(operator.as_str(), "===" | "==" | "!==" | "!=");
+    // Null can only be compared for equality, `null ?? b` is the way to
+    // give it a value
+    let has_null = matches!(left_hand_side, values::RuntimeValue::Null(_))
+        || matches!(right_hand_side, values::RuntimeValue::Null(_));
+    if has_null && !is_equality {
+        return Err(RuntimeError::create(
+            ErrorCategory::TypeMismatch,
+            format!(
+                "Cannot apply {} to {} and {}, use ?? to replace null with a value",
+                operator,
+                left_hand_side.as_value_type().as_string(),
+                right_hand_side.as_value_type().as_string()
+            ),
+        ));
+    }
+
+    if !has_null
+        && (matches!(left_hand_side, values::RuntimeValue::Newtype(_))
+            || matches!(right_hand_side, values::RuntimeValue::Newtype(_)))
+    {
+        return evaluate_newtype_binary_expression(left_hand_side, right_hand_side, operator);
+    }
+
+    match operator.as_str() {
+        "===" | "==" | "!==" | "!=" | "<" | ">" | "<=" | ">=" => {
+            return evaluate_comparison_expression(left_hand_side, right_hand_side, operator)
+        }
+        _ => (),
+    }
+
+    if matches!(operator.as_str(), "&" | "|" | "^" | "<<" | ">>") {
+        return evaluate_bitwise_expression(left_hand_side, right_hand_side, operator);
+    }
+
+    if helpers::runtime_value_is_digit(&left_hand_side)
+        && helpers::runtime_value_is_digit(&right_hand_side)
+    {
+        return evaluate_digit_binary_expression(left_hand_side, right_hand_side, operator);
+    }
+
+    if matches!(left_hand_side, values::RuntimeValue::String(_)) {
+        return evaluate_string_binary_expression(left_hand_side, right_hand_side, operator);
+    }
+
+    Err(operator_mismatch(
+        &operator,
+        &left_hand_side,
+        &right_hand_side,
+    ))
+}
+
+/// Newtypes can only be combined with values of the same newtype. Arithmetic
+/// keeps the newtype, e.g. `Meters(1.0) + Meters(2.0)` is `Meters(3.0)`.
+fn evaluate_newtype_binary_expression(
+    left_hand_side: values::RuntimeValue,
+    right_hand_side: values::RuntimeValue,
+    operator: String,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let (lhs, rhs) = match (left_hand_side, right_hand_side) {
+        (values::RuntimeValue::Newtype(lhs), values::RuntimeValue::Newtype(rhs))
+            if lhs.value_type == rhs.value_type =>
+        {
+            (lhs, rhs)
+        }
+        (left_hand_side, right_hand_side) => {
+            return Err(operator_mismatch(
+                &operator,
+                &left_hand_side,
+                &right_hand_side,
+            ))
+        }
+    };
+
+    let is_comparison = matches!(
+        operator.as_str(),
+        "===" | "==" | "!==" | "!=" | "<" | ">" | "<=" | ">="
+    );
+    let runtime_val = evaluate_binary_operation(*lhs.value, *rhs.value, operator)?;
+    match is_comparison {
+        true => Ok(runtime_val),
+        false => Ok(values::NewtypeValue::create(lhs.value_type.as_string(), runtime_val).as_raw()),
+    }
+}
+
+/// `str + str`, `str + char` and `str * n`
+pub fn evaluate_string_binary_expression(
+    left_hand_side: values::RuntimeValue,
+    right_hand_side: values::RuntimeValue,
+    operator: String,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    match (&left_hand_side, operator.as_str(), &right_hand_side) {
+        (values::RuntimeValue::String(lhs), "+", values::RuntimeValue::String(rhs)) => {
+            Ok(values::StringValue::create(format!("{}{}", lhs.value, rhs.value)).as_raw())
+        }
+        (values::RuntimeValue::String(lhs), "+", values::RuntimeValue::Character(rhs)) => {
+            Ok(values::StringValue::create(format!("{}{}", lhs.value, rhs.value)).as_raw())
+        }
+        (values::RuntimeValue::String(lhs), "*", count)
+            if helpers::runtime_value_is_integer(count) =>
+        {
+            let length = count
+                .to_string()
+                .parse::<usize>()
+                .ok()
+                .and_then(|count| Some((count, lhs.value.len().checked_mul(count)?)));
+            match length {
+                Some((count, length)) if length <= helpers::MAX_STRING_LENGTH => {
+                    Ok(values::StringValue::create(lhs.value.repeat(count)).as_raw())
+                }
+                Some((_, length)) => Err(format!(
+                    "Cannot repeat a str {} times, the result would be {} bytes long and the limit is {}",
+                    count, length, helpers::MAX_STRING_LENGTH
+                )
+                .into()),
+                None => Err(format!("Cannot repeat a str {} times", count).into()),
+            }
+        }
+        _ => Err(operator_mismatch(
+            &operator,
+            &left_hand_side,
+            &right_hand_side,
+        )),
+    }
+}
+
+pub fn evaluate_comparison_expression(
+    left_hand_side: values::RuntimeValue,
+    right_hand_side: values::RuntimeValue,
+    operator: String,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let result = match operator.as_str() {
+        "===" | "==" => helpers::runtime_values_equal(&left_hand_side, &right_hand_side),
+        "!==" | "!=" => !helpers::runtime_values_equal(&left_hand_side, &right_hand_side),
+        // Like IEEE 754, NaN is neither less than, greater than nor equal
+        // to any float, itself included
+        _ if (helpers::runtime_value_is_nan(&left_hand_side)
+            || helpers::runtime_value_is_nan(&right_hand_side))
+            && left_hand_side.to_owned().as_value_type()
+                == right_hand_side.to_owned().as_value_type() =>
+        {
+            false
+        }
+        _ => {
+            let ordering = match helpers::compare_runtime_values(&left_hand_side, &right_hand_side)
+            {
+                Some(ordering) => ordering,
+                None => {
+                    return Err(operator_mismatch(
+                        &operator,
+                        &left_hand_side,
+                        &right_hand_side,
+                    ))
+                }
+            };
+            match operator.as_str() {
+                "<" => ordering.is_lt(),
+                ">" => ordering.is_gt(),
+                "<=" => ordering.is_le(),
+                ">=" => ordering.is_ge(),
+                _ => {
+                    return Err(format!(
+                        "Unexpected operator found during comparison evaluation, got: {}",
+                        operator
+                    )
+                    .into())
+                }
+            }
+        }
+    };
+
+    Ok(values::BooleanValue::create(result).as_raw())
+}
+
+/// Evaluates `typeof x` to an object holding the type the variable was
+/// declared with, or `dynamic`, and the type of its current value, which is
+/// null until a value has been assigned.
+pub fn evaluate_typeof_expression(
+    ast_node: ast::TypeofExpression,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let binding = match environment.lookup_binding(&ast_node.identifier) {
+        Some(binding) => binding,
+        None => {
+            return Err(RuntimeError::create(
+                ErrorCategory::UndefinedVariable,
+                format!("Variable \"{}\" does not exist", ast_node.identifier),
+            ))
+        }
+    };
+
+    let declared = match &binding.declared_type {
+        Some(declared_type) => declared_type.to_string(),
+        None => String::from("dynamic"),
+    };
+    let runtime = match binding.assigned {
+        true => values::StringValue::create(binding.value.to_owned().as_value_type().as_string())
+            .as_raw(),
+        false => values::NullValue::create().as_raw(),
+    };
+
+    Ok(values::ObjectValue::create(vec![
+        (
+            String::from("declared"),
+            values::StringValue::create(declared).as_raw(),
+        ),
+        (String::from("runtime"), runtime),
+    ])
+    .as_raw())
 }
 
 pub fn evaluate_identifier_expression(
     ast_node: ast::IdentifierExpression,
     environment: &mut Environment,
-) -> Result<values::RuntimeValue, String> {
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if environment.is_unassigned(&ast_node.symbol) {
+        return Err(RuntimeError::create(
+            ErrorCategory::UndefinedVariable,
+            format!(
+                "Variable \"{}\" is read before a value has been assigned to it",
+                ast_node.symbol
+            ),
+        ));
+    }
+
     match environment.lookup_variable(ast_node.symbol.to_string()) {
         Some(value) => Ok(value),
-        None => Err(format!("Variable \"{}\" does not exist", ast_node.symbol)),
+        None => Err(RuntimeError::create(
+            ErrorCategory::UndefinedVariable,
+            format!("Variable \"{}\" does not exist", ast_node.symbol),
+        )),
     }
 }
 
 pub fn evaluate_assignment_expression(
     ast_node: Box<ast::VariableAssignmentExpression>,
     environment: &mut Environment,
-) -> Result<values::RuntimeValue, String> {
+) -> Result<values::RuntimeValue, RuntimeError> {
     let assignee = match ast_node.assignee {
         ast::Expression::Identifier(identifier) => identifier,
         _ => {
             return Err(format!(
                 "Invalid left hand side expression. Expected identifier, got {:#?}",
                 &ast_node.assignee
-            ))
+            )
+            .into())
         }
     };
 
-    let variable_value = match environment.lookup_variable(assignee.symbol.to_owned()) {
-        Some(runtime_value) => runtime_value,
-        None => {
-            return Err(format!(
+    if environment.lookup_binding(&assignee.symbol).is_none() {
+        return Err(RuntimeError::create(
+            ErrorCategory::UndefinedVariable,
+            format!(
                 "Unknown variable: {} detected during variable assignment",
                 assignee.symbol
-            ))
-        }
-    };
+            ),
+        ));
+    }
 
     let new_variable_value = match evaluate(ast::Statement::Expression(ast_node.value), environment)
     {
         Ok(new_runtime_value) => match helpers::evaluate_variable_type(
-            Some(ast::IdentifierExpression::create(
-                variable_value.as_value_type().as_string(),
-            )),
+            environment.declared_type(&assignee.symbol).as_ref(),
             assignee.symbol.to_owned(),
             new_runtime_value,
         ) {
@@ -196,6 +833,445 @@ pub fn evaluate_assignment_expression(
 
     match environment.assign_variable(assignee.symbol, new_variable_value.to_owned()) {
         Ok(_) => Ok(new_variable_value),
-        Err(m) => return Err(m),
+        Err(m) => return Err(m.into()),
+    }
+}
+
+pub fn evaluate_destructuring_assignment_expression(
+    ast_node: Box<ast::DestructuringAssignmentExpression>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let runtime_val = evaluate(ast::Statement::Expression(ast_node.value), environment)?;
+
+    if !patterns::evaluate_pattern(
+        &ast_node.pattern,
+        &runtime_val,
+        environment,
+        patterns::BindingMode::Assign,
+    )? {
+        return Err(format!(
+            "Cannot destructure a value of type {} with the given pattern",
+            runtime_val.as_value_type().as_string()
+        )
+        .into());
+    }
+
+    Ok(runtime_val)
+}
+
+pub fn evaluate_template_literal(
+    ast_node: ast::TemplateLiteral,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let mut result = String::new();
+
+    for part in ast_node.parts {
+        match part {
+            ast::TemplatePart::Text(text) => result.push_str(&text),
+            ast::TemplatePart::Interpolation { expression, format } => {
+                let runtime_val = evaluate(ast::Statement::Expression(expression), environment)?;
+                match format {
+                    Some(format) => {
+                        result.push_str(&helpers::format_runtime_value(&runtime_val, &format)?)
+                    }
+                    None => result.push_str(&runtime_val.to_string()),
+                }
+            }
+        }
+    }
+
+    Ok(values::StringValue::create(result).as_raw())
+}
+
+pub fn evaluate_tuple_literal(
+    ast_node: ast::TupleLiteral,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let mut elements = Vec::new();
+    for element in ast_node.elements {
+        elements.push(evaluate(ast::Statement::Expression(element), environment)?);
+    }
+
+    Ok(values::TupleValue::create(elements).as_raw())
+}
+
+pub fn evaluate_array_literal(
+    ast_node: ast::ArrayLiteral,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let mut elements = Vec::new();
+    for element in ast_node.elements {
+        elements.push(evaluate(ast::Statement::Expression(element), environment)?);
+    }
+
+    Ok(values::ArrayValue::create(elements).as_raw())
+}
+
+pub fn evaluate_object_literal(
+    ast_node: ast::ObjectLiteral,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let mut properties = Vec::new();
+    for (key, value) in ast_node.properties {
+        properties.push((
+            key,
+            evaluate(ast::Statement::Expression(value), environment)?,
+        ));
+    }
+
+    Ok(values::ObjectValue::create(properties).as_raw())
+}
+
+/// Builds a struct value, inferring the type arguments of a generic struct
+/// from its fields.
+pub fn evaluate_struct_literal(
+    ast_node: ast::StructLiteral,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let declaration = match environment.lookup_struct(&ast_node.identifier) {
+        Some(declaration) => declaration,
+        None => return Err(format!("Struct \"{}\" does not exist", ast_node.identifier).into()),
+    };
+
+    let mut expressions = ast_node.fields;
+    let mut field_values = Vec::new();
+    for (field, _) in &declaration.fields {
+        let position = match expressions
+            .iter()
+            .position(|(identifier, _)| identifier == field)
+        {
+            Some(position) => position,
+            None => {
+                return Err(format!("Missing field {} in {}", field, ast_node.identifier).into())
+            }
+        };
+        let (_, expression) = expressions.remove(position);
+        field_values.push(evaluate(
+            ast::Statement::Expression(expression),
+            environment,
+        )?);
+    }
+    if let Some((field, _)) = expressions.first() {
+        return Err(format!("{} has no field named {}", ast_node.identifier, field).into());
+    }
+
+    let type_parameters = ast::type_parameter_identifiers(&declaration.type_parameters);
+    let mut type_arguments = HashMap::new();
+    for ((_, field_type), field_value) in declaration.fields.iter().zip(&field_values) {
+        generics::infer_type_arguments(
+            field_type,
+            field_value,
+            &type_parameters,
+            &mut type_arguments,
+        );
+    }
+
+    let instantiation = generics::instantiation(
+        &declaration.identifier,
+        &declaration.type_parameters,
+        &type_arguments,
+    );
+    generics::check_bounds(
+        &declaration.type_parameters,
+        &type_arguments,
+        &instantiation,
+    )?;
+
+    let mut fields = Vec::new();
+    for ((field, field_type), field_value) in declaration.fields.iter().zip(field_values) {
+        let field_type = field_type.substitute(&type_arguments);
+        // Type parameters that could not be inferred accept any value
+        if field_type.mentions(&type_parameters) {
+            fields.push((field.to_owned(), field_value));
+            continue;
+        }
+        let field_value = helpers::evaluate_variable_type(
+            Some(&field_type),
+            format!("{}.{}", ast_node.identifier, field),
+            field_value,
+        )
+        .map_err(|error| generics::in_instantiation(error, &instantiation))?;
+        fields.push((field.to_owned(), field_value));
+    }
+
+    Ok(values::StructValue::create(declaration, fields).as_raw())
+}
+
+/// The object of `object?.property`, `None` when it is null. Option and
+/// Result objects are unwrapped as if by `object?.property`.
+pub fn optional_object(
+    object: values::RuntimeValue,
+) -> Result<Option<values::RuntimeValue>, RuntimeError> {
+    match &object {
+        values::RuntimeValue::Null(_) => Ok(None),
+        values::RuntimeValue::Enum(enum_value) if matches!(&enum_value.value_type, values::ValueType::Enum(name) if name == "Option" || name == "Result") => {
+            propagate(object).map(Some)
+        }
+        _ => Ok(Some(object)),
+    }
+}
+
+pub fn evaluate_member_expression(
+    ast_node: Box<ast::MemberExpression>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let object = evaluate(ast::Statement::Expression(ast_node.object), environment)?;
+    let object = match ast_node.optional {
+        true => match optional_object(object)? {
+            Some(object) => object,
+            None => return Ok(values::NullValue::create().as_raw()),
+        },
+        false => object,
+    };
+
+    match (&object, ast_node.property) {
+        (values::RuntimeValue::Tuple(tuple), ast::MemberProperty::Index(index)) => {
+            match tuple.value.get(index) {
+                Some(element) => Ok(element.to_owned()),
+                None => Err(format!(
+                    "Tuple index {} is out of bounds for a tuple of length {}",
+                    index,
+                    tuple.value.len()
+                )
+                .into()),
+            }
+        }
+        (values::RuntimeValue::Object(object), ast::MemberProperty::Name(name)) => {
+            match object.get(&name) {
+                Some(value) => Ok(value.to_owned()),
+                None => Err(format!("Object has no property named {}", name).into()),
+            }
+        }
+        (values::RuntimeValue::Struct(struct_value), ast::MemberProperty::Name(name)) => {
+            match struct_value.get(&name) {
+                Some(value) => Ok(value.to_owned()),
+                None => Err(format!(
+                    "Struct {} has no field named {}",
+                    struct_value.declaration.identifier, name
+                )
+                .into()),
+            }
+        }
+        (values::RuntimeValue::Newtype(newtype), ast::MemberProperty::Index(0)) => {
+            Ok(newtype.value.as_ref().to_owned())
+        }
+        (_, ast::MemberProperty::Index(index)) => Err(format!(
+            "Cannot access index {} of a {}",
+            index,
+            object.as_value_type().as_string()
+        )
+        .into()),
+        (_, ast::MemberProperty::Name(name)) => Err(format!(
+            "Cannot access property {} of a {}",
+            name,
+            object.as_value_type().as_string()
+        )
+        .into()),
+    }
+}
+
+/// Unwraps `Some(x)`/`Ok(x)` to `x`. `None` and `Err(e)` stop evaluation and
+/// are handed back to the caller as an error.
+pub fn evaluate_propagation_expression(
+    ast_node: Box<ast::PropagationExpression>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let runtime_val = evaluate(ast::Statement::Expression(ast_node.value), environment)?;
+    propagate(runtime_val)
+}
+
+fn propagate(runtime_val: values::RuntimeValue) -> Result<values::RuntimeValue, RuntimeError> {
+    let enum_value = match runtime_val {
+        values::RuntimeValue::Enum(enum_value) if matches!(&enum_value.value_type, values::ValueType::Enum(name) if name == "Option" || name == "Result") => {
+            enum_value
+        }
+        _ => {
+            return Err(format!(
+                "The ? operator can only be applied to Option and Result values, got: {}",
+                runtime_val.as_value_type().as_string()
+            )
+            .into())
+        }
+    };
+
+    match (enum_value.variant.as_str(), enum_value.payload) {
+        ("Some" | "Ok", ast::EnumPayload::Tuple(mut payload_values))
+            if payload_values.len() == 1 =>
+        {
+            Ok(payload_values.remove(0))
+        }
+        ("None", _) => Err(RuntimeError::with_value(
+            ErrorCategory::Propagated,
+            "Propagated None with the ? operator".to_string(),
+            values::EnumValue::create(
+                "Option".to_string(),
+                "None".to_string(),
+                ast::EnumPayload::Unit,
+            )
+            .as_raw(),
+        )),
+        ("Err", ast::EnumPayload::Tuple(mut payload_values)) if payload_values.len() == 1 => {
+            let error = payload_values.remove(0);
+            Err(RuntimeError::with_value(
+                ErrorCategory::Propagated,
+                format!("Propagated error with the ? operator: {}", error),
+                error,
+            ))
+        }
+        (variant, _) => Err(format!("Unexpected variant {} for the ? operator", variant).into()),
     }
 }
+
+pub fn evaluate_enum_variant_expression(
+    ast_node: Box<ast::EnumVariantExpression>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let (type_parameters, variant) = match environment.lookup_enum(&ast_node.enum_identifier) {
+        Some(declaration) => match declaration.find_variant(&ast_node.variant_identifier) {
+            Some(variant) => (declaration.type_parameters.to_owned(), variant.to_owned()),
+            None => {
+                return Err(format!(
+                    "Enum {} has no variant named {}",
+                    ast_node.enum_identifier, ast_node.variant_identifier
+                )
+                .into())
+            }
+        },
+        None => return Err(format!("Enum \"{}\" does not exist", ast_node.enum_identifier).into()),
+    };
+
+    let path = format!(
+        "{}::{}",
+        ast_node.enum_identifier, ast_node.variant_identifier
+    );
+
+    let payload = match (variant.payload, ast_node.payload) {
+        (ast::EnumPayload::Unit, ast::EnumPayload::Unit) => ast::EnumPayload::Unit,
+        (ast::EnumPayload::Tuple(value_types), ast::EnumPayload::Tuple(expressions)) => {
+            if value_types.len() != expressions.len() {
+                return Err(format!(
+                    "{} expects {} value(s), got {}",
+                    path,
+                    value_types.len(),
+                    expressions.len()
+                )
+                .into());
+            }
+            let mut payload_values = Vec::new();
+            for (value_type, expression) in value_types.into_iter().zip(expressions) {
+                let runtime_val = evaluate(ast::Statement::Expression(expression), environment)?;
+                // Type parameters accept any value
+                if value_type.mentions(&type_parameters) {
+                    payload_values.push(runtime_val);
+                    continue;
+                }
+                payload_values.push(helpers::evaluate_variable_type(
+                    Some(&value_type),
+                    path.to_owned(),
+                    runtime_val,
+                )?);
+            }
+            ast::EnumPayload::Tuple(payload_values)
+        }
+        (ast::EnumPayload::Struct(field_types), ast::EnumPayload::Struct(mut expressions)) => {
+            let mut payload_fields = Vec::new();
+            for (field, value_type) in field_types {
+                let position = match expressions
+                    .iter()
+                    .position(|(identifier, _)| *identifier == field)
+                {
+                    Some(position) => position,
+                    None => return Err(format!("Missing field {} in {}", field, path).into()),
+                };
+                let (_, expression) = expressions.remove(position);
+                let runtime_val = evaluate(ast::Statement::Expression(expression), environment)?;
+                if value_type.mentions(&type_parameters) {
+                    payload_fields.push((field.to_owned(), runtime_val));
+                    continue;
+                }
+                payload_fields.push((
+                    field.to_owned(),
+                    helpers::evaluate_variable_type(
+                        Some(&value_type),
+                        format!("{}.{}", path, field),
+                        runtime_val,
+                    )?,
+                ));
+            }
+            if let Some((field, _)) = expressions.first() {
+                return Err(format!("{} has no field named {}", path, field).into());
+            }
+            ast::EnumPayload::Struct(payload_fields)
+        }
+        (ast::EnumPayload::Unit, _) => {
+            return Err(format!("{} does not hold any values", path).into())
+        }
+        (ast::EnumPayload::Tuple(_), _) => {
+            return Err(format!("{} must be constructed with parentheses", path).into())
+        }
+        (ast::EnumPayload::Struct(_), _) => {
+            return Err(format!("{} must be constructed with braces", path).into())
+        }
+    };
+
+    Ok(values::EnumValue::create(
+        ast_node.enum_identifier,
+        ast_node.variant_identifier,
+        payload,
+    )
+    .as_raw())
+}
+
+pub fn evaluate_match_expression(
+    ast_node: Box<ast::MatchExpression>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let subject = evaluate(ast::Statement::Expression(ast_node.subject), environment)?;
+
+    for arm in ast_node.arms {
+        // Each arm gets its own scope so pattern bindings do not leak
+        environment.enter_scope();
+        let result = evaluate_match_arm(arm, &subject, environment);
+        environment.exit_scope()?;
+
+        if let Some(runtime_val) = result? {
+            return Ok(runtime_val);
+        }
+    }
+
+    Err(format!("No match arm matched the value {}", subject).into())
+}
+
+fn evaluate_match_arm(
+    arm: ast::MatchArm,
+    subject: &values::RuntimeValue,
+    environment: &mut Environment,
+) -> Result<Option<values::RuntimeValue>, RuntimeError> {
+    if !patterns::evaluate_pattern(
+        &arm.pattern,
+        subject,
+        environment,
+        patterns::BindingMode::Match,
+    )? {
+        return Ok(None);
+    }
+
+    if let Some(guard) = arm.guard {
+        match evaluate(ast::Statement::Expression(guard), environment)? {
+            values::RuntimeValue::Boolean(boolean) => {
+                if !boolean.value {
+                    return Ok(None);
+                }
+            }
+            runtime_val => {
+                return Err(format!(
+                    "Match guards must evaluate to a bool, got: {}",
+                    runtime_val.as_value_type().as_string()
+                )
+                .into())
+            }
+        }
+    }
+
+    evaluate(ast::Statement::Expression(arm.body), environment).map(Some)
+}
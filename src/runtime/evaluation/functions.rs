@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use crate::{
+    frontend::ast,
+    runtime::{
+        environment::Environment,
+        errors::{ErrorCategory, RuntimeError},
+        evaluation::{expressions, statements},
+        generics, helpers,
+        interpreter::evaluate,
+        values,
+    },
+};
+
+pub fn evaluate_function_declaration(
+    mut function_declaration: ast::FunctionDeclarationStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let type_parameters = ast::type_parameter_identifiers(&function_declaration.type_parameters);
+    for (_, parameter_type) in &mut function_declaration.parameters {
+        *parameter_type = environment.expand_type_aliases(parameter_type, &type_parameters);
+    }
+    if let Some(return_type) = &mut function_declaration.return_type {
+        *return_type = environment.expand_type_aliases(return_type, &type_parameters);
+    }
+    environment.declare_variable(
+        function_declaration.identifier.to_owned(),
+        values::FunctionValue::create(function_declaration).as_raw(),
+        true,
+    )?;
+
+    Ok(values::NullValue::create().as_raw())
+}
+
+pub fn evaluate_call_expression(
+    ast_node: Box<ast::CallExpression>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if let ast::Expression::Identifier(identifier) = &ast_node.callee {
+        if let Some(newtype_declaration) = environment.lookup_newtype(&identifier.symbol) {
+            let newtype_declaration = newtype_declaration.to_owned();
+            return evaluate_newtype_construction(newtype_declaration, ast_node, environment);
+        }
+    }
+
+    let callee = evaluate(ast::Statement::Expression(ast_node.callee), environment)?;
+
+    let mut arguments = Vec::new();
+    for argument in ast_node.arguments {
+        arguments.push(evaluate(ast::Statement::Expression(argument), environment)?);
+    }
+
+    call_value(callee, ast_node.type_arguments, arguments, environment)
+}
+
+/// Calls a function declared in Jarlang or implemented natively.
+pub fn call_value(
+    callee: values::RuntimeValue,
+    type_arguments: Vec<ast::TypeExpr>,
+    arguments: Vec<values::RuntimeValue>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    match callee {
+        values::RuntimeValue::Function(function) => {
+            call_function(function, type_arguments, arguments, environment)
+        }
+        values::RuntimeValue::NativeFunction(function) if environment.trap_nan() => {
+            let identifier = function.identifier;
+            let operands = arguments.to_owned();
+            let runtime_val = call_native_function(function, type_arguments, arguments)?;
+            expressions::check_nan_production(identifier, &operands, &runtime_val)?;
+            Ok(runtime_val)
+        }
+        values::RuntimeValue::NativeFunction(function) => {
+            call_native_function(function, type_arguments, arguments)
+        }
+        runtime_val => Err(format!(
+            "Cannot call a value of type {}",
+            runtime_val.as_value_type().as_string()
+        )
+        .into()),
+    }
+}
+
+fn call_native_function(
+    function: values::NativeFunctionValue,
+    type_arguments: Vec<ast::TypeExpr>,
+    arguments: Vec<values::RuntimeValue>,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if !type_arguments.is_empty() {
+        return Err(format!("{} does not take type arguments", function.identifier).into());
+    }
+    if !function.accepts(arguments.len()) {
+        let expected = match function.max_arguments {
+            Some(max_arguments) if max_arguments == function.min_arguments => {
+                max_arguments.to_string()
+            }
+            Some(max_arguments) => format!("{} to {}", function.min_arguments, max_arguments),
+            None => format!("at least {}", function.min_arguments),
+        };
+        return Err(format!(
+            "{} expects {} argument(s), got {}",
+            function.identifier,
+            expected,
+            arguments.len()
+        )
+        .into());
+    }
+
+    (function.function)(arguments)
+}
+
+/// `Meters(1.5)`, wraps a value in a newtype after checking it against the
+/// wrapped type.
+fn evaluate_newtype_construction(
+    newtype_declaration: ast::NewtypeDeclarationStatement,
+    ast_node: Box<ast::CallExpression>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let identifier = newtype_declaration.identifier;
+    let mut arguments = ast_node.arguments;
+    if arguments.len() != 1 || !ast_node.type_arguments.is_empty() {
+        return Err(format!(
+            "Newtype {} expects 1 argument, got {}",
+            identifier,
+            arguments.len()
+        )
+        .into());
+    }
+
+    let runtime_val = evaluate(ast::Statement::Expression(arguments.remove(0)), environment)?;
+    let runtime_val = helpers::evaluate_variable_type(
+        Some(&newtype_declaration.value_type),
+        identifier.to_owned(),
+        runtime_val,
+    )?;
+
+    Ok(values::NewtypeValue::create(identifier, runtime_val).as_raw())
+}
+
+/// Calls a function with the given arguments. The type arguments of a
+/// generic function are either given with `f::<T>()` or inferred from the
+/// arguments, then the arguments and the returned value are checked against
+/// the instantiated parameter and return types.
+///
+/// Functions only see the global scope and their parameters. An `Err` or
+/// `None` propagated with `?` inside the body is returned by the function.
+pub fn call_function(
+    function: values::FunctionValue,
+    explicit_type_arguments: Vec<ast::TypeExpr>,
+    arguments: Vec<values::RuntimeValue>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let declaration = function.declaration.to_owned();
+
+    if arguments.len() != declaration.parameters.len() {
+        return Err(format!(
+            "{} expects {} argument(s), got {}",
+            declaration.identifier,
+            declaration.parameters.len(),
+            arguments.len()
+        )
+        .into());
+    }
+    if !explicit_type_arguments.is_empty()
+        && explicit_type_arguments.len() != declaration.type_parameters.len()
+    {
+        return Err(format!(
+            "{} expects {} type argument(s), got {}",
+            declaration.identifier,
+            declaration.type_parameters.len(),
+            explicit_type_arguments.len()
+        )
+        .into());
+    }
+
+    let type_parameters = ast::type_parameter_identifiers(&declaration.type_parameters);
+    let mut type_arguments: HashMap<String, ast::TypeExpr> = type_parameters
+        .iter()
+        .cloned()
+        .zip(explicit_type_arguments)
+        .collect();
+    for ((_, parameter_type), argument) in declaration.parameters.iter().zip(&arguments) {
+        generics::infer_type_arguments(
+            parameter_type,
+            argument,
+            &type_parameters,
+            &mut type_arguments,
+        );
+    }
+
+    let instantiation = generics::instantiation(
+        &declaration.identifier,
+        &declaration.type_parameters,
+        &type_arguments,
+    );
+    generics::check_bounds(
+        &declaration.type_parameters,
+        &type_arguments,
+        &instantiation,
+    )?;
+
+    let mut parameters = Vec::new();
+    for ((identifier, parameter_type), argument) in declaration.parameters.iter().zip(arguments) {
+        let parameter_type = parameter_type.substitute(&type_arguments);
+        // Type parameters that could not be inferred accept any value
+        if parameter_type.mentions(&type_parameters) {
+            parameters.push((identifier.to_owned(), argument));
+            continue;
+        }
+        let argument =
+            helpers::evaluate_variable_type(Some(&parameter_type), identifier.to_owned(), argument)
+                .map_err(|error| generics::in_instantiation(error, &instantiation))?;
+        parameters.push((identifier.to_owned(), argument));
+    }
+
+    let scope_type_arguments: HashMap<String, Option<ast::TypeExpr>> = type_parameters
+        .iter()
+        .map(|identifier| {
+            (
+                identifier.to_owned(),
+                type_arguments.get(identifier).cloned(),
+            )
+        })
+        .collect();
+
+    // Imported functions run against the globals of their own module
+    let result = match &function.module {
+        Some(module) => {
+            let mut module_environment = module.as_ref().to_owned();
+            module_environment.enter_scope();
+            evaluate_function_body(
+                &declaration,
+                parameters,
+                scope_type_arguments,
+                &mut module_environment,
+            )
+        }
+        None => {
+            let locals = environment.enter_call_scope();
+            let result =
+                evaluate_function_body(&declaration, parameters, scope_type_arguments, environment);
+            environment.exit_call_scope(locals)?;
+            result
+        }
+    };
+
+    let runtime_val = match result {
+        Ok(runtime_val) => runtime_val,
+        Err(error) if error.category == ErrorCategory::Propagated => match error.value {
+            Some(value) => match *value {
+                values::RuntimeValue::Enum(enum_value)
+                    if enum_value.value_type == values::ValueType::Enum("Option".to_string())
+                        && enum_value.variant == "None" =>
+                {
+                    enum_value.as_raw()
+                }
+                value => values::EnumValue::create(
+                    "Result".to_string(),
+                    "Err".to_string(),
+                    ast::EnumPayload::Tuple(vec![value]),
+                )
+                .as_raw(),
+            },
+            None => values::NullValue::create().as_raw(),
+        },
+        Err(error) => return Err(error),
+    };
+
+    let return_type = match &declaration.return_type {
+        Some(return_type) => return_type.substitute(&type_arguments),
+        None => return Ok(runtime_val),
+    };
+    if return_type.mentions(&type_parameters) {
+        return Ok(runtime_val);
+    }
+    helpers::evaluate_variable_type(
+        Some(&return_type),
+        format!("the return value of {}", declaration.identifier),
+        runtime_val,
+    )
+    .map_err(|error| generics::in_instantiation(error, &instantiation))
+}
+
+fn evaluate_function_body(
+    declaration: &ast::FunctionDeclarationStatement,
+    parameters: Vec<(String, values::RuntimeValue)>,
+    type_arguments: HashMap<String, Option<ast::TypeExpr>>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    environment.bind_type_arguments(type_arguments);
+    for (identifier, argument) in parameters {
+        environment.declare_variable(identifier, argument, false)?;
+    }
+
+    statements::evaluate_block_statement(declaration.body.to_owned(), environment)
+}
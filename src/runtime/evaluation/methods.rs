@@ -0,0 +1,702 @@
+use crate::{
+    frontend::ast,
+    runtime::{
+        collections::{OrderedMap, OrderedSet},
+        decimal::{Decimal, RoundingMode},
+        environment::Environment,
+        errors::{ErrorCategory, RuntimeError},
+        evaluation::{expressions, functions},
+        helpers, interpreter, values,
+    },
+};
+
+pub fn evaluate_method_call_expression(
+    ast_node: Box<ast::MethodCallExpression>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    // A map or set changed by a method is stored back into the variable it
+    // was read from
+    let receiver = match (&ast_node.object, ast_node.optional) {
+        (ast::Expression::Identifier(identifier), false) => Some(identifier.symbol.to_owned()),
+        _ => None,
+    };
+    let object = interpreter::evaluate(ast::Statement::Expression(ast_node.object), environment)?;
+    let object = match ast_node.optional {
+        true => match expressions::optional_object(object)? {
+            Some(object) => object,
+            None => return Ok(values::NullValue::create().as_raw()),
+        },
+        false => object,
+    };
+
+    let mut arguments = Vec::new();
+    for argument in ast_node.arguments {
+        arguments.push(interpreter::evaluate(
+            ast::Statement::Expression(argument),
+            environment,
+        )?);
+    }
+
+    let mut call = MethodCall {
+        method: ast_node.method,
+        type_arguments: ast_node.type_arguments,
+        arguments,
+    };
+
+    match object {
+        values::RuntimeValue::String(string) => call_string_method(&string.value, call),
+        values::RuntimeValue::Character(character) => call_character_method(character.value, call),
+        values::RuntimeValue::Decimal(decimal) => call_decimal_method(&decimal.value, call),
+        values::RuntimeValue::Map(mut map) => {
+            let changes = matches!(call.method.as_str(), "insert" | "remove");
+            check_inserted(receiver.as_deref(), &mut call, environment)?;
+            let result = call_map_method(&mut map.value, call)?;
+            if changes {
+                store_receiver(receiver, map.as_raw(), environment)?;
+            }
+            Ok(result)
+        }
+        values::RuntimeValue::Set(mut set) => {
+            let changes = matches!(call.method.as_str(), "insert" | "remove");
+            check_inserted(receiver.as_deref(), &mut call, environment)?;
+            let result = call_set_method(&mut set.value, call)?;
+            if changes {
+                store_receiver(receiver, set.as_raw(), environment)?;
+            }
+            Ok(result)
+        }
+        // Functions stored in a namespace import or struct field
+        values::RuntimeValue::Object(ref object) => match object.get(&call.method) {
+            Some(
+                function @ (values::RuntimeValue::Function(_)
+                | values::RuntimeValue::NativeFunction(_)),
+            ) => functions::call_value(
+                function.to_owned(),
+                call.type_arguments,
+                call.arguments,
+                environment,
+            ),
+            _ => Err(format!(
+                "No method named {} found for a value of type object",
+                call.method
+            )
+            .into()),
+        },
+        values::RuntimeValue::Struct(ref struct_value) => match struct_value.get(&call.method) {
+            Some(
+                function @ (values::RuntimeValue::Function(_)
+                | values::RuntimeValue::NativeFunction(_)),
+            ) => functions::call_value(
+                function.to_owned(),
+                call.type_arguments,
+                call.arguments,
+                environment,
+            ),
+            _ => Err(format!(
+                "No method named {} found for a value of type {}",
+                call.method, struct_value.declaration.identifier
+            )
+            .into()),
+        },
+        _ => Err(format!(
+            "No method named {} found for a value of type {}",
+            call.method,
+            object.as_value_type().as_string()
+        )
+        .into()),
+    }
+}
+
+/// Coerces the key and value given to `insert` to the type arguments the
+/// receiver was declared with, e.g. `Map<u8, str>`, like an assignment would.
+fn check_inserted(
+    receiver: Option<&str>,
+    call: &mut MethodCall,
+    environment: &mut Environment,
+) -> Result<(), RuntimeError> {
+    let (receiver, declared) = match receiver {
+        Some(receiver) if call.method == "insert" => match environment
+            .declared_type(receiver)
+            .and_then(|declared| environment.resolve_type(&declared))
+        {
+            Some(declared) => (receiver, declared),
+            None => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    let (element_types, labels) = match &declared {
+        ast::TypeExpr::Named {
+            identifier,
+            type_arguments,
+        } if identifier == "Map" => (
+            type_arguments,
+            vec![
+                format!("a key of {}", receiver),
+                format!("a value of {}", receiver),
+            ],
+        ),
+        ast::TypeExpr::Named {
+            identifier,
+            type_arguments,
+        } if identifier == "Set" => (type_arguments, vec![format!("a value of {}", receiver)]),
+        _ => return Ok(()),
+    };
+
+    for ((element_type, label), argument) in element_types
+        .iter()
+        .zip(labels)
+        .zip(call.arguments.iter_mut())
+    {
+        *argument =
+            helpers::evaluate_variable_type(Some(element_type), label, argument.to_owned())?;
+    }
+    Ok(())
+}
+
+/// Assigns a map or set changed in place to the variable the method was
+/// called on. Changing a value that is not held by a variable would lose the
+/// change, so it is an error.
+fn store_receiver(
+    receiver: Option<String>,
+    runtime_val: values::RuntimeValue,
+    environment: &mut Environment,
+) -> Result<(), RuntimeError> {
+    match receiver {
+        Some(identifier) => Ok(environment.assign_variable(identifier, runtime_val)?),
+        None => Err(format!(
+            "insert and remove change a {} in place, call them on a variable holding it",
+            runtime_val.as_value_type().as_string()
+        )
+        .into()),
+    }
+}
+
+struct MethodCall {
+    method: String,
+    type_arguments: Vec<ast::TypeExpr>,
+    arguments: Vec<values::RuntimeValue>,
+}
+
+impl MethodCall {
+    fn expect_arguments(&self, count: usize) -> Result<(), RuntimeError> {
+        if self.arguments.len() != count {
+            return Err(format!(
+                "{} expects {} argument(s), got {}",
+                self.method,
+                count,
+                self.arguments.len()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn expect_arguments_between(&self, min: usize, max: usize) -> Result<(), RuntimeError> {
+        if self.arguments.len() < min || self.arguments.len() > max {
+            return Err(format!(
+                "{} expects {} to {} argument(s), got {}",
+                self.method,
+                min,
+                max,
+                self.arguments.len()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn string_argument(&self, index: usize) -> Result<String, RuntimeError> {
+        match &self.arguments[index] {
+            values::RuntimeValue::String(string) => Ok(string.value.to_owned()),
+            values::RuntimeValue::Character(character) => Ok(character.value.to_string()),
+            argument => Err(self.argument_mismatch(index, "str", argument)),
+        }
+    }
+
+    /// Integer arguments of any integer type, used for indexes and counts.
+    fn index_argument(&self, index: usize) -> Result<usize, RuntimeError> {
+        let argument = &self.arguments[index];
+        if !helpers::runtime_value_is_integer(argument) {
+            return Err(self.argument_mismatch(index, "integer", argument));
+        }
+        match argument.to_string().parse::<usize>() {
+            Ok(value) => Ok(value),
+            Err(_) => Err(format!(
+                "Argument {} of {} must not be negative, got: {}",
+                index + 1,
+                self.method,
+                argument
+            )
+            .into()),
+        }
+    }
+
+    /// Decimal arguments, integers are converted to a decimal.
+    fn decimal_argument(&self, index: usize) -> Result<Decimal, RuntimeError> {
+        match &self.arguments[index] {
+            values::RuntimeValue::Decimal(decimal) => Ok(decimal.value.to_owned()),
+            argument if helpers::runtime_value_is_integer(argument) => {
+                Ok(argument.to_owned().as_decimal()?.value)
+            }
+            argument => Err(self.argument_mismatch(index, "decimal", argument)),
+        }
+    }
+
+    /// An optional rounding mode such as `"half_up"`, half to even when it is
+    /// left out.
+    fn rounding_argument(&self, index: usize) -> Result<RoundingMode, RuntimeError> {
+        match self.arguments.get(index) {
+            Some(_) => Ok(RoundingMode::parse(&self.string_argument(index)?)?),
+            None => Ok(RoundingMode::HalfEven),
+        }
+    }
+
+    fn argument_mismatch(
+        &self,
+        index: usize,
+        expected: &str,
+        argument: &values::RuntimeValue,
+    ) -> RuntimeError {
+        RuntimeError::create(
+            ErrorCategory::TypeMismatch,
+            format!(
+                "Argument {} of {} must be a {}, got: {}",
+                index + 1,
+                self.method,
+                expected,
+                argument.to_owned().as_value_type().as_string()
+            ),
+        )
+    }
+
+    fn unknown(&self, value_type: &str) -> RuntimeError {
+        format!(
+            "No method named {} found for a value of type {}",
+            self.method, value_type
+        )
+        .into()
+    }
+}
+
+fn string_value(value: String) -> values::RuntimeValue {
+    values::StringValue::create(value).as_raw()
+}
+
+fn boolean_value(value: bool) -> values::RuntimeValue {
+    values::BooleanValue::create(value).as_raw()
+}
+
+fn index_value(value: usize) -> values::RuntimeValue {
+    values::Integer32Value::create(value as i32).as_raw()
+}
+
+fn option_value(value: Option<values::RuntimeValue>) -> values::RuntimeValue {
+    match value {
+        Some(value) => values::EnumValue::create(
+            String::from("Option"),
+            String::from("Some"),
+            ast::EnumPayload::Tuple(vec![value]),
+        )
+        .as_raw(),
+        None => values::EnumValue::create(
+            String::from("Option"),
+            String::from("None"),
+            ast::EnumPayload::Unit,
+        )
+        .as_raw(),
+    }
+}
+
+fn result_value(value: Result<values::RuntimeValue, String>) -> values::RuntimeValue {
+    let (variant, payload) = match value {
+        Ok(value) => ("Ok", value),
+        Err(message) => ("Err", string_value(message)),
+    };
+    values::EnumValue::create(
+        String::from("Result"),
+        String::from(variant),
+        ast::EnumPayload::Tuple(vec![payload]),
+    )
+    .as_raw()
+}
+
+/// Methods on `str`. Lengths and indexes count characters rather than bytes.
+fn call_string_method(
+    string: &str,
+    call: MethodCall,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    match call.method.as_str() {
+        "len" => {
+            call.expect_arguments(0)?;
+            Ok(index_value(string.chars().count()))
+        }
+        "is_empty" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(string.is_empty()))
+        }
+        "chars" => {
+            call.expect_arguments(0)?;
+            Ok(values::ArrayValue::create(
+                string
+                    .chars()
+                    .map(|character| values::CharacterValue::create(character).as_raw())
+                    .collect(),
+            )
+            .as_raw())
+        }
+        "bytes" => {
+            call.expect_arguments(0)?;
+            Ok(values::ArrayValue::create(
+                string
+                    .bytes()
+                    .map(|byte| values::UnsignedInteger8Value::create(byte).as_raw())
+                    .collect(),
+            )
+            .as_raw())
+        }
+        "split" => {
+            call.expect_arguments(1)?;
+            let separator = call.string_argument(0)?;
+            Ok(values::ArrayValue::create(
+                string
+                    .split(separator.as_str())
+                    .map(|part| string_value(part.to_string()))
+                    .collect(),
+            )
+            .as_raw())
+        }
+        "trim" => {
+            call.expect_arguments(0)?;
+            Ok(string_value(string.trim().to_string()))
+        }
+        "trim_start" => {
+            call.expect_arguments(0)?;
+            Ok(string_value(string.trim_start().to_string()))
+        }
+        "trim_end" => {
+            call.expect_arguments(0)?;
+            Ok(string_value(string.trim_end().to_string()))
+        }
+        "to_upper" => {
+            call.expect_arguments(0)?;
+            Ok(string_value(string.to_uppercase()))
+        }
+        "to_lower" => {
+            call.expect_arguments(0)?;
+            Ok(string_value(string.to_lowercase()))
+        }
+        "replace" => {
+            call.expect_arguments(2)?;
+            let from = call.string_argument(0)?;
+            let to = call.string_argument(1)?;
+            Ok(string_value(string.replace(from.as_str(), to.as_str())))
+        }
+        "repeat" => {
+            call.expect_arguments(1)?;
+            Ok(string_value(string.repeat(call.index_argument(0)?)))
+        }
+        "find" => {
+            call.expect_arguments(1)?;
+            let pattern = call.string_argument(0)?;
+            Ok(option_value(string.find(pattern.as_str()).map(
+                |byte_index| index_value(string[..byte_index].chars().count()),
+            )))
+        }
+        "contains" => {
+            call.expect_arguments(1)?;
+            Ok(boolean_value(
+                string.contains(call.string_argument(0)?.as_str()),
+            ))
+        }
+        "starts_with" => {
+            call.expect_arguments(1)?;
+            Ok(boolean_value(
+                string.starts_with(call.string_argument(0)?.as_str()),
+            ))
+        }
+        "ends_with" => {
+            call.expect_arguments(1)?;
+            Ok(boolean_value(
+                string.ends_with(call.string_argument(0)?.as_str()),
+            ))
+        }
+        "slice" => {
+            call.expect_arguments(2)?;
+            let start = call.index_argument(0)?;
+            let end = call.index_argument(1)?;
+            let length = string.chars().count();
+            if start > end || end > length {
+                return Err(format!(
+                    "Cannot slice {}..{} of a str of length {}",
+                    start, end, length
+                )
+                .into());
+            }
+            Ok(string_value(
+                string.chars().skip(start).take(end - start).collect(),
+            ))
+        }
+        "parse" => {
+            call.expect_arguments(0)?;
+            let value_type = match call.type_arguments.as_slice() {
+                [value_type] => value_type.to_string(),
+                _ => {
+                    return Err(
+                        "parse expects a single numeric type argument, e.g. parse::<i32>()"
+                            .to_string()
+                            .into(),
+                    )
+                }
+            };
+            Ok(result_value(helpers::parse_numeric(string, &value_type)?))
+        }
+        _ => Err(call.unknown("str")),
+    }
+}
+
+/// Methods on `decimal`. Scales count the digits after the decimal point.
+fn call_decimal_method(
+    decimal: &Decimal,
+    call: MethodCall,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    match call.method.as_str() {
+        "round" => {
+            call.expect_arguments_between(1, 2)?;
+            let scale = call.index_argument(0)? as u32;
+            let mode = call.rounding_argument(1)?;
+            Ok(values::DecimalValue::create(decimal.rescale(scale, mode)).as_raw())
+        }
+        "div" => {
+            call.expect_arguments_between(2, 3)?;
+            let divisor = call.decimal_argument(0)?;
+            let scale = call.index_argument(1)? as u32;
+            let mode = call.rounding_argument(2)?;
+            if divisor.is_zero() {
+                return Err(RuntimeError::create(
+                    ErrorCategory::DivisionByZero,
+                    format!("Cannot divide {} by zero", decimal),
+                ));
+            }
+            Ok(values::DecimalValue::create(decimal.div(&divisor, scale, mode)).as_raw())
+        }
+        "scale" => {
+            call.expect_arguments(0)?;
+            Ok(index_value(decimal.scale() as usize))
+        }
+        "to_string" => {
+            call.expect_arguments(0)?;
+            Ok(string_value(decimal.to_string()))
+        }
+        _ => Err(call.unknown("decimal")),
+    }
+}
+
+fn array_value<'a>(values: impl Iterator<Item = &'a values::RuntimeValue>) -> values::RuntimeValue {
+    values::ArrayValue::create(values.cloned().collect()).as_raw()
+}
+
+/// Methods on `Map<K, V>`. `insert` and `remove` change the map in place and
+/// give back the value they replaced or removed, e.g. `scores.insert("amy", 3)`.
+fn call_map_method(
+    map: &mut OrderedMap,
+    call: MethodCall,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    match call.method.as_str() {
+        "len" => {
+            call.expect_arguments(0)?;
+            Ok(index_value(map.len()))
+        }
+        "is_empty" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(map.is_empty()))
+        }
+        "insert" => {
+            call.expect_arguments(2)?;
+            let mut arguments = call.arguments;
+            let value = arguments.remove(1);
+            Ok(option_value(map.insert(arguments.remove(0), value)?))
+        }
+        "get" => {
+            call.expect_arguments(1)?;
+            Ok(option_value(map.get(&call.arguments[0])?.cloned()))
+        }
+        "remove" => {
+            call.expect_arguments(1)?;
+            Ok(option_value(map.remove(&call.arguments[0])?))
+        }
+        "contains" => {
+            call.expect_arguments(1)?;
+            Ok(boolean_value(map.contains_key(&call.arguments[0])?))
+        }
+        "keys" => {
+            call.expect_arguments(0)?;
+            Ok(array_value(map.keys()))
+        }
+        "values" => {
+            call.expect_arguments(0)?;
+            Ok(array_value(map.values()))
+        }
+        "entries" => {
+            call.expect_arguments(0)?;
+            Ok(values::ArrayValue::create(
+                map.entries()
+                    .iter()
+                    .map(|(key, value)| {
+                        values::TupleValue::create(vec![key.to_owned(), value.to_owned()]).as_raw()
+                    })
+                    .collect(),
+            )
+            .as_raw())
+        }
+        _ => Err(call.unknown("Map")),
+    }
+}
+
+/// Methods on `Set<T>`. Like those on maps `insert` and `remove` change the
+/// set in place, giving whether it changed.
+fn call_set_method(
+    set: &mut OrderedSet,
+    call: MethodCall,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    match call.method.as_str() {
+        "len" => {
+            call.expect_arguments(0)?;
+            Ok(index_value(set.len()))
+        }
+        "is_empty" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(set.is_empty()))
+        }
+        "insert" => {
+            call.expect_arguments(1)?;
+            Ok(boolean_value(set.insert(call.arguments[0].to_owned())?))
+        }
+        "remove" => {
+            call.expect_arguments(1)?;
+            Ok(boolean_value(set.remove(&call.arguments[0])?))
+        }
+        "contains" => {
+            call.expect_arguments(1)?;
+            Ok(boolean_value(set.contains(&call.arguments[0])?))
+        }
+        "values" => {
+            call.expect_arguments(0)?;
+            Ok(array_value(set.values()))
+        }
+        "union" | "intersection" | "difference" => {
+            call.expect_arguments(1)?;
+            let other = match &call.arguments[0] {
+                values::RuntimeValue::Set(other) => &other.value,
+                argument => return Err(call.argument_mismatch(0, "Set", argument)),
+            };
+            let result = match call.method.as_str() {
+                "union" => set.union(other)?,
+                "intersection" => set.intersection(other)?,
+                _ => set.difference(other)?,
+            };
+            Ok(values::SetValue::create(result).as_raw())
+        }
+        _ => Err(call.unknown("Set")),
+    }
+}
+
+/// Methods on `char`.
+fn call_character_method(
+    character: char,
+    call: MethodCall,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    match call.method.as_str() {
+        "is_digit" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(character.is_ascii_digit()))
+        }
+        "is_alphabetic" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(character.is_alphabetic()))
+        }
+        "is_alphanumeric" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(character.is_alphanumeric()))
+        }
+        "is_whitespace" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(character.is_whitespace()))
+        }
+        "is_upper" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(character.is_uppercase()))
+        }
+        "is_lower" => {
+            call.expect_arguments(0)?;
+            Ok(boolean_value(character.is_lowercase()))
+        }
+        // Characters without a single character case mapping are left as is
+        "to_upper" => {
+            call.expect_arguments(0)?;
+            let mut upper = character.to_uppercase();
+            Ok(values::CharacterValue::create(match upper.len() {
+                1 => upper.next().unwrap_or(character),
+                _ => character,
+            })
+            .as_raw())
+        }
+        "to_lower" => {
+            call.expect_arguments(0)?;
+            let mut lower = character.to_lowercase();
+            Ok(values::CharacterValue::create(match lower.len() {
+                1 => lower.next().unwrap_or(character),
+                _ => character,
+            })
+            .as_raw())
+        }
+        "to_digit" => {
+            call.expect_arguments(0)?;
+            Ok(option_value(
+                character
+                    .to_digit(10)
+                    .map(|digit| index_value(digit as usize)),
+            ))
+        }
+        "to_string" => {
+            call.expect_arguments(0)?;
+            Ok(string_value(character.to_string()))
+        }
+        _ => Err(call.unknown("char")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frontend::parser::Parser;
+    use crate::runtime::{environment::Environment, errors::RuntimeError, interpreter, values};
+
+    fn run(source: &str) -> Result<values::RuntimeValue, RuntimeError> {
+        let program = Parser::new().produce_ast(source)?;
+        let mut environment = Environment::create(None);
+        environment.init_global_scope()?;
+        interpreter::evaluate(program, &mut environment)
+    }
+
+    #[test]
+    fn insert_changes_the_variable_in_place() {
+        let map = run("let m = Map();\nm.insert(1, \"a\")\nm.insert(2, \"b\")\nm.remove(1)\nm");
+        assert_eq!(map.unwrap().to_string(), "Map { 2: \"b\" }");
+        let set = run("let s = Set();\ns.insert(1)\ns.insert(1)\ns");
+        assert_eq!(set.unwrap().to_string(), "Set { 1 }");
+    }
+
+    #[test]
+    fn insert_coerces_to_the_declared_type_arguments() {
+        let map =
+            run("let m: Map<u8, str> = Map();\nm.insert(1, \"a\")\nlet n: Map<u8, str> = m;\nn");
+        assert_eq!(map.unwrap().to_string(), "Map { 1: \"a\" }");
+    }
+
+    #[test]
+    fn insert_rejects_values_of_other_types() {
+        let map = run("let m: Map<i32, str> = Map();\nm.insert(\"x\", 5)");
+        assert!(map.is_err());
+        let set = run("let s: Set<str> = Set();\ns.insert(1)");
+        assert!(set.unwrap_err().message.contains("for a value of s"));
+    }
+}
@@ -0,0 +1,348 @@
+use crate::{
+    frontend::ast,
+    runtime::{
+        environment::Environment,
+        errors::{ErrorCategory, RuntimeError},
+        helpers, interpreter, values,
+    },
+};
+
+/// What to do with the names bound by a pattern once it has matched.
+#[derive(Clone, Copy)]
+pub enum BindingMode {
+    /// Declare mutable variables for a match arm
+    Match,
+    /// Declare variables for a destructuring declaration
+    Declare { constant: bool },
+    /// Assign to existing variables for a destructuring assignment
+    Assign,
+}
+
+/// Checks whether a runtime value matches a pattern, binding the names in the
+/// pattern in the given environment according to the binding mode.
+pub fn evaluate_pattern(
+    pattern: &ast::Pattern,
+    runtime_val: &values::RuntimeValue,
+    environment: &mut Environment,
+    mode: BindingMode,
+) -> Result<bool, RuntimeError> {
+    match pattern {
+        ast::Pattern::Wildcard => Ok(true),
+        ast::Pattern::Binding {
+            identifier,
+            value_type,
+            mutable,
+        } => {
+            // `mut name` makes the binding mutable whatever the declaration
+            let mode = match mode {
+                BindingMode::Declare { constant } => BindingMode::Declare {
+                    constant: constant && !mutable,
+                },
+                mode => mode,
+            };
+            evaluate_binding(
+                identifier,
+                value_type,
+                runtime_val.to_owned(),
+                environment,
+                mode,
+            )?;
+            Ok(true)
+        }
+        ast::Pattern::Literal(expression) => {
+            match evaluate_pattern_literal(expression, runtime_val, environment)? {
+                Some(literal) => Ok(helpers::runtime_values_equal(&literal, runtime_val)),
+                None => Ok(false),
+            }
+        }
+        ast::Pattern::Range {
+            start,
+            end,
+            inclusive,
+        } => {
+            let start = evaluate_pattern_literal(start, runtime_val, environment)?;
+            let end = evaluate_pattern_literal(end, runtime_val, environment)?;
+            let (start, end) = match (start, end) {
+                (Some(start), Some(end)) => (start, end),
+                _ => return Ok(false),
+            };
+
+            let after_start = matches!(
+                helpers::compare_runtime_values(runtime_val, &start),
+                Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)
+            );
+            let before_end = match helpers::compare_runtime_values(runtime_val, &end) {
+                Some(std::cmp::Ordering::Less) => true,
+                Some(std::cmp::Ordering::Equal) => *inclusive,
+                _ => false,
+            };
+
+            Ok(after_start && before_end)
+        }
+        ast::Pattern::Tuple(patterns) => match runtime_val {
+            values::RuntimeValue::Tuple(tuple) if tuple.value.len() == patterns.len() => {
+                evaluate_patterns(patterns, &tuple.value, environment, mode)
+            }
+            _ => Ok(false),
+        },
+        ast::Pattern::Array { head, rest, tail } => {
+            let elements = match runtime_val {
+                values::RuntimeValue::Array(array) => &array.value,
+                _ => return Ok(false),
+            };
+
+            let fixed_length = head.len() + tail.len();
+            match rest {
+                Some(_) if elements.len() < fixed_length => return Ok(false),
+                None if elements.len() != fixed_length => return Ok(false),
+                _ => (),
+            }
+
+            let tail_start = elements.len() - tail.len();
+            if !evaluate_patterns(head, &elements[..head.len()], environment, mode)?
+                || !evaluate_patterns(tail, &elements[tail_start..], environment, mode)?
+            {
+                return Ok(false);
+            }
+
+            match rest {
+                Some(rest) => evaluate_pattern(
+                    rest,
+                    &values::ArrayValue::create(elements[head.len()..tail_start].to_vec()).as_raw(),
+                    environment,
+                    mode,
+                ),
+                None => Ok(true),
+            }
+        }
+        ast::Pattern::Object(fields) => {
+            let object = match runtime_val {
+                values::RuntimeValue::Object(object) => object,
+                _ => return Ok(false),
+            };
+            for (field, pattern) in fields {
+                let value = match object.get(field) {
+                    Some(value) => value,
+                    None => return Ok(false),
+                };
+                if !evaluate_pattern(pattern, value, environment, mode)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        ast::Pattern::EnumVariant {
+            enum_identifier,
+            variant_identifier,
+            payload,
+        } => evaluate_enum_variant_pattern(
+            enum_identifier,
+            variant_identifier,
+            payload,
+            runtime_val,
+            environment,
+            mode,
+        ),
+    }
+}
+
+fn evaluate_patterns(
+    patterns: &[ast::Pattern],
+    runtime_values: &[values::RuntimeValue],
+    environment: &mut Environment,
+    mode: BindingMode,
+) -> Result<bool, RuntimeError> {
+    for (pattern, runtime_val) in patterns.iter().zip(runtime_values) {
+        if !evaluate_pattern(pattern, runtime_val, environment, mode)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn evaluate_binding(
+    identifier: &str,
+    value_type: &Option<ast::TypeExpr>,
+    runtime_val: values::RuntimeValue,
+    environment: &mut Environment,
+    mode: BindingMode,
+) -> Result<(), RuntimeError> {
+    match mode {
+        BindingMode::Match => environment
+            .declare_variable(identifier.to_string(), runtime_val, false)
+            .map_err(RuntimeError::from),
+        BindingMode::Declare { constant } => {
+            let value_type = value_type
+                .as_ref()
+                .and_then(|value_type| environment.resolve_type(value_type));
+            let runtime_val = helpers::evaluate_variable_type(
+                value_type.as_ref(),
+                identifier.to_string(),
+                runtime_val,
+            )?;
+            match value_type {
+                Some(value_type) => environment.declare_typed_variable(
+                    identifier.to_string(),
+                    runtime_val,
+                    value_type,
+                    constant,
+                ),
+                None => environment.declare_variable(identifier.to_string(), runtime_val, constant),
+            }
+            .map_err(RuntimeError::from)
+        }
+        BindingMode::Assign => {
+            if environment.lookup_binding(identifier).is_none() {
+                return Err(RuntimeError::create(
+                    ErrorCategory::UndefinedVariable,
+                    format!(
+                        "Unknown variable: {} detected during variable assignment",
+                        identifier
+                    ),
+                ));
+            }
+            let runtime_val = helpers::evaluate_variable_type(
+                environment.declared_type(identifier).as_ref(),
+                identifier.to_string(),
+                runtime_val,
+            )?;
+            environment
+                .assign_variable(identifier.to_string(), runtime_val)
+                .map_err(RuntimeError::from)
+        }
+    }
+}
+
+/// Evaluates a literal used in a pattern, converting numeric literals into the
+/// type of the value being matched. Returns `None` when the literal cannot be
+/// represented by that type, in which case the pattern can never match.
+fn evaluate_pattern_literal(
+    expression: &ast::Expression,
+    runtime_val: &values::RuntimeValue,
+    environment: &mut Environment,
+) -> Result<Option<values::RuntimeValue>, RuntimeError> {
+    let literal = interpreter::evaluate(
+        ast::Statement::Expression(expression.to_owned()),
+        environment,
+    )?;
+
+    if !helpers::runtime_value_is_digit(&literal) || !helpers::runtime_value_is_digit(runtime_val) {
+        return Ok(Some(literal));
+    }
+
+    match helpers::evaluate_variable_type(
+        Some(&runtime_val.to_owned().as_value_type().as_type_expr()),
+        String::from("match pattern"),
+        literal,
+    ) {
+        Ok(literal) => Ok(Some(literal)),
+        Err(_) => Ok(None),
+    }
+}
+
+fn evaluate_enum_variant_pattern(
+    enum_identifier: &str,
+    variant_identifier: &str,
+    payload: &ast::EnumPayload<ast::Pattern>,
+    runtime_val: &values::RuntimeValue,
+    environment: &mut Environment,
+    mode: BindingMode,
+) -> Result<bool, RuntimeError> {
+    let enum_value = match runtime_val {
+        values::RuntimeValue::Enum(enum_value)
+            if enum_value.value_type == values::ValueType::Enum(enum_identifier.to_string()) =>
+        {
+            enum_value
+        }
+        _ => {
+            return Err(format!(
+                "Cannot match {} against the pattern {}::{}",
+                runtime_val.to_owned().as_value_type().as_string(),
+                enum_identifier,
+                variant_identifier
+            )
+            .into())
+        }
+    };
+
+    match environment.lookup_enum(enum_identifier) {
+        Some(declaration) if declaration.find_variant(variant_identifier).is_some() => (),
+        _ => {
+            return Err(format!(
+                "Enum {} has no variant named {}",
+                enum_identifier, variant_identifier
+            )
+            .into())
+        }
+    }
+
+    if enum_value.variant != variant_identifier {
+        return Ok(false);
+    }
+
+    match (payload, &enum_value.payload) {
+        (ast::EnumPayload::Unit, ast::EnumPayload::Unit) => Ok(true),
+        (ast::EnumPayload::Tuple(patterns), ast::EnumPayload::Tuple(values)) => {
+            if patterns.len() != values.len() {
+                return Err(format!(
+                    "Pattern {}::{} expects {} value(s), but the variant holds {}",
+                    enum_identifier,
+                    variant_identifier,
+                    patterns.len(),
+                    values.len()
+                )
+                .into());
+            }
+            evaluate_patterns(patterns, values, environment, mode)
+        }
+        (ast::EnumPayload::Struct(patterns), ast::EnumPayload::Struct(fields)) => {
+            for (field, pattern) in patterns {
+                let value = match fields.iter().find(|(identifier, _)| identifier == field) {
+                    Some((_, value)) => value,
+                    None => {
+                        return Err(format!(
+                            "Variant {}::{} has no field named {}",
+                            enum_identifier, variant_identifier, field
+                        )
+                        .into())
+                    }
+                };
+                if !evaluate_pattern(pattern, value, environment, mode)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        _ => Err(format!(
+            "Pattern {}::{} does not match the shape of the variant's payload",
+            enum_identifier, variant_identifier
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frontend::parser::Parser;
+    use crate::runtime::{environment::Environment, errors::RuntimeError, interpreter, values};
+
+    fn run(source: &str) -> Result<values::RuntimeValue, RuntimeError> {
+        let program = Parser::new().produce_ast(source)?;
+        let mut environment = Environment::create(None);
+        environment.init_global_scope()?;
+        interpreter::evaluate(program, &mut environment)
+    }
+
+    #[test]
+    fn names_unmatched_values_by_value_and_type() {
+        let unmatched = run("match 5 { 1..=3 => 1 }").unwrap_err();
+        assert_eq!(unmatched.message, "No match arm matched the value 5");
+        let guard = run("match 5 { x if 1 => 1 }").unwrap_err();
+        assert_eq!(
+            guard.message,
+            "Match guards must evaluate to a bool, got: i32"
+        );
+        let variant = run("enum E { A }\nmatch 5 { E::A => 1, _ => 2 }").unwrap_err();
+        assert_eq!(variant.message, "Cannot match i32 against the pattern E::A");
+    }
+}
@@ -1,34 +1,124 @@
+use std::rc::Rc;
+
 use crate::{
     frontend::ast,
-    runtime::{environment::Environment, helpers, interpreter, values},
+    runtime::{
+        environment::Environment,
+        errors::{ErrorCategory, RuntimeError},
+        evaluation::patterns,
+        helpers, interpreter, values,
+    },
 };
 
 pub fn evaluate_program(
     program: ast::ProgramStatement,
     environment: &mut Environment,
-) -> Result<values::RuntimeValue, String> {
+) -> Result<values::RuntimeValue, RuntimeError> {
+    evaluate_statements(program.body, environment)
+}
+
+/// Evaluates statements in order, returning the value of the last one.
+/// Errors are tagged with the location of the statement that raised them.
+fn evaluate_statements(
+    body: Vec<ast::LocatedStatement>,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
     let mut last_evaluated = values::NullValue::create().as_raw();
 
-    for statment in program.body {
-        last_evaluated = match interpreter::evaluate(statment, environment) {
+    for located in body {
+        last_evaluated = match interpreter::evaluate(located.statement, environment) {
             Ok(runtime_val) => runtime_val,
-            Err(m) => return Err(m),
+            Err(error) => return Err(error.at(located.location)),
         }
     }
 
     Ok(last_evaluated)
 }
 
+pub fn evaluate_block_statement(
+    block: ast::BlockStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    environment.enter_scope();
+    let result = evaluate_statements(block.body, environment);
+    environment.exit_scope()?;
+
+    result
+}
+
+pub fn evaluate_throw_statement(
+    throw_statement: ast::ThrowStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let value = interpreter::evaluate(
+        ast::Statement::Expression(throw_statement.value),
+        environment,
+    )?;
+
+    Err(RuntimeError::with_value(
+        ErrorCategory::Thrown,
+        value.to_string(),
+        value,
+    ))
+}
+
+pub fn evaluate_try_statement(
+    try_statement: ast::TryStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let result = evaluate_block_statement(try_statement.body, environment);
+
+    // `?` returning early from the enclosing function is not an error to
+    // catch, only the finally block runs
+    let result = match (result, try_statement.catch_clause) {
+        (Err(error), Some(catch_clause)) if error.category != ErrorCategory::Propagated => {
+            environment.enter_scope();
+            let result = evaluate_catch_clause(catch_clause, error, environment);
+            environment.exit_scope()?;
+            result
+        }
+        (result, _) => result,
+    };
+
+    // An error raised by the finally block replaces the result of the try
+    // and catch blocks
+    if let Some(finally_body) = try_statement.finally_body {
+        evaluate_block_statement(finally_body, environment)?;
+    }
+
+    result
+}
+
+fn evaluate_catch_clause(
+    catch_clause: ast::CatchClause,
+    error: RuntimeError,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if let Some(identifier) = catch_clause.identifier {
+        environment.declare_variable(identifier, error.as_object(), false)?;
+    }
+
+    evaluate_statements(catch_clause.body.body, environment)
+}
+
 pub fn evaluate_variable_declaration(
     variable_declaration: ast::VariableDeclarationStatement,
     environment: &mut Environment,
-) -> Result<values::RuntimeValue, String> {
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let value_type = variable_declaration
+        .value_type
+        .as_ref()
+        .and_then(|value_type| environment.resolve_type(value_type));
+    let constant = environment
+        .mutability()
+        .is_immutable(variable_declaration.constant, variable_declaration.mutable);
+
     let value = match variable_declaration.to_owned().value {
         Some(expression) => {
             match interpreter::evaluate(ast::Statement::Expression(expression), environment) {
                 Ok(runtime_val) => {
                     match helpers::evaluate_variable_type(
-                        variable_declaration.to_owned().value_type,
+                        value_type.as_ref(),
                         variable_declaration.to_owned().identifier,
                         runtime_val.to_owned(),
                     ) {
@@ -39,17 +129,154 @@ pub fn evaluate_variable_declaration(
                 Err(m) => return Err(m),
             }
         }
-        None => values::NullValue::create().as_raw(),
+        None => {
+            environment.declare_unassigned_variable(
+                variable_declaration.identifier,
+                value_type,
+                constant,
+            )?;
+            return Ok(values::NullValue::create().as_raw());
+        }
     };
 
-    match environment.declare_variable(
-        variable_declaration.identifier,
-        value.to_owned(),
-        variable_declaration.constant,
-    ) {
+    let declared = match value_type {
+        Some(value_type) => environment.declare_typed_variable(
+            variable_declaration.identifier,
+            value.to_owned(),
+            value_type,
+            constant,
+        ),
+        None => environment.declare_variable(
+            variable_declaration.identifier,
+            value.to_owned(),
+            constant,
+        ),
+    };
+    match declared {
         Ok(_) => (),
-        Err(m) => return Err(m),
+        Err(m) => return Err(m.into()),
     }
 
     Ok(value)
 }
+
+pub fn evaluate_destructuring_declaration(
+    destructuring_declaration: ast::DestructuringDeclarationStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let value = interpreter::evaluate(
+        ast::Statement::Expression(destructuring_declaration.value),
+        environment,
+    )?;
+
+    if !patterns::evaluate_pattern(
+        &destructuring_declaration.pattern,
+        &value,
+        environment,
+        patterns::BindingMode::Declare {
+            constant: environment.mutability().is_immutable(
+                destructuring_declaration.constant,
+                destructuring_declaration.mutable,
+            ),
+        },
+    )? {
+        return Err(format!(
+            "Cannot destructure a value of type {} with the given pattern",
+            value.as_value_type().as_string()
+        )
+        .into());
+    }
+
+    Ok(value)
+}
+
+pub fn evaluate_enum_declaration(
+    mut enum_declaration: ast::EnumDeclarationStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let type_parameters = &enum_declaration.type_parameters;
+    let expand =
+        |value_type: &ast::TypeExpr| environment.expand_type_aliases(value_type, type_parameters);
+    for variant in &mut enum_declaration.variants {
+        variant.payload = match &variant.payload {
+            ast::EnumPayload::Unit => ast::EnumPayload::Unit,
+            ast::EnumPayload::Tuple(payload_types) => {
+                ast::EnumPayload::Tuple(payload_types.iter().map(expand).collect())
+            }
+            ast::EnumPayload::Struct(fields) => ast::EnumPayload::Struct(
+                fields
+                    .iter()
+                    .map(|(field, value_type)| (field.to_owned(), expand(value_type)))
+                    .collect(),
+            ),
+        };
+    }
+    environment.declare_enum(enum_declaration)?;
+
+    Ok(values::NullValue::create().as_raw())
+}
+
+pub fn evaluate_struct_declaration(
+    mut struct_declaration: ast::StructDeclarationStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let type_parameters = ast::type_parameter_identifiers(&struct_declaration.type_parameters);
+    for (_, field_type) in &mut struct_declaration.fields {
+        *field_type = environment.expand_type_aliases(field_type, &type_parameters);
+    }
+    environment.declare_struct(Rc::new(struct_declaration))?;
+
+    Ok(values::NullValue::create().as_raw())
+}
+
+pub fn evaluate_type_alias(
+    type_alias: ast::TypeAliasStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    environment.declare_type_alias(type_alias.identifier, type_alias.value_type)?;
+
+    Ok(values::NullValue::create().as_raw())
+}
+
+pub fn evaluate_newtype_declaration(
+    mut newtype_declaration: ast::NewtypeDeclarationStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    newtype_declaration.value_type =
+        environment.expand_type_aliases(&newtype_declaration.value_type, &[]);
+    environment.declare_newtype(newtype_declaration)?;
+
+    Ok(values::NullValue::create().as_raw())
+}
+
+/// Imports are bound by the module loader before the module is evaluated,
+/// so all that is left is to make sure they were at the top level.
+pub fn evaluate_import_statement(
+    import_statement: ast::ImportStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if !environment.is_global() {
+        return Err(format!(
+            "Cannot import \"{}\" outside the top level of a module",
+            import_statement.specifier
+        )
+        .into());
+    }
+
+    Ok(values::NullValue::create().as_raw())
+}
+
+pub fn evaluate_export_statement(
+    export_statement: ast::ExportStatement,
+    environment: &mut Environment,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    if !environment.is_global() {
+        return Err(
+            "Cannot export declarations outside the top level of a module"
+                .to_string()
+                .into(),
+        );
+    }
+
+    interpreter::evaluate(*export_statement.declaration, environment)
+}
@@ -0,0 +1,211 @@
+use super::super::frontend::{ast, prelude};
+use super::errors::{ErrorCategory, RuntimeError};
+use super::values;
+use std::collections::HashMap;
+
+/// Infers the type arguments of a generic function or struct by matching a
+/// declared type against the value given for it. Type parameters that are
+/// already bound keep their type, so later mismatches are caught when the
+/// value is checked against the instantiated type.
+pub fn infer_type_arguments(
+    value_type: &ast::TypeExpr,
+    runtime_val: &values::RuntimeValue,
+    type_parameters: &[String],
+    type_arguments: &mut HashMap<String, ast::TypeExpr>,
+) {
+    match (value_type, runtime_val) {
+        // Null says nothing about the type it stands in for
+        (_, values::RuntimeValue::Null(_)) => (),
+        (
+            ast::TypeExpr::Named {
+                identifier,
+                type_arguments: named_arguments,
+            },
+            _,
+        ) if named_arguments.is_empty() && type_parameters.contains(identifier) => {
+            type_arguments
+                .entry(identifier.to_owned())
+                .or_insert(runtime_val.to_owned().as_value_type().as_type_expr());
+        }
+        (
+            ast::TypeExpr::Named {
+                identifier,
+                type_arguments: named_arguments,
+            },
+            values::RuntimeValue::Enum(enum_value),
+        ) if enum_value.value_type == values::ValueType::Enum(identifier.to_owned()) => {
+            if let (Some(index), ast::EnumPayload::Tuple(payload_values)) = (
+                prelude::payload_type_argument(identifier, &enum_value.variant),
+                &enum_value.payload,
+            ) {
+                if let (Some(named_argument), [payload_value]) =
+                    (named_arguments.get(index), payload_values.as_slice())
+                {
+                    infer_type_arguments(
+                        named_argument,
+                        payload_value,
+                        type_parameters,
+                        type_arguments,
+                    );
+                }
+            }
+        }
+        (
+            ast::TypeExpr::Named {
+                identifier,
+                type_arguments: named_arguments,
+            },
+            values::RuntimeValue::Struct(struct_value),
+        ) if struct_value.declaration.identifier == *identifier => {
+            let field_arguments = struct_type_arguments(&struct_value.declaration, named_arguments);
+            for (field, field_value) in &struct_value.fields {
+                if let Some(field_type) = struct_value.declaration.field_type(field) {
+                    infer_type_arguments(
+                        &field_type.substitute(&field_arguments),
+                        field_value,
+                        type_parameters,
+                        type_arguments,
+                    );
+                }
+            }
+        }
+        (ast::TypeExpr::Array(element_type), values::RuntimeValue::Array(array)) => {
+            for element in &array.value {
+                infer_type_arguments(element_type, element, type_parameters, type_arguments);
+            }
+        }
+        (
+            ast::TypeExpr::Named {
+                identifier,
+                type_arguments: named_arguments,
+            },
+            values::RuntimeValue::Map(map),
+        ) if identifier == "Map" && named_arguments.len() == 2 => {
+            for (key, value) in map.value.entries() {
+                infer_type_arguments(&named_arguments[0], key, type_parameters, type_arguments);
+                infer_type_arguments(&named_arguments[1], value, type_parameters, type_arguments);
+            }
+        }
+        (
+            ast::TypeExpr::Named {
+                identifier,
+                type_arguments: named_arguments,
+            },
+            values::RuntimeValue::Set(set),
+        ) if identifier == "Set" && named_arguments.len() == 1 => {
+            for value in set.value.values() {
+                infer_type_arguments(&named_arguments[0], value, type_parameters, type_arguments);
+            }
+        }
+        (ast::TypeExpr::Tuple(element_types), values::RuntimeValue::Tuple(tuple))
+            if element_types.len() == tuple.value.len() =>
+        {
+            for (element_type, element) in element_types.iter().zip(&tuple.value) {
+                infer_type_arguments(element_type, element, type_parameters, type_arguments);
+            }
+        }
+        (ast::TypeExpr::Optional(value_type), _) => {
+            infer_type_arguments(value_type, runtime_val, type_parameters, type_arguments)
+        }
+        _ => (),
+    }
+}
+
+/// Maps the type parameters of a struct to the type arguments given in an
+/// annotation such as `Pair<i32, str>`.
+pub fn struct_type_arguments(
+    declaration: &ast::StructDeclarationStatement,
+    type_arguments: &[ast::TypeExpr],
+) -> HashMap<String, ast::TypeExpr> {
+    declaration
+        .type_parameters
+        .iter()
+        .map(|type_parameter| type_parameter.identifier.to_owned())
+        .zip(type_arguments.iter().cloned())
+        .collect()
+}
+
+/// Names an instantiation of a generic function or struct, such as
+/// `first<i32>`. Type parameters that could not be inferred are written `_`.
+pub fn instantiation(
+    identifier: &str,
+    type_parameters: &[ast::TypeParameter],
+    type_arguments: &HashMap<String, ast::TypeExpr>,
+) -> String {
+    if type_parameters.is_empty() {
+        return identifier.to_string();
+    }
+
+    let type_arguments: Vec<String> = type_parameters
+        .iter()
+        .map(
+            |type_parameter| match type_arguments.get(&type_parameter.identifier) {
+                Some(type_argument) => type_argument.to_string(),
+                None => String::from("_"),
+            },
+        )
+        .collect();
+    format!("{}<{}>", identifier, type_arguments.join(", "))
+}
+
+/// Names the instantiation an error was raised in.
+pub fn in_instantiation(mut error: RuntimeError, instantiation: &str) -> RuntimeError {
+    error.message = format!("{} (in {})", error.message, instantiation);
+    error
+}
+
+/// Checks that the type arguments implement the traits bounding their type
+/// parameters.
+pub fn check_bounds(
+    type_parameters: &[ast::TypeParameter],
+    type_arguments: &HashMap<String, ast::TypeExpr>,
+    instantiation: &str,
+) -> Result<(), RuntimeError> {
+    for type_parameter in type_parameters {
+        let type_argument = match type_arguments.get(&type_parameter.identifier) {
+            Some(type_argument) => type_argument,
+            None => continue,
+        };
+        for bound in &type_parameter.bounds {
+            if !prelude::implements_trait(bound, type_argument) {
+                return Err(RuntimeError::create(
+                    ErrorCategory::TypeMismatch,
+                    format!(
+                        "{} does not implement {}, required by {}: {} (in {})",
+                        type_argument,
+                        bound,
+                        type_parameter.identifier,
+                        type_parameter.bounds.join(" + "),
+                        instantiation
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a function can be given to a variable annotated with a function
+/// type. Type parameters of the function accept any type, and functions
+/// without a declared return type accept any return type.
+pub fn function_type_matches(
+    parameters: &[ast::TypeExpr],
+    return_type: &ast::TypeExpr,
+    function: &values::FunctionValue,
+) -> bool {
+    let type_parameters = ast::type_parameter_identifiers(&function.declaration.type_parameters);
+    let matches = |declared: &ast::TypeExpr, actual: &ast::TypeExpr| {
+        actual.mentions(&type_parameters) || declared == actual
+    };
+
+    parameters.len() == function.declaration.parameters.len()
+        && parameters
+            .iter()
+            .zip(&function.declaration.parameters)
+            .all(|(declared, (_, actual))| matches(declared, actual))
+        && match &function.declaration.return_type {
+            Some(actual) => matches(return_type, actual),
+            None => true,
+        }
+}
@@ -1,180 +1,543 @@
-use super::super::frontend::ast;
+use super::super::frontend::{ast, prelude};
+use super::collections::{OrderedMap, OrderedSet};
+use super::decimal::{Decimal, RoundingMode};
+use super::errors::{ErrorCategory, RuntimeError};
+use super::generics;
 use super::values;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use std::cmp::Ordering;
 
 pub fn evaluate_variable_type(
-    value_type: Option<ast::IdentifierExpression>,
+    value_type: Option<&ast::TypeExpr>,
     identifier: String,
     runtime_val: values::RuntimeValue,
-) -> Result<values::RuntimeValue, String> {
-    if value_type.is_none() {
-        Ok(runtime_val)
-    } else {
-        match value_type.to_owned().unwrap().symbol.as_str() {
-            "i8" => {
-                if runtime_digit_is_i8(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_i8() {
-                        Ok(integer8) => Ok(integer8.as_raw()),
-                        Err(m) => Err(m),
-                    }
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let value_type = match value_type {
+        Some(value_type) => value_type,
+        None => return Ok(runtime_val),
+    };
+
+    // Annotations are not nullable unless they are written `T?`
+    if matches!(runtime_val, values::RuntimeValue::Null(_))
+        && !matches!(value_type, ast::TypeExpr::Optional(_))
+        && *value_type != ast::TypeExpr::named("null")
+    {
+        let mut message = format!(
+            "Incorrect runtime value for {}, expected: {}, got: null",
+            identifier, value_type
+        );
+        if !matches!(value_type, ast::TypeExpr::Function { .. }) {
+            message.push_str(&format!(", use {}? to allow null", value_type));
+        }
+        return Err(RuntimeError::create(ErrorCategory::TypeMismatch, message));
+    }
+
+    match value_type {
+        ast::TypeExpr::Named {
+            identifier: symbol,
+            type_arguments,
+        } if type_arguments.is_empty() => evaluate_named_type(symbol, identifier, runtime_val),
+        ast::TypeExpr::Named {
+            identifier: collection_identifier,
+            type_arguments,
+        } if collection_identifier == "Map" || collection_identifier == "Set" => {
+            evaluate_collection_type(value_type, type_arguments, identifier, runtime_val)
+        }
+        ast::TypeExpr::Named {
+            identifier: struct_identifier,
+            type_arguments,
+        } if matches!(runtime_val, values::RuntimeValue::Struct(_)) => {
+            evaluate_generic_struct_type(
+                value_type,
+                struct_identifier,
+                type_arguments,
+                identifier,
+                runtime_val,
+            )
+        }
+        ast::TypeExpr::Named {
+            identifier: enum_identifier,
+            type_arguments,
+        } => evaluate_generic_enum_type(
+            value_type,
+            enum_identifier,
+            type_arguments,
+            identifier,
+            runtime_val,
+        ),
+        ast::TypeExpr::Tuple(element_types) => {
+            evaluate_tuple_type(value_type, element_types, identifier, runtime_val)
+        }
+        ast::TypeExpr::Array(element_type) => {
+            evaluate_array_type(value_type, element_type, identifier, runtime_val)
+        }
+        ast::TypeExpr::Optional(value_type) => match runtime_val {
+            values::RuntimeValue::Null(_) => Ok(runtime_val),
+            _ => evaluate_variable_type(Some(value_type), identifier, runtime_val),
+        },
+        ast::TypeExpr::Function {
+            parameters,
+            return_type,
+        } => match &runtime_val {
+            values::RuntimeValue::Function(function)
+                if generics::function_type_matches(parameters, return_type, function) =>
+            {
+                Ok(runtime_val)
+            }
+            // Native functions do not declare types, only how many
+            // arguments they take
+            values::RuntimeValue::NativeFunction(function)
+                if function.accepts(parameters.len()) =>
+            {
+                Ok(runtime_val)
+            }
+            values::RuntimeValue::Function(function) => Err(RuntimeError::create(
+                ErrorCategory::TypeMismatch,
+                format!(
+                    "Incorrect runtime value for {}, expected: {}, got: {}",
+                    identifier,
+                    value_type,
+                    function.signature()
+                ),
+            )),
+            _ => Err(type_mismatch(value_type, &identifier, &runtime_val)),
+        },
+    }
+}
+
+fn type_mismatch(
+    value_type: &ast::TypeExpr,
+    identifier: &str,
+    runtime_val: &values::RuntimeValue,
+) -> RuntimeError {
+    RuntimeError::create(
+        ErrorCategory::TypeMismatch,
+        format!(
+            "Incorrect runtime value for {}, expected: {}, got: {}",
+            identifier,
+            value_type,
+            runtime_val.to_owned().as_value_type().as_string()
+        ),
+    )
+}
+
+/// Primitive types and user types such as enums.
+fn evaluate_named_type(
+    symbol: &str,
+    identifier: String,
+    runtime_val: values::RuntimeValue,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    // Newtypes are never converted to or from the type they wrap
+    if let values::RuntimeValue::Newtype(newtype) = &runtime_val {
+        if newtype.value_type.to_owned().as_string() != symbol {
+            return Err(type_mismatch(
+                &ast::TypeExpr::named(symbol),
+                &identifier,
+                &runtime_val,
+            ));
+        }
+    }
+
+    match symbol {
+        "i8" => {
+            if runtime_digit_is_i8(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_i8() {
+                    Ok(integer8) => Ok(integer8.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
+                }
+            }
+        }
+        "i16" => {
+            if runtime_digit_is_i16(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_i16() {
+                    Ok(integer16) => Ok(integer16.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "i16" => {
-                if runtime_digit_is_i16(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_i16() {
-                        Ok(integer16) => Ok(integer16.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "i32" => {
+            if runtime_digit_is_i32(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_i32() {
+                    Ok(integer32) => Ok(integer32.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "i32" => {
-                if runtime_digit_is_i32(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_i32() {
-                        Ok(integer32) => Ok(integer32.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "i64" => {
+            if runtime_digit_is_i64(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_i64() {
+                    Ok(integer64) => Ok(integer64.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "i64" => {
-                if runtime_digit_is_i64(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_i64() {
-                        Ok(integer64) => Ok(integer64.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "i128" => {
+            if runtime_digit_is_i128(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_i128() {
+                    Ok(integer128) => Ok(integer128.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "i128" => {
-                if runtime_digit_is_i128(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_i128() {
-                        Ok(integer128) => Ok(integer128.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "u8" => {
+            if runtime_digit_is_u8(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_u8() {
+                    Ok(unsigned_integer8) => Ok(unsigned_integer8.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "u8" => {
-                if runtime_digit_is_u8(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_u8() {
-                        Ok(unsigned_integer8) => Ok(unsigned_integer8.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "u16" => {
+            if runtime_digit_is_u16(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_u16() {
+                    Ok(unsigned_integer16) => Ok(unsigned_integer16.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "u16" => {
-                if runtime_digit_is_u16(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_u16() {
-                        Ok(unsigned_integer16) => Ok(unsigned_integer16.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "u32" => {
+            if runtime_digit_is_u32(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_u32() {
+                    Ok(unsigned_integer32) => Ok(unsigned_integer32.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "u32" => {
-                if runtime_digit_is_u32(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_u32() {
-                        Ok(unsigned_integer32) => Ok(unsigned_integer32.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "u64" => {
+            if runtime_digit_is_u64(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_u64() {
+                    Ok(unsigned_integer64) => Ok(unsigned_integer64.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "u64" => {
-                if runtime_digit_is_u64(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_u64() {
-                        Ok(unsigned_integer64) => Ok(unsigned_integer64.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "u128" => {
+            if runtime_digit_is_u128(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_u128() {
+                    Ok(unsigned_integer128) => Ok(unsigned_integer128.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "u128" => {
-                if runtime_digit_is_u128(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_u128() {
-                        Ok(unsigned_integer128) => Ok(unsigned_integer128.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "bigint" => {
+            if runtime_digit_is_bigint(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_bigint() {
+                    Ok(bigint) => Ok(bigint.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "f32" => {
-                if runtime_digit_is_f32(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_f32() {
-                        Ok(float32) => Ok(float32.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "decimal" => {
+            if runtime_digit_is_decimal(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_decimal() {
+                    Ok(decimal) => Ok(decimal.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "f64" => {
-                if runtime_digit_is_f64(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    match runtime_val.as_f64() {
-                        Ok(float64) => Ok(float64.as_raw()),
-                        Err(m) => Err(m),
-                    }
+        }
+        "f32" => {
+            if runtime_digit_is_f32(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_f32() {
+                    Ok(float32) => Ok(float32.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "char" => {
-                if runtime_is_char(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    return Err(format!(
-                        "Incorrect runtime value for {}, expected: char, got: {:#?}",
-                        identifier,
-                        runtime_val.as_value_type()
-                    ));
+        }
+        "f64" => {
+            if runtime_digit_is_f64(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                match runtime_val.as_f64() {
+                    Ok(float64) => Ok(float64.as_raw()),
+                    Err(m) => Err(RuntimeError::create(ErrorCategory::InvalidCast, m)),
                 }
             }
-            "str" => {
-                if runtime_is_str(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    return Err(format!(
-                        "Incorrect runtime value for {}, expected: str, got: {:#?}",
+        }
+        "char" => {
+            if runtime_is_char(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                return Err(RuntimeError::create(
+                    ErrorCategory::TypeMismatch,
+                    format!(
+                        "Incorrect runtime value for {}, expected: char, got: {}",
                         identifier,
-                        runtime_val.as_value_type()
-                    ));
-                }
+                        runtime_val.as_value_type().as_string()
+                    ),
+                ));
             }
-            "bool" => {
-                if runtime_is_bool(&runtime_val) {
-                    Ok(runtime_val)
-                } else {
-                    return Err(format!(
-                        "Incorrect runtime value for {}, expected: bool, got: {:#?}",
+        }
+        "str" => {
+            if runtime_is_str(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                return Err(RuntimeError::create(
+                    ErrorCategory::TypeMismatch,
+                    format!(
+                        "Incorrect runtime value for {}, expected: str, got: {}",
                         identifier,
-                        runtime_val.as_value_type()
-                    ));
-                }
+                        runtime_val.as_value_type().as_string()
+                    ),
+                ));
             }
-            _ => {
-                return Err(format!(
-                    "Unexpected variable type given during variable declaration/assignment evaluation, got {}",
-                    value_type.unwrap().symbol.as_str()
-                ))
+        }
+        "bool" => {
+            if runtime_is_bool(&runtime_val) {
+                Ok(runtime_val)
+            } else {
+                return Err(RuntimeError::create(
+                    ErrorCategory::TypeMismatch,
+                    format!(
+                        "Incorrect runtime value for {}, expected: bool, got: {}",
+                        identifier,
+                        runtime_val.as_value_type().as_string()
+                    ),
+                ));
             }
         }
+        // User defined types such as enums have to match exactly
+        symbol if runtime_val.to_owned().as_value_type().as_string() == symbol => {
+            Ok(runtime_val)
+        }
+        symbol if runtime_is_user_type(&runtime_val) => Err(RuntimeError::create(
+            ErrorCategory::TypeMismatch,
+            format!(
+                "Incorrect runtime value for {}, expected: {}, got: {}",
+                identifier,
+                symbol,
+                runtime_val.as_value_type().as_string()
+            ),
+        )),
+        _ => {
+            return Err(format!(
+                "Unexpected variable type given during variable declaration/assignment evaluation, got {}",
+                symbol
+            )
+            .into())
+        }
     }
 }
 
+fn evaluate_tuple_type(
+    value_type: &ast::TypeExpr,
+    element_types: &[ast::TypeExpr],
+    identifier: String,
+    runtime_val: values::RuntimeValue,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let elements = match runtime_val {
+        values::RuntimeValue::Tuple(tuple) if tuple.value.len() == element_types.len() => {
+            tuple.value
+        }
+        _ => return Err(type_mismatch(value_type, &identifier, &runtime_val)),
+    };
+
+    let mut converted = Vec::new();
+    for (index, (element_type, element)) in element_types.iter().zip(elements).enumerate() {
+        converted.push(evaluate_variable_type(
+            Some(element_type),
+            format!("{}.{}", identifier, index),
+            element,
+        )?);
+    }
+
+    Ok(values::TupleValue::create(converted).as_raw())
+}
+
+fn evaluate_array_type(
+    value_type: &ast::TypeExpr,
+    element_type: &ast::TypeExpr,
+    identifier: String,
+    runtime_val: values::RuntimeValue,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let elements = match runtime_val {
+        values::RuntimeValue::Array(array) => array.value,
+        _ => return Err(type_mismatch(value_type, &identifier, &runtime_val)),
+    };
+
+    let mut converted = Vec::new();
+    for (index, element) in elements.into_iter().enumerate() {
+        converted.push(evaluate_variable_type(
+            Some(element_type),
+            format!("{}[{}]", identifier, index),
+            element,
+        )?);
+    }
+
+    Ok(values::ArrayValue::create(converted).as_raw())
+}
+
+/// Checks a value against `Map<K, V>` or `Set<T>`, coercing each key and
+/// value to its type argument.
+fn evaluate_collection_type(
+    value_type: &ast::TypeExpr,
+    type_arguments: &[ast::TypeExpr],
+    identifier: String,
+    runtime_val: values::RuntimeValue,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    prelude::check_hashable(value_type)
+        .map_err(|message| RuntimeError::create(ErrorCategory::TypeMismatch, message))?;
+    match (runtime_val, type_arguments) {
+        (values::RuntimeValue::Map(map), [key_type, entry_type]) => {
+            let mut converted = Vec::new();
+            for (key, value) in map.value.entries() {
+                let key = evaluate_variable_type(
+                    Some(key_type),
+                    format!("a key of {}", identifier),
+                    key.to_owned(),
+                )?;
+                let value = evaluate_variable_type(
+                    Some(entry_type),
+                    format!("{}[{}]", identifier, key),
+                    value.to_owned(),
+                )?;
+                converted.push((key, value));
+            }
+            Ok(values::MapValue::create(OrderedMap::from_entries(converted)?).as_raw())
+        }
+        (values::RuntimeValue::Set(set), [element_type]) => {
+            let mut converted = Vec::new();
+            for value in set.value.values() {
+                converted.push(evaluate_variable_type(
+                    Some(element_type),
+                    format!("a value of {}", identifier),
+                    value.to_owned(),
+                )?);
+            }
+            Ok(values::SetValue::create(OrderedSet::from_values(converted)?).as_raw())
+        }
+        (runtime_val, _) => Err(type_mismatch(value_type, &identifier, &runtime_val)),
+    }
+}
+
+/// Checks a value against a prelude enum type such as `Option<i32>` or
+/// `Result<i32, str>`, coercing the payload to its type argument.
+fn evaluate_generic_enum_type(
+    value_type: &ast::TypeExpr,
+    enum_identifier: &str,
+    type_arguments: &[ast::TypeExpr],
+    identifier: String,
+    runtime_val: values::RuntimeValue,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let enum_value = match runtime_val {
+        values::RuntimeValue::Enum(enum_value)
+            if enum_value.value_type == values::ValueType::Enum(enum_identifier.to_string()) =>
+        {
+            enum_value
+        }
+        _ => return Err(type_mismatch(value_type, &identifier, &runtime_val)),
+    };
+
+    let payload = match (
+        prelude::payload_type_argument(enum_identifier, &enum_value.variant),
+        enum_value.payload,
+    ) {
+        (Some(index), ast::EnumPayload::Tuple(mut payload_values))
+            if index < type_arguments.len() && payload_values.len() == 1 =>
+        {
+            let payload_value = payload_values.remove(0);
+            ast::EnumPayload::Tuple(vec![evaluate_variable_type(
+                Some(&type_arguments[index]),
+                format!("{}::{}", identifier, enum_value.variant),
+                payload_value,
+            )?])
+        }
+        (_, payload) => payload,
+    };
+
+    Ok(
+        values::EnumValue::create(enum_identifier.to_string(), enum_value.variant, payload)
+            .as_raw(),
+    )
+}
+
+/// Checks a value against a generic struct type such as `Pair<i32, str>`,
+/// coercing each field to its instantiated type.
+fn evaluate_generic_struct_type(
+    value_type: &ast::TypeExpr,
+    struct_identifier: &str,
+    type_arguments: &[ast::TypeExpr],
+    identifier: String,
+    runtime_val: values::RuntimeValue,
+) -> Result<values::RuntimeValue, RuntimeError> {
+    let struct_value = match runtime_val {
+        values::RuntimeValue::Struct(struct_value)
+            if struct_value.declaration.identifier == struct_identifier =>
+        {
+            struct_value
+        }
+        _ => return Err(type_mismatch(value_type, &identifier, &runtime_val)),
+    };
+
+    let declaration = struct_value.declaration.to_owned();
+    if declaration.type_parameters.len() != type_arguments.len() {
+        return Err(RuntimeError::create(
+            ErrorCategory::TypeMismatch,
+            format!(
+                "Struct {} expects {} type argument(s), got {}",
+                struct_identifier,
+                declaration.type_parameters.len(),
+                type_arguments.len()
+            ),
+        ));
+    }
+
+    let field_arguments = generics::struct_type_arguments(&declaration, type_arguments);
+    let instantiation = value_type.to_string();
+    generics::check_bounds(
+        &declaration.type_parameters,
+        &field_arguments,
+        &instantiation,
+    )?;
+
+    let mut fields = Vec::new();
+    for (field, field_value) in struct_value.fields {
+        let field_type = declaration
+            .field_type(&field)
+            .map(|field_type| field_type.substitute(&field_arguments));
+        let field_value = evaluate_variable_type(
+            field_type.as_ref(),
+            format!("{}.{}", identifier, field),
+            field_value,
+        )
+        .map_err(|error| generics::in_instantiation(error, &instantiation))?;
+        fields.push((field, field_value));
+    }
+
+    Ok(values::StructValue::create(declaration, fields).as_raw())
+}
+
 pub fn runtime_value_is_digit(runtime_value: &values::RuntimeValue) -> bool {
-    if runtime_value_is_integer(&runtime_value) || runtime_value_is_float(&runtime_value) {
+    if runtime_value_is_integer(&runtime_value)
+        || runtime_value_is_float(&runtime_value)
+        || runtime_digit_is_decimal(runtime_value)
+    {
         return true;
     }
     false
@@ -198,11 +561,196 @@ pub fn runtime_value_is_integer(runtime_value: &values::RuntimeValue) -> bool {
         | values::RuntimeValue::UnsignedInteger16(_)
         | values::RuntimeValue::UnsignedInteger32(_)
         | values::RuntimeValue::UnsignedInteger64(_)
-        | values::RuntimeValue::UnsignedInteger128(_) => true,
+        | values::RuntimeValue::UnsignedInteger128(_)
+        | values::RuntimeValue::BigInt(_) => true,
         _ => false,
     }
 }
 
+pub fn runtime_value_is_nan(runtime_value: &values::RuntimeValue) -> bool {
+    match runtime_value {
+        values::RuntimeValue::Float32(float) => float.value.is_nan(),
+        values::RuntimeValue::Float64(float) => float.value.is_nan(),
+        values::RuntimeValue::Newtype(newtype) => runtime_value_is_nan(&newtype.value),
+        _ => false,
+    }
+}
+
+pub fn runtime_integer_is_zero(runtime_value: &values::RuntimeValue) -> bool {
+    match &runtime_value {
+        values::RuntimeValue::Integer8(integer) => integer.value == 0,
+        values::RuntimeValue::Integer16(integer) => integer.value == 0,
+        values::RuntimeValue::Integer32(integer) => integer.value == 0,
+        values::RuntimeValue::Integer64(integer) => integer.value == 0,
+        values::RuntimeValue::Integer128(integer) => integer.value == 0,
+        values::RuntimeValue::UnsignedInteger8(integer) => integer.value == 0,
+        values::RuntimeValue::UnsignedInteger16(integer) => integer.value == 0,
+        values::RuntimeValue::UnsignedInteger32(integer) => integer.value == 0,
+        values::RuntimeValue::UnsignedInteger64(integer) => integer.value == 0,
+        values::RuntimeValue::UnsignedInteger128(integer) => integer.value == 0,
+        values::RuntimeValue::BigInt(integer) => integer.value.is_zero(),
+        values::RuntimeValue::Decimal(decimal) => decimal.value.is_zero(),
+        _ => false,
+    }
+}
+
+/// Orders two runtime values of the same type, returns `None` when the values
+/// are of different types or cannot be ordered.
+pub fn compare_runtime_values(
+    left_hand_side: &values::RuntimeValue,
+    right_hand_side: &values::RuntimeValue,
+) -> Option<Ordering> {
+    match (left_hand_side, right_hand_side) {
+        (values::RuntimeValue::Integer8(lhs), values::RuntimeValue::Integer8(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Integer16(lhs), values::RuntimeValue::Integer16(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Integer32(lhs), values::RuntimeValue::Integer32(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Integer64(lhs), values::RuntimeValue::Integer64(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Integer128(lhs), values::RuntimeValue::Integer128(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (
+            values::RuntimeValue::UnsignedInteger8(lhs),
+            values::RuntimeValue::UnsignedInteger8(rhs),
+        ) => lhs.value.partial_cmp(&rhs.value),
+        (
+            values::RuntimeValue::UnsignedInteger16(lhs),
+            values::RuntimeValue::UnsignedInteger16(rhs),
+        ) => lhs.value.partial_cmp(&rhs.value),
+        (
+            values::RuntimeValue::UnsignedInteger32(lhs),
+            values::RuntimeValue::UnsignedInteger32(rhs),
+        ) => lhs.value.partial_cmp(&rhs.value),
+        (
+            values::RuntimeValue::UnsignedInteger64(lhs),
+            values::RuntimeValue::UnsignedInteger64(rhs),
+        ) => lhs.value.partial_cmp(&rhs.value),
+        (
+            values::RuntimeValue::UnsignedInteger128(lhs),
+            values::RuntimeValue::UnsignedInteger128(rhs),
+        ) => lhs.value.partial_cmp(&rhs.value),
+        (values::RuntimeValue::BigInt(lhs), values::RuntimeValue::BigInt(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Decimal(lhs), values::RuntimeValue::Decimal(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Float32(lhs), values::RuntimeValue::Float32(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Float64(lhs), values::RuntimeValue::Float64(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::String(lhs), values::RuntimeValue::String(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Character(lhs), values::RuntimeValue::Character(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        (values::RuntimeValue::Boolean(lhs), values::RuntimeValue::Boolean(rhs)) => {
+            lhs.value.partial_cmp(&rhs.value)
+        }
+        _ => None,
+    }
+}
+
+pub fn runtime_values_equal(
+    left_hand_side: &values::RuntimeValue,
+    right_hand_side: &values::RuntimeValue,
+) -> bool {
+    match (left_hand_side, right_hand_side) {
+        (values::RuntimeValue::Null(_), values::RuntimeValue::Null(_)) => true,
+        (values::RuntimeValue::Enum(lhs), values::RuntimeValue::Enum(rhs)) => {
+            if lhs.value_type != rhs.value_type || lhs.variant != rhs.variant {
+                return false;
+            }
+            match (&lhs.payload, &rhs.payload) {
+                (ast::EnumPayload::Unit, ast::EnumPayload::Unit) => true,
+                (ast::EnumPayload::Tuple(lhs), ast::EnumPayload::Tuple(rhs)) => {
+                    runtime_value_lists_equal(lhs, rhs)
+                }
+                (ast::EnumPayload::Struct(lhs), ast::EnumPayload::Struct(rhs)) => {
+                    lhs.len() == rhs.len()
+                        && lhs.iter().zip(rhs).all(|(lhs, rhs)| {
+                            lhs.0 == rhs.0 && runtime_values_equal(&lhs.1, &rhs.1)
+                        })
+                }
+                _ => false,
+            }
+        }
+        (values::RuntimeValue::Tuple(lhs), values::RuntimeValue::Tuple(rhs)) => {
+            runtime_value_lists_equal(&lhs.value, &rhs.value)
+        }
+        (values::RuntimeValue::Struct(lhs), values::RuntimeValue::Struct(rhs)) => {
+            lhs.value_type == rhs.value_type
+                && lhs
+                    .fields
+                    .iter()
+                    .all(|(field, value)| match rhs.get(field) {
+                        Some(other) => runtime_values_equal(value, other),
+                        None => false,
+                    })
+        }
+        (values::RuntimeValue::Newtype(lhs), values::RuntimeValue::Newtype(rhs)) => {
+            lhs.value_type == rhs.value_type && runtime_values_equal(&lhs.value, &rhs.value)
+        }
+        (values::RuntimeValue::Function(lhs), values::RuntimeValue::Function(rhs)) => {
+            std::rc::Rc::ptr_eq(&lhs.declaration, &rhs.declaration)
+        }
+        (values::RuntimeValue::NativeFunction(lhs), values::RuntimeValue::NativeFunction(rhs)) => {
+            lhs.identifier == rhs.identifier
+        }
+        (values::RuntimeValue::Array(lhs), values::RuntimeValue::Array(rhs)) => {
+            runtime_value_lists_equal(&lhs.value, &rhs.value)
+        }
+        (values::RuntimeValue::Object(lhs), values::RuntimeValue::Object(rhs)) => {
+            lhs.value.len() == rhs.value.len()
+                && lhs.value.iter().all(|(key, value)| match rhs.get(key) {
+                    Some(other) => runtime_values_equal(value, other),
+                    None => false,
+                })
+        }
+        // Maps and sets are equal whatever order their keys were inserted in
+        (values::RuntimeValue::Map(lhs), values::RuntimeValue::Map(rhs)) => {
+            lhs.value.len() == rhs.value.len()
+                && lhs
+                    .value
+                    .entries()
+                    .iter()
+                    .all(|(key, value)| match rhs.value.get(key) {
+                        Ok(Some(other)) => runtime_values_equal(value, other),
+                        _ => false,
+                    })
+        }
+        (values::RuntimeValue::Set(lhs), values::RuntimeValue::Set(rhs)) => {
+            lhs.value.len() == rhs.value.len()
+                && lhs
+                    .value
+                    .values()
+                    .all(|value| rhs.value.contains(value).unwrap_or(false))
+        }
+        _ => compare_runtime_values(left_hand_side, right_hand_side) == Some(Ordering::Equal),
+    }
+}
+
+fn runtime_value_lists_equal(
+    left_hand_side: &[values::RuntimeValue],
+    right_hand_side: &[values::RuntimeValue],
+) -> bool {
+    left_hand_side.len() == right_hand_side.len()
+        && left_hand_side
+            .iter()
+            .zip(right_hand_side)
+            .all(|(lhs, rhs)| runtime_values_equal(lhs, rhs))
+}
+
 pub fn runtime_digit_is_i8(runtime_value: &values::RuntimeValue) -> bool {
     match runtime_value {
         values::RuntimeValue::Integer8(_) => true,
@@ -273,6 +821,14 @@ pub fn runtime_digit_is_u128(runtime_value: &values::RuntimeValue) -> bool {
     }
 }
 
+pub fn runtime_digit_is_bigint(runtime_value: &values::RuntimeValue) -> bool {
+    matches!(runtime_value, values::RuntimeValue::BigInt(_))
+}
+
+pub fn runtime_digit_is_decimal(runtime_value: &values::RuntimeValue) -> bool {
+    matches!(runtime_value, values::RuntimeValue::Decimal(_))
+}
+
 pub fn runtime_digit_is_f32(runtime_value: &values::RuntimeValue) -> bool {
     match runtime_value {
         values::RuntimeValue::Float32(_) => true,
@@ -301,6 +857,22 @@ pub fn runtime_is_str(runtime_value: &values::RuntimeValue) -> bool {
     }
 }
 
+pub fn runtime_is_user_type(runtime_value: &values::RuntimeValue) -> bool {
+    matches!(
+        runtime_value,
+        values::RuntimeValue::Tuple(_)
+            | values::RuntimeValue::Array(_)
+            | values::RuntimeValue::Object(_)
+            | values::RuntimeValue::Map(_)
+            | values::RuntimeValue::Set(_)
+            | values::RuntimeValue::Enum(_)
+            | values::RuntimeValue::Struct(_)
+            | values::RuntimeValue::Newtype(_)
+            | values::RuntimeValue::Function(_)
+            | values::RuntimeValue::NativeFunction(_)
+    )
+}
+
 pub fn runtime_is_bool(runtime_value: &values::RuntimeValue) -> bool {
     match runtime_value {
         values::RuntimeValue::Boolean(_) => true,
@@ -580,6 +1152,35 @@ where
     }
 }
 
+/// Casts a bigint into a fixed-width integer type, erroring when it does
+/// not fit.
+pub fn cast_bigint<T>(value: &BigInt) -> Result<T, String>
+where
+    T: for<'a> TryFrom<&'a BigInt>,
+{
+    T::try_from(value).map_err(|_| {
+        format!(
+            "Value {} does not fit in an {}",
+            value,
+            std::any::type_name::<T>()
+        )
+    })
+}
+
+pub fn cast_bigint_to_f32(value: &BigInt) -> Result<f32, String> {
+    match value.to_f32() {
+        Some(float) if float.is_finite() => Ok(float),
+        _ => Err(format!("Value {} is too large to cast into an f32", value)),
+    }
+}
+
+pub fn cast_bigint_to_f64(value: &BigInt) -> Result<f64, String> {
+    match value.to_f64() {
+        Some(float) if float.is_finite() => Ok(float),
+        _ => Err(format!("Value {} is too large to cast into an f64", value)),
+    }
+}
+
 pub fn cast_to_f32<T>(value: T) -> Result<f32, String>
 where
     T: Into<f32> + Copy,
@@ -633,3 +1234,195 @@ pub fn cast_u64_to_f64(value: u64) -> Result<f64, String> {
 pub fn cast_i128_to_f64(value: i128) -> Result<f64, String> {
     Ok(value as f64)
 }
+
+/// The longest str, in bytes, that `str * n` or a format width will build.
+pub const MAX_STRING_LENGTH: usize = 1 << 30;
+
+/// The largest format precision, the same limit as Rust's `format!`.
+const MAX_FORMAT_PRECISION: usize = u16::MAX as usize;
+
+/// Formats a value for a template string interpolation with a format
+/// specifier, following the behaviour of Rust's `format!`.
+pub fn format_runtime_value(
+    runtime_val: &values::RuntimeValue,
+    format: &ast::FormatSpec,
+) -> Result<String, String> {
+    let limits = [
+        ("width", format.width, MAX_STRING_LENGTH),
+        ("precision", format.precision, MAX_FORMAT_PRECISION),
+    ];
+    for (name, value, limit) in limits {
+        if let Some(value) = value.filter(|value| *value > limit) {
+            return Err(format!(
+                "Cannot format a value with a {} of {}, the limit is {}",
+                name, value, limit
+            ));
+        }
+    }
+    let numeric = runtime_value_is_digit(runtime_val);
+
+    let mut text = match (runtime_val, format.precision) {
+        (values::RuntimeValue::Float32(float), Some(precision)) => {
+            format!("{:.*}", precision, float.value)
+        }
+        (values::RuntimeValue::Float64(float), Some(precision)) => {
+            format!("{:.*}", precision, float.value)
+        }
+        (values::RuntimeValue::Decimal(decimal), Some(precision)) => decimal
+            .value
+            .rescale(precision as u32, RoundingMode::HalfEven)
+            .to_string(),
+        (values::RuntimeValue::String(_) | values::RuntimeValue::Character(_), Some(precision)) => {
+            runtime_val.to_string().chars().take(precision).collect()
+        }
+        _ => runtime_val.to_string(),
+    };
+
+    if numeric && format.sign_plus && !text.starts_with('-') {
+        text.insert(0, '+');
+    }
+
+    let length = text.chars().count();
+    let padding = match format.width {
+        Some(width) if width > length => width - length,
+        _ => return Ok(text),
+    };
+
+    // Zero padding goes between the sign and the digits
+    if numeric && format.zero_pad {
+        let (sign, digits) = match text.starts_with(['+', '-']) {
+            true => text.split_at(1),
+            false => ("", text.as_str()),
+        };
+        return Ok(format!("{}{}{}", sign, "0".repeat(padding), digits));
+    }
+
+    let fill = format.fill.to_string();
+    let align = match &format.align {
+        Some(align) => align.to_owned(),
+        None if numeric => ast::FormatAlignment::Right,
+        None => ast::FormatAlignment::Left,
+    };
+
+    Ok(match align {
+        ast::FormatAlignment::Left => format!("{}{}", text, fill.repeat(padding)),
+        ast::FormatAlignment::Right => format!("{}{}", fill.repeat(padding), text),
+        ast::FormatAlignment::Center => format!(
+            "{}{}{}",
+            fill.repeat(padding / 2),
+            text,
+            fill.repeat(padding - padding / 2)
+        ),
+    })
+}
+
+/// Parses text into the numeric type named by `symbol`. The inner result
+/// holds the parse failure, the outer one an unknown type.
+pub fn parse_numeric(
+    text: &str,
+    symbol: &str,
+) -> Result<Result<values::RuntimeValue, String>, RuntimeError> {
+    fn parsed<T, E: ToString>(
+        result: Result<T, E>,
+        create: fn(T) -> values::RuntimeValue,
+    ) -> Result<values::RuntimeValue, String> {
+        result.map(create).map_err(|error| error.to_string())
+    }
+
+    Ok(match symbol {
+        "i8" => parsed(text.parse::<i8>(), |value| {
+            values::Integer8Value::create(value).as_raw()
+        }),
+        "i16" => parsed(text.parse::<i16>(), |value| {
+            values::Integer16Value::create(value).as_raw()
+        }),
+        "i32" => parsed(text.parse::<i32>(), |value| {
+            values::Integer32Value::create(value).as_raw()
+        }),
+        "i64" => parsed(text.parse::<i64>(), |value| {
+            values::Integer64Value::create(value).as_raw()
+        }),
+        "i128" => parsed(text.parse::<i128>(), |value| {
+            values::Integer128Value::create(value).as_raw()
+        }),
+        "u8" => parsed(text.parse::<u8>(), |value| {
+            values::UnsignedInteger8Value::create(value).as_raw()
+        }),
+        "u16" => parsed(text.parse::<u16>(), |value| {
+            values::UnsignedInteger16Value::create(value).as_raw()
+        }),
+        "u32" => parsed(text.parse::<u32>(), |value| {
+            values::UnsignedInteger32Value::create(value).as_raw()
+        }),
+        "u64" => parsed(text.parse::<u64>(), |value| {
+            values::UnsignedInteger64Value::create(value).as_raw()
+        }),
+        "u128" => parsed(text.parse::<u128>(), |value| {
+            values::UnsignedInteger128Value::create(value).as_raw()
+        }),
+        "bigint" => parsed(text.parse::<BigInt>(), |value| {
+            values::BigIntValue::create(value).as_raw()
+        }),
+        "decimal" => parsed(Decimal::parse(text.trim()), |value| {
+            values::DecimalValue::create(value).as_raw()
+        }),
+        "f32" => parsed(text.parse::<f32>(), |value| {
+            values::Float32Value::create(value).as_raw()
+        }),
+        "f64" => parsed(text.parse::<f64>(), |value| {
+            values::Float64Value::create(value).as_raw()
+        }),
+        _ => {
+            return Err(RuntimeError::create(
+                ErrorCategory::TypeMismatch,
+                format!(
+                    "Cannot parse a str into a {}, expected a numeric type",
+                    symbol
+                ),
+            ))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_spec(width: Option<usize>, precision: Option<usize>) -> ast::FormatSpec {
+        ast::FormatSpec {
+            fill: ' ',
+            align: None,
+            sign_plus: false,
+            zero_pad: false,
+            width,
+            precision,
+        }
+    }
+
+    #[test]
+    fn formats_within_the_width_and_precision_limits() {
+        let value = values::Float64Value::create(2.5).as_raw();
+        let text = format_runtime_value(&value, &format_spec(Some(6), Some(2)));
+        assert_eq!(text.unwrap(), "  2.50");
+    }
+
+    #[test]
+    fn rejects_widths_and_precisions_above_the_limits() {
+        let value = values::Integer32Value::create(1).as_raw();
+        let width = format_runtime_value(&value, &format_spec(Some(99999999999999), None));
+        assert!(width.unwrap_err().contains("width of 99999999999999"));
+        let precision = format_runtime_value(&value, &format_spec(None, Some(70000)));
+        assert!(precision.unwrap_err().contains("precision of 70000"));
+    }
+
+    #[test]
+    fn names_mismatched_types_by_their_type_names() {
+        let value = values::Integer32Value::create(1).as_raw();
+        let error =
+            evaluate_variable_type(Some(&ast::TypeExpr::named("str")), String::from("y"), value);
+        assert_eq!(
+            error.unwrap_err().message,
+            "Incorrect runtime value for y, expected: str, got: i32"
+        );
+    }
+}
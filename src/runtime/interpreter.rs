@@ -1,42 +1,127 @@
 use super::super::frontend::ast;
+use super::decimal::Decimal;
 use super::environment::Environment;
-use super::evaluation::{expressions, statements};
+use super::errors::RuntimeError;
+use super::evaluation::{expressions, functions, methods, statements};
 use super::values::{self};
+use num_bigint::BigInt;
 
 pub fn evaluate(
     ast_node: ast::Statement,
     environment: &mut Environment,
-) -> Result<values::RuntimeValue, String> {
+) -> Result<values::RuntimeValue, RuntimeError> {
     match ast_node {
         ast::Statement::Program(program) => statements::evaluate_program(program, environment),
         ast::Statement::VariableDeclaration(variable_declaration) => {
             statements::evaluate_variable_declaration(variable_declaration, environment)
         }
+        ast::Statement::DestructuringDeclaration(destructuring_declaration) => {
+            statements::evaluate_destructuring_declaration(destructuring_declaration, environment)
+        }
+        ast::Statement::EnumDeclaration(enum_declaration) => {
+            statements::evaluate_enum_declaration(enum_declaration, environment)
+        }
+        ast::Statement::FunctionDeclaration(function_declaration) => {
+            functions::evaluate_function_declaration(function_declaration, environment)
+        }
+        ast::Statement::StructDeclaration(struct_declaration) => {
+            statements::evaluate_struct_declaration(struct_declaration, environment)
+        }
+        ast::Statement::TypeAlias(type_alias) => {
+            statements::evaluate_type_alias(type_alias, environment)
+        }
+        ast::Statement::NewtypeDeclaration(newtype_declaration) => {
+            statements::evaluate_newtype_declaration(newtype_declaration, environment)
+        }
+        ast::Statement::Throw(throw_statement) => {
+            statements::evaluate_throw_statement(throw_statement, environment)
+        }
+        ast::Statement::Try(try_statement) => {
+            statements::evaluate_try_statement(try_statement, environment)
+        }
+        ast::Statement::Import(import_statement) => {
+            statements::evaluate_import_statement(import_statement, environment)
+        }
+        ast::Statement::Export(export_statement) => {
+            statements::evaluate_export_statement(export_statement, environment)
+        }
         ast::Statement::Expression(expression) => match expression {
+            ast::Expression::BigInt(bigint) => {
+                Ok(values::BigIntValue::create(bigint.value.parse::<BigInt>().unwrap()).as_raw())
+            }
+            ast::Expression::Decimal(decimal) => {
+                Ok(values::DecimalValue::create(Decimal::parse(&decimal.value)?).as_raw())
+            }
             ast::Expression::Float(float) => {
                 Ok(values::Float32Value::create(float.value.parse::<f32>().unwrap()).as_raw())
             }
             ast::Expression::Integer(integer) => {
                 Ok(values::Integer32Value::create(integer.value.parse::<i32>().unwrap()).as_raw())
             }
+            ast::Expression::String(string) => {
+                Ok(values::StringValue::create(string.value).as_raw())
+            }
+            ast::Expression::Character(character) => {
+                Ok(values::CharacterValue::create(character.value).as_raw())
+            }
+            ast::Expression::Boolean(boolean) => {
+                Ok(values::BooleanValue::create(boolean.value).as_raw())
+            }
+            ast::Expression::Null(_) => Ok(values::NullValue::create().as_raw()),
+            ast::Expression::Template(template) => {
+                expressions::evaluate_template_literal(template, environment)
+            }
+            ast::Expression::Tuple(tuple) => {
+                expressions::evaluate_tuple_literal(tuple, environment)
+            }
+            ast::Expression::Array(array) => {
+                expressions::evaluate_array_literal(array, environment)
+            }
+            ast::Expression::Object(object) => {
+                expressions::evaluate_object_literal(object, environment)
+            }
+            ast::Expression::Struct(struct_literal) => {
+                expressions::evaluate_struct_literal(struct_literal, environment)
+            }
+            ast::Expression::Member(member_expression) => {
+                expressions::evaluate_member_expression(member_expression, environment)
+            }
+            ast::Expression::MethodCall(method_call_expression) => {
+                methods::evaluate_method_call_expression(method_call_expression, environment)
+            }
+            ast::Expression::Call(call_expression) => {
+                functions::evaluate_call_expression(call_expression, environment)
+            }
+            ast::Expression::Propagation(propagation_expression) => {
+                expressions::evaluate_propagation_expression(propagation_expression, environment)
+            }
             ast::Expression::Identifier(identifier) => {
                 expressions::evaluate_identifier_expression(identifier, environment)
             }
+            ast::Expression::Typeof(typeof_expression) => {
+                expressions::evaluate_typeof_expression(typeof_expression, environment)
+            }
             ast::Expression::Binary(binary_expression) => {
                 expressions::evaluate_binary_expression(binary_expression, environment)
             }
+            ast::Expression::DestructuringAssignment(destructuring_assignment_expression) => {
+                expressions::evaluate_destructuring_assignment_expression(
+                    destructuring_assignment_expression,
+                    environment,
+                )
+            }
+            ast::Expression::EnumVariant(enum_variant_expression) => {
+                expressions::evaluate_enum_variant_expression(enum_variant_expression, environment)
+            }
+            ast::Expression::Match(match_expression) => {
+                expressions::evaluate_match_expression(match_expression, environment)
+            }
             ast::Expression::VariableAssignment(variable_assignment_expression) => {
                 expressions::evaluate_assignment_expression(
                     variable_assignment_expression,
                     environment,
                 )
             }
-            _ => {
-                return Err(format!(
-                    "This AST Node has not yet been implemented for interpretation: {:#?}",
-                    expression
-                ))
-            }
         },
     }
 }
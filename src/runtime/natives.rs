@@ -0,0 +1,139 @@
+use super::collections::{OrderedMap, OrderedSet};
+use super::errors::{ErrorCategory, RuntimeError};
+use super::values::{self, NativeFunctionValue, RuntimeValue};
+use std::io::{self, BufRead, Write};
+
+pub mod math;
+
+/// The functions available to every Jarlang program, declared as constants
+/// by `Environment::init_global_scope`.
+pub fn functions() -> Vec<NativeFunctionValue> {
+    vec![
+        NativeFunctionValue::create("print", 0, None, print),
+        NativeFunctionValue::create("println", 0, None, println),
+        NativeFunctionValue::create("eprint", 0, None, eprint),
+        NativeFunctionValue::create("debug", 0, None, debug),
+        NativeFunctionValue::create("input", 0, Some(1), input),
+        NativeFunctionValue::create("Map", 0, Some(1), map),
+        NativeFunctionValue::create("Set", 0, Some(1), set),
+    ]
+}
+
+/// Arguments are displayed separated by spaces.
+fn display_arguments(arguments: &[RuntimeValue]) -> String {
+    arguments
+        .iter()
+        .map(RuntimeValue::to_string)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn flush_stdout() -> Result<(), RuntimeError> {
+    io::stdout()
+        .flush()
+        .map_err(|_| RuntimeError::from("Failed to flush stdout".to_string()))
+}
+
+/// `print(values...)`, writes to stdout without a trailing newline.
+fn print(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    print!("{}", display_arguments(&arguments));
+    flush_stdout()?;
+    Ok(values::NullValue::create().as_raw())
+}
+
+/// `println(values...)`
+fn println(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    println!("{}", display_arguments(&arguments));
+    Ok(values::NullValue::create().as_raw())
+}
+
+/// `eprint(values...)`, writes to stderr without a trailing newline.
+fn eprint(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    eprint!("{}", display_arguments(&arguments));
+    Ok(values::NullValue::create().as_raw())
+}
+
+/// `debug(values...)`, prints the values with strings quoted and returns
+/// them, so it can wrap an expression like Rust's `dbg!`.
+fn debug(mut arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let formatted: Vec<String> = arguments
+        .iter()
+        .map(RuntimeValue::to_debug_string)
+        .collect();
+    println!("{}", formatted.join(" "));
+
+    Ok(match arguments.len() {
+        0 => values::NullValue::create().as_raw(),
+        1 => arguments.remove(0),
+        _ => values::TupleValue::create(arguments).as_raw(),
+    })
+}
+
+/// `input(prompt?)`, reads a line from stdin without its line ending,
+/// returning null once stdin is closed.
+fn input(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    if let Some(prompt) = arguments.first() {
+        print!("{}", prompt);
+        flush_stdout()?;
+    }
+
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) => Ok(values::NullValue::create().as_raw()),
+        Ok(_) => {
+            let line = line.strip_suffix('\n').unwrap_or(&line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            Ok(values::StringValue::create(line.to_string()).as_raw())
+        }
+        Err(_) => Err("Failed to read a line from stdin".to_string().into()),
+    }
+}
+
+/// The elements of the array given to `Map` or `Set`, if any.
+fn initial_elements(
+    function: &str,
+    mut arguments: Vec<RuntimeValue>,
+) -> Result<Vec<RuntimeValue>, RuntimeError> {
+    match arguments.pop() {
+        None => Ok(Vec::new()),
+        Some(RuntimeValue::Array(array)) => Ok(array.value),
+        Some(argument) => Err(RuntimeError::create(
+            ErrorCategory::TypeMismatch,
+            format!(
+                "{} expects an array, got: {}",
+                function,
+                argument.as_value_type().as_string()
+            ),
+        )),
+    }
+}
+
+/// `Map(entries?)`, e.g. `Map([("a", 1), ("b", 2)])`. A key given more than
+/// once takes its last value.
+fn map(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let mut entries = Vec::new();
+    for element in initial_elements("Map", arguments)? {
+        match element {
+            RuntimeValue::Tuple(mut tuple) if tuple.value.len() == 2 => {
+                let value = tuple.value.remove(1);
+                entries.push((tuple.value.remove(0), value));
+            }
+            element => {
+                return Err(RuntimeError::create(
+                    ErrorCategory::TypeMismatch,
+                    format!(
+                        "Map expects its entries to be (key, value) tuples, got: {}",
+                        element.as_value_type().as_string()
+                    ),
+                ))
+            }
+        }
+    }
+    Ok(values::MapValue::create(OrderedMap::from_entries(entries)?).as_raw())
+}
+
+/// `Set(values?)`, e.g. `Set([1, 2, 3])`.
+fn set(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let elements = initial_elements("Set", arguments)?;
+    Ok(values::SetValue::create(OrderedSet::from_values(elements)?).as_raw())
+}
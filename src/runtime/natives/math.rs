@@ -0,0 +1,504 @@
+use num_integer::Integer;
+use num_traits::Signed;
+use std::ops::Rem;
+
+use super::super::decimal::RoundingMode;
+use super::super::errors::{ErrorCategory, RuntimeError};
+use super::super::evaluation::expressions;
+use super::super::helpers;
+use super::super::values::{self, NativeFunction, NativeFunctionValue, RuntimeValue};
+
+/// The `math` object declared by `Environment::init_global_scope`, e.g.
+/// `math.sqrt(2.0)` or `math.PI`.
+pub fn module() -> RuntimeValue {
+    let functions: Vec<(&'static str, usize, NativeFunction)> = vec![
+        ("math.abs", 1, abs),
+        ("math.min", 2, min),
+        ("math.max", 2, max),
+        ("math.clamp", 3, clamp),
+        ("math.pow", 2, pow),
+        ("math.sqrt", 1, sqrt),
+        ("math.cbrt", 1, cbrt),
+        ("math.exp", 1, exp),
+        ("math.ln", 1, ln),
+        ("math.log10", 1, log10),
+        ("math.sin", 1, sin),
+        ("math.cos", 1, cos),
+        ("math.tan", 1, tan),
+        ("math.asin", 1, asin),
+        ("math.acos", 1, acos),
+        ("math.atan", 1, atan),
+        ("math.atan2", 2, atan2),
+        ("math.sinh", 1, sinh),
+        ("math.cosh", 1, cosh),
+        ("math.tanh", 1, tanh),
+        ("math.floor", 1, floor),
+        ("math.ceil", 1, ceil),
+        ("math.round", 1, round),
+        ("math.trunc", 1, trunc),
+        ("math.is_nan", 1, is_nan),
+        ("math.gcd", 2, gcd),
+        ("math.count_ones", 1, count_ones),
+        ("math.leading_zeros", 1, leading_zeros),
+    ];
+
+    let mut members: Vec<(String, RuntimeValue)> = vec![
+        (
+            String::from("PI"),
+            values::Float64Value::create(std::f64::consts::PI).as_raw(),
+        ),
+        (
+            String::from("E"),
+            values::Float64Value::create(std::f64::consts::E).as_raw(),
+        ),
+        (
+            String::from("INF"),
+            values::Float64Value::create(f64::INFINITY).as_raw(),
+        ),
+        (
+            String::from("NAN"),
+            values::Float64Value::create(f64::NAN).as_raw(),
+        ),
+    ];
+    for (identifier, arguments, function) in functions {
+        let member = identifier.trim_start_matches("math.").to_string();
+        members.push((
+            member,
+            NativeFunctionValue::create(identifier, arguments, Some(arguments), function).as_raw(),
+        ));
+    }
+
+    values::ObjectValue::create(members).as_raw()
+}
+
+fn expected_number(function: &str, runtime_val: &RuntimeValue) -> RuntimeError {
+    RuntimeError::create(
+        ErrorCategory::TypeMismatch,
+        format!(
+            "math.{} expects a number, got {}",
+            function,
+            runtime_val.to_owned().as_value_type().as_string()
+        ),
+    )
+}
+
+fn expected_integer(function: &str, runtime_val: &RuntimeValue) -> RuntimeError {
+    RuntimeError::create(
+        ErrorCategory::TypeMismatch,
+        format!(
+            "math.{} expects an integer, got {}",
+            function,
+            runtime_val.to_owned().as_value_type().as_string()
+        ),
+    )
+}
+
+fn expected_same_type(function: &str, arguments: &[RuntimeValue]) -> RuntimeError {
+    let types: Vec<String> = arguments
+        .iter()
+        .map(|argument| argument.to_owned().as_value_type().as_string())
+        .collect();
+    RuntimeError::create(
+        ErrorCategory::TypeMismatch,
+        format!(
+            "math.{} expects numbers of the same type, got {}",
+            function,
+            types.join(", ")
+        ),
+    )
+}
+
+fn overflow(function: &str) -> RuntimeError {
+    RuntimeError::create(
+        ErrorCategory::Runtime,
+        format!("math.{} overflowed its integer type", function),
+    )
+}
+
+/// Applies a float function, keeping the width of `f32` and `f64` values.
+/// Integers and decimals are converted to an `f64` unless `rounding` is set,
+/// in which case integers are returned as they are and decimals are rounded
+/// to a whole number with it, e.g. `math.floor(3)` is `3`.
+fn float_function(
+    function: &str,
+    mut arguments: Vec<RuntimeValue>,
+    rounding: Option<RoundingMode>,
+    float32: fn(f32) -> f32,
+    float64: fn(f64) -> f64,
+) -> Result<RuntimeValue, RuntimeError> {
+    let runtime_val = arguments.remove(0);
+    match (runtime_val, rounding) {
+        (RuntimeValue::Float32(float), _) => {
+            Ok(values::Float32Value::create(float32(float.value)).as_raw())
+        }
+        (RuntimeValue::Float64(float), _) => {
+            Ok(values::Float64Value::create(float64(float.value)).as_raw())
+        }
+        (RuntimeValue::Decimal(decimal), Some(mode)) => {
+            Ok(values::DecimalValue::create(decimal.value.rescale(0, mode)).as_raw())
+        }
+        (runtime_val, Some(_)) if helpers::runtime_value_is_integer(&runtime_val) => {
+            Ok(runtime_val)
+        }
+        (runtime_val, None) if helpers::runtime_value_is_digit(&runtime_val) => {
+            let float = runtime_val.as_f64()?;
+            Ok(values::Float64Value::create(float64(float.value)).as_raw())
+        }
+        (runtime_val, _) => Err(expected_number(function, &runtime_val)),
+    }
+}
+
+fn sqrt(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("sqrt", arguments, None, f32::sqrt, f64::sqrt)
+}
+
+fn cbrt(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("cbrt", arguments, None, f32::cbrt, f64::cbrt)
+}
+
+fn exp(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("exp", arguments, None, f32::exp, f64::exp)
+}
+
+fn ln(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("ln", arguments, None, f32::ln, f64::ln)
+}
+
+fn log10(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("log10", arguments, None, f32::log10, f64::log10)
+}
+
+fn sin(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("sin", arguments, None, f32::sin, f64::sin)
+}
+
+fn cos(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("cos", arguments, None, f32::cos, f64::cos)
+}
+
+fn tan(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("tan", arguments, None, f32::tan, f64::tan)
+}
+
+fn asin(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("asin", arguments, None, f32::asin, f64::asin)
+}
+
+fn acos(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("acos", arguments, None, f32::acos, f64::acos)
+}
+
+fn atan(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("atan", arguments, None, f32::atan, f64::atan)
+}
+
+fn sinh(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("sinh", arguments, None, f32::sinh, f64::sinh)
+}
+
+fn cosh(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("cosh", arguments, None, f32::cosh, f64::cosh)
+}
+
+fn tanh(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function("tanh", arguments, None, f32::tanh, f64::tanh)
+}
+
+fn floor(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function(
+        "floor",
+        arguments,
+        Some(RoundingMode::Floor),
+        f32::floor,
+        f64::floor,
+    )
+}
+
+fn ceil(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function(
+        "ceil",
+        arguments,
+        Some(RoundingMode::Ceiling),
+        f32::ceil,
+        f64::ceil,
+    )
+}
+
+fn round(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function(
+        "round",
+        arguments,
+        Some(RoundingMode::HalfUp),
+        f32::round,
+        f64::round,
+    )
+}
+
+fn trunc(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    float_function(
+        "trunc",
+        arguments,
+        Some(RoundingMode::Down),
+        f32::trunc,
+        f64::trunc,
+    )
+}
+
+/// `math.atan2(y, x)`
+fn atan2(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    match (&arguments[0], &arguments[1]) {
+        (RuntimeValue::Float32(y), RuntimeValue::Float32(x)) => {
+            Ok(values::Float32Value::create(y.value.atan2(x.value)).as_raw())
+        }
+        (RuntimeValue::Float64(y), RuntimeValue::Float64(x)) => {
+            Ok(values::Float64Value::create(y.value.atan2(x.value)).as_raw())
+        }
+        _ => Err(expected_same_type("atan2", &arguments)),
+    }
+}
+
+fn is_nan(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    match &arguments[0] {
+        RuntimeValue::Float32(float) => {
+            Ok(values::BooleanValue::create(float.value.is_nan()).as_raw())
+        }
+        RuntimeValue::Float64(float) => {
+            Ok(values::BooleanValue::create(float.value.is_nan()).as_raw())
+        }
+        runtime_val if helpers::runtime_value_is_digit(runtime_val) => {
+            Ok(values::BooleanValue::create(false).as_raw())
+        }
+        runtime_val => Err(expected_number("is_nan", runtime_val)),
+    }
+}
+
+/// `math.abs(x)`, `math.abs(i32::MIN)` is an error rather than wrapping.
+fn abs(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let runtime_val = &arguments[0];
+    match runtime_val {
+        RuntimeValue::Integer8(integer) => integer
+            .value
+            .checked_abs()
+            .map(|value| values::Integer8Value::create(value).as_raw())
+            .ok_or_else(|| overflow("abs")),
+        RuntimeValue::Integer16(integer) => integer
+            .value
+            .checked_abs()
+            .map(|value| values::Integer16Value::create(value).as_raw())
+            .ok_or_else(|| overflow("abs")),
+        RuntimeValue::Integer32(integer) => integer
+            .value
+            .checked_abs()
+            .map(|value| values::Integer32Value::create(value).as_raw())
+            .ok_or_else(|| overflow("abs")),
+        RuntimeValue::Integer64(integer) => integer
+            .value
+            .checked_abs()
+            .map(|value| values::Integer64Value::create(value).as_raw())
+            .ok_or_else(|| overflow("abs")),
+        RuntimeValue::Integer128(integer) => integer
+            .value
+            .checked_abs()
+            .map(|value| values::Integer128Value::create(value).as_raw())
+            .ok_or_else(|| overflow("abs")),
+        RuntimeValue::UnsignedInteger8(_)
+        | RuntimeValue::UnsignedInteger16(_)
+        | RuntimeValue::UnsignedInteger32(_)
+        | RuntimeValue::UnsignedInteger64(_)
+        | RuntimeValue::UnsignedInteger128(_) => Ok(runtime_val.to_owned()),
+        RuntimeValue::BigInt(integer) => {
+            Ok(values::BigIntValue::create(integer.value.abs()).as_raw())
+        }
+        RuntimeValue::Decimal(decimal) => {
+            Ok(values::DecimalValue::create(decimal.value.abs()).as_raw())
+        }
+        RuntimeValue::Float32(float) => {
+            Ok(values::Float32Value::create(float.value.abs()).as_raw())
+        }
+        RuntimeValue::Float64(float) => {
+            Ok(values::Float64Value::create(float.value.abs()).as_raw())
+        }
+        _ => Err(expected_number("abs", runtime_val)),
+    }
+}
+
+/// Picks between two numbers of the same type. Float NaNs are ignored like
+/// Rust's `f64::min`, so `math.min(math.NAN, 1.0)` is `1`.
+fn pick(
+    function: &str,
+    arguments: Vec<RuntimeValue>,
+    ordering: std::cmp::Ordering,
+) -> Result<RuntimeValue, RuntimeError> {
+    let (left_hand_side, right_hand_side) = (&arguments[0], &arguments[1]);
+    if !helpers::runtime_value_is_digit(left_hand_side) {
+        return Err(expected_number(function, left_hand_side));
+    }
+    match (left_hand_side, right_hand_side) {
+        (RuntimeValue::Float32(lhs), RuntimeValue::Float32(rhs)) => {
+            let value = match ordering.is_lt() {
+                true => lhs.value.min(rhs.value),
+                false => lhs.value.max(rhs.value),
+            };
+            Ok(values::Float32Value::create(value).as_raw())
+        }
+        (RuntimeValue::Float64(lhs), RuntimeValue::Float64(rhs)) => {
+            let value = match ordering.is_lt() {
+                true => lhs.value.min(rhs.value),
+                false => lhs.value.max(rhs.value),
+            };
+            Ok(values::Float64Value::create(value).as_raw())
+        }
+        _ => match helpers::compare_runtime_values(left_hand_side, right_hand_side) {
+            Some(result) if result == ordering => Ok(left_hand_side.to_owned()),
+            Some(_) => Ok(right_hand_side.to_owned()),
+            None => Err(expected_same_type(function, &arguments)),
+        },
+    }
+}
+
+fn min(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    pick("min", arguments, std::cmp::Ordering::Less)
+}
+
+fn max(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    pick("max", arguments, std::cmp::Ordering::Greater)
+}
+
+/// `math.clamp(x, min, max)`
+fn clamp(mut arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let upper = arguments.pop().unwrap();
+    let lower = arguments.pop().unwrap();
+    let runtime_val = arguments.pop().unwrap();
+
+    if helpers::compare_runtime_values(&lower, &upper).is_some_and(|ordering| ordering.is_gt()) {
+        return Err(RuntimeError::create(
+            ErrorCategory::Runtime,
+            format!(
+                "math.clamp expects min to be at most max, got {} and {}",
+                lower, upper
+            ),
+        ));
+    }
+
+    let runtime_val = pick(
+        "clamp",
+        vec![runtime_val, lower],
+        std::cmp::Ordering::Greater,
+    )?;
+    pick("clamp", vec![runtime_val, upper], std::cmp::Ordering::Less)
+}
+
+/// `math.pow(base, exponent)`, the same as `base ** exponent`.
+fn pow(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    expressions::evaluate_exponent_expression(&arguments[0], &arguments[1])
+}
+
+fn gcd_of<T>(mut lhs: T, mut rhs: T) -> T
+where
+    T: Rem<Output = T> + PartialEq + Default + Copy,
+{
+    while rhs != T::default() {
+        (lhs, rhs) = (rhs, lhs % rhs);
+    }
+    lhs
+}
+
+/// `math.gcd(a, b)`, signed integers must not be negative.
+fn gcd(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    for argument in &arguments {
+        if !helpers::runtime_value_is_integer(argument) {
+            return Err(expected_integer("gcd", argument));
+        }
+        if argument
+            .to_owned()
+            .as_i128()
+            .is_ok_and(|integer| integer.value < 0)
+        {
+            return Err(RuntimeError::create(
+                ErrorCategory::Runtime,
+                format!("math.gcd expects non-negative integers, got {}", argument),
+            ));
+        }
+    }
+
+    match (&arguments[0], &arguments[1]) {
+        (RuntimeValue::Integer8(lhs), RuntimeValue::Integer8(rhs)) => {
+            Ok(values::Integer8Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::Integer16(lhs), RuntimeValue::Integer16(rhs)) => {
+            Ok(values::Integer16Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::Integer32(lhs), RuntimeValue::Integer32(rhs)) => {
+            Ok(values::Integer32Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::Integer64(lhs), RuntimeValue::Integer64(rhs)) => {
+            Ok(values::Integer64Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::Integer128(lhs), RuntimeValue::Integer128(rhs)) => {
+            Ok(values::Integer128Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::UnsignedInteger8(lhs), RuntimeValue::UnsignedInteger8(rhs)) => {
+            Ok(values::UnsignedInteger8Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::UnsignedInteger16(lhs), RuntimeValue::UnsignedInteger16(rhs)) => {
+            Ok(values::UnsignedInteger16Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::UnsignedInteger32(lhs), RuntimeValue::UnsignedInteger32(rhs)) => {
+            Ok(values::UnsignedInteger32Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::UnsignedInteger64(lhs), RuntimeValue::UnsignedInteger64(rhs)) => {
+            Ok(values::UnsignedInteger64Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::UnsignedInteger128(lhs), RuntimeValue::UnsignedInteger128(rhs)) => {
+            Ok(values::UnsignedInteger128Value::create(gcd_of(lhs.value, rhs.value)).as_raw())
+        }
+        (RuntimeValue::BigInt(lhs), RuntimeValue::BigInt(rhs)) => {
+            Ok(values::BigIntValue::create(lhs.value.gcd(&rhs.value)).as_raw())
+        }
+        _ => Err(expected_same_type("gcd", &arguments)),
+    }
+}
+
+/// Applies a bit count to an integer, returning a `u32` like Rust does.
+fn bit_function(
+    function: &str,
+    arguments: Vec<RuntimeValue>,
+    count: fn(u128, u32) -> u32,
+) -> Result<RuntimeValue, RuntimeError> {
+    // Every integer is widened to its bits in a u128, along with its width
+    let (bits, width) = match &arguments[0] {
+        RuntimeValue::Integer8(integer) => (integer.value as u8 as u128, 8),
+        RuntimeValue::Integer16(integer) => (integer.value as u16 as u128, 16),
+        RuntimeValue::Integer32(integer) => (integer.value as u32 as u128, 32),
+        RuntimeValue::Integer64(integer) => (integer.value as u64 as u128, 64),
+        RuntimeValue::Integer128(integer) => (integer.value as u128, 128),
+        RuntimeValue::UnsignedInteger8(integer) => (integer.value as u128, 8),
+        RuntimeValue::UnsignedInteger16(integer) => (integer.value as u128, 16),
+        RuntimeValue::UnsignedInteger32(integer) => (integer.value as u128, 32),
+        RuntimeValue::UnsignedInteger64(integer) => (integer.value as u128, 64),
+        RuntimeValue::UnsignedInteger128(integer) => (integer.value, 128),
+        runtime_val @ RuntimeValue::BigInt(_) => {
+            return Err(RuntimeError::create(
+                ErrorCategory::TypeMismatch,
+                format!(
+                    "math.{} expects a fixed-width integer, got {}",
+                    function,
+                    runtime_val.to_owned().as_value_type().as_string()
+                ),
+            ))
+        }
+        runtime_val => return Err(expected_integer(function, runtime_val)),
+    };
+
+    Ok(values::UnsignedInteger32Value::create(count(bits, width)).as_raw())
+}
+
+fn count_ones(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    bit_function("count_ones", arguments, |bits, _| bits.count_ones())
+}
+
+fn leading_zeros(arguments: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    bit_function("leading_zeros", arguments, |bits, width| {
+        bits.leading_zeros() - (128 - width)
+    })
+}
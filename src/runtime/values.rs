@@ -1,6 +1,14 @@
+use super::super::frontend::{ast, prelude};
+use super::collections::{OrderedMap, OrderedSet};
+use super::decimal::Decimal;
+use super::environment::Environment;
+use super::errors::RuntimeError;
 use super::helpers;
+use num_bigint::BigInt;
+use std::fmt;
+use std::rc::Rc;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ValueType {
     Integer8,
     Integer16,
@@ -12,12 +20,23 @@ pub enum ValueType {
     UnsignedInteger32,
     UnsignedInteger64,
     UnsignedInteger128,
+    BigInt,
+    Decimal,
     Float32,
     Float64,
     String,
     Character,
     Boolean,
     Null,
+    Tuple(Vec<ValueType>),
+    Array,
+    Object,
+    Map,
+    Set,
+    Enum(String),
+    Struct(String),
+    Newtype(String),
+    Function,
 }
 
 impl ValueType {
@@ -33,12 +52,45 @@ impl ValueType {
             ValueType::UnsignedInteger32 => String::from("u32"),
             ValueType::UnsignedInteger64 => String::from("u64"),
             ValueType::UnsignedInteger128 => String::from("u128"),
+            ValueType::BigInt => String::from("bigint"),
+            ValueType::Decimal => String::from("decimal"),
             ValueType::Float32 => String::from("f32"),
             ValueType::Float64 => String::from("f64"),
             ValueType::String => String::from("str"),
             ValueType::Character => String::from("char"),
             ValueType::Boolean => String::from("bool"),
             ValueType::Null => String::from("null"),
+            ValueType::Tuple(element_types) => {
+                let element_types: Vec<String> = element_types
+                    .into_iter()
+                    .map(ValueType::as_string)
+                    .collect();
+                match element_types.len() {
+                    1 => format!("({},)", element_types[0]),
+                    _ => format!("({})", element_types.join(", ")),
+                }
+            }
+            ValueType::Array => String::from("array"),
+            ValueType::Object => String::from("object"),
+            ValueType::Map => String::from("Map"),
+            ValueType::Set => String::from("Set"),
+            ValueType::Enum(identifier)
+            | ValueType::Struct(identifier)
+            | ValueType::Newtype(identifier) => identifier,
+            ValueType::Function => String::from("fn"),
+        }
+    }
+
+    /// The type annotation that accepts values of this type.
+    pub fn as_type_expr(self) -> ast::TypeExpr {
+        match self {
+            ValueType::Tuple(element_types) => ast::TypeExpr::Tuple(
+                element_types
+                    .into_iter()
+                    .map(ValueType::as_type_expr)
+                    .collect(),
+            ),
+            value_type => ast::TypeExpr::named(&value_type.as_string()),
         }
     }
 }
@@ -55,12 +107,24 @@ pub enum RuntimeValue {
     UnsignedInteger32(UnsignedInteger32Value),
     UnsignedInteger64(UnsignedInteger64Value),
     UnsignedInteger128(UnsignedInteger128Value),
+    BigInt(BigIntValue),
+    Decimal(DecimalValue),
     Float32(Float32Value),
     Float64(Float64Value),
     String(StringValue),
     Character(CharacterValue),
     Boolean(BooleanValue),
     Null(NullValue),
+    Tuple(TupleValue),
+    Array(ArrayValue),
+    Object(ObjectValue),
+    Map(MapValue),
+    Set(SetValue),
+    Enum(EnumValue),
+    Struct(StructValue),
+    Newtype(NewtypeValue),
+    Function(FunctionValue),
+    NativeFunction(NativeFunctionValue),
 }
 
 impl RuntimeValue {
@@ -76,16 +140,39 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger32(_) => ValueType::UnsignedInteger32,
             RuntimeValue::UnsignedInteger64(_) => ValueType::UnsignedInteger64,
             RuntimeValue::UnsignedInteger128(_) => ValueType::UnsignedInteger128,
+            RuntimeValue::BigInt(_) => ValueType::BigInt,
+            RuntimeValue::Decimal(_) => ValueType::Decimal,
             RuntimeValue::Float32(_) => ValueType::Float32,
             RuntimeValue::Float64(_) => ValueType::Float64,
             RuntimeValue::String(_) => ValueType::String,
             RuntimeValue::Character(_) => ValueType::Character,
             RuntimeValue::Boolean(_) => ValueType::Boolean,
             RuntimeValue::Null(_) => ValueType::Null,
+            RuntimeValue::Tuple(runtime_val) => runtime_val.value_type,
+            RuntimeValue::Array(runtime_val) => runtime_val.value_type,
+            RuntimeValue::Map(runtime_val) => runtime_val.value_type,
+            RuntimeValue::Set(runtime_val) => runtime_val.value_type,
+            RuntimeValue::Object(runtime_val) => runtime_val.value_type,
+            RuntimeValue::Enum(runtime_val) => runtime_val.value_type,
+            RuntimeValue::Struct(runtime_val) => runtime_val.value_type,
+            RuntimeValue::Newtype(runtime_val) => runtime_val.value_type,
+            RuntimeValue::Function(runtime_val) => runtime_val.value_type,
+            RuntimeValue::NativeFunction(runtime_val) => runtime_val.value_type,
         }
     }
 
+    /// The representation used by `debug`, which quotes strings and
+    /// characters.
+    pub fn to_debug_string(&self) -> String {
+        display_nested(self)
+    }
+
     pub fn as_i8(self) -> Result<Integer8Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_i8();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an i8", self));
         }
@@ -119,11 +206,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_i8(runtime_val.value).map(Integer8Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(Integer8Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an i8", self)),
         }
     }
 
     pub fn as_i16(self) -> Result<Integer16Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_i16();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an i16", self));
         }
@@ -157,11 +252,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_i16(runtime_val.value).map(Integer16Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(Integer16Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an i8", self)),
         }
     }
 
     pub fn as_i32(self) -> Result<Integer32Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_i32();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an i32", self));
         }
@@ -195,11 +298,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_i32(runtime_val.value).map(Integer32Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(Integer32Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an i32", self)),
         }
     }
 
     pub fn as_i64(self) -> Result<Integer64Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_i64();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an i64", self));
         }
@@ -233,11 +344,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_i64(runtime_val.value).map(Integer64Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(Integer64Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an i64", self)),
         }
     }
 
     pub fn as_i128(self) -> Result<Integer128Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_i128();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an i128", self));
         }
@@ -271,11 +390,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_i128(runtime_val.value).map(Integer128Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(Integer128Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an i128", self)),
         }
     }
 
     pub fn as_u8(self) -> Result<UnsignedInteger8Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_u8();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an u8", self));
         }
@@ -311,11 +438,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_u8(runtime_val.value).map(UnsignedInteger8Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(UnsignedInteger8Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an u8", self)),
         }
     }
 
     pub fn as_u16(self) -> Result<UnsignedInteger16Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_u16();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an 168", self));
         }
@@ -351,11 +486,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_u16(runtime_val.value).map(UnsignedInteger16Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(UnsignedInteger16Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an u16", self)),
         }
     }
 
     pub fn as_u32(self) -> Result<UnsignedInteger32Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_u32();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an u32", self));
         }
@@ -391,11 +534,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_u32(runtime_val.value).map(UnsignedInteger32Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(UnsignedInteger32Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an u32", self)),
         }
     }
 
     pub fn as_u64(self) -> Result<UnsignedInteger64Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_u64();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an u64", self));
         }
@@ -429,11 +580,19 @@ impl RuntimeValue {
             RuntimeValue::UnsignedInteger128(runtime_val) => {
                 helpers::cast_u128_to_u64(runtime_val.value).map(UnsignedInteger64Value::create)
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(UnsignedInteger64Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an u64", self)),
         }
     }
 
     pub fn as_u128(self) -> Result<UnsignedInteger128Value, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return BigIntValue::create(decimal.value.to_integer()?)
+                .as_raw()
+                .as_u128();
+        }
         if !helpers::runtime_value_is_integer(&self) {
             return Err(format!("Cannot cast {:#?} into an u128", self));
         }
@@ -467,10 +626,52 @@ impl RuntimeValue {
                 helpers::cast_to_u128(runtime_val.value).map(UnsignedInteger128Value::create)
             }
             RuntimeValue::UnsignedInteger128(runtime_val) => Ok(runtime_val),
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint(&runtime_val.value).map(UnsignedInteger128Value::create)
+            }
             _ => Err(format!("Cannot cast {:#?} into an u128", self)),
         }
     }
 
+    pub fn as_bigint(self) -> Result<BigIntValue, String> {
+        if let RuntimeValue::Decimal(decimal) = self {
+            return Ok(BigIntValue::create(decimal.value.to_integer()?));
+        }
+        let value = match self {
+            RuntimeValue::Integer8(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::Integer16(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::Integer32(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::Integer64(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::Integer128(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::UnsignedInteger8(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::UnsignedInteger16(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::UnsignedInteger32(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::UnsignedInteger64(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::UnsignedInteger128(runtime_val) => BigInt::from(runtime_val.value),
+            RuntimeValue::BigInt(runtime_val) => return Ok(runtime_val),
+            _ => return Err(format!("Cannot cast {:#?} into a bigint", self)),
+        };
+
+        Ok(BigIntValue::create(value))
+    }
+
+    pub fn as_decimal(self) -> Result<DecimalValue, String> {
+        match self {
+            RuntimeValue::Decimal(runtime_val) => Ok(runtime_val),
+            RuntimeValue::Float32(runtime_val) => {
+                Decimal::from_float(runtime_val.value).map(DecimalValue::create)
+            }
+            RuntimeValue::Float64(runtime_val) => {
+                Decimal::from_float(runtime_val.value).map(DecimalValue::create)
+            }
+            runtime_val if helpers::runtime_value_is_integer(&runtime_val) => {
+                let integer = runtime_val.as_bigint()?;
+                Ok(DecimalValue::create(Decimal::from_integer(integer.value)))
+            }
+            _ => Err(format!("Cannot cast {:#?} into a decimal", self)),
+        }
+    }
+
     pub fn as_f32(self) -> Result<Float32Value, String> {
         if !helpers::runtime_value_is_digit(&self) {
             return Err(format!("Cannot cast {:#?} into an f32", self));
@@ -511,6 +712,13 @@ impl RuntimeValue {
             RuntimeValue::Float64(runtime_val) => {
                 Ok(Float32Value::create(runtime_val.value as f32))
             }
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint_to_f32(&runtime_val.value).map(Float32Value::create)
+            }
+            RuntimeValue::Decimal(runtime_val) => match runtime_val.value.to_f32() {
+                Some(float) => Ok(Float32Value::create(float)),
+                None => Err(format!("Cannot cast {} into an f32", runtime_val.value)),
+            },
             _ => Err(format!("Cannot cast {:#?} into an f64", self)),
         }
     }
@@ -555,11 +763,137 @@ impl RuntimeValue {
                 Ok(Float64Value::create(runtime_val.value as f64))
             }
             RuntimeValue::Float64(runtime_val) => Ok(runtime_val),
+            RuntimeValue::BigInt(runtime_val) => {
+                helpers::cast_bigint_to_f64(&runtime_val.value).map(Float64Value::create)
+            }
+            RuntimeValue::Decimal(runtime_val) => match runtime_val.value.to_f64() {
+                Some(float) => Ok(Float64Value::create(float)),
+                None => Err(format!("Cannot cast {} into an f64", runtime_val.value)),
+            },
             _ => Err(format!("Cannot cast {:#?} into an f64", self)),
         }
     }
 }
 
+/// The user facing representation of a value, used by template strings.
+/// Strings and characters are quoted when nested inside another value.
+impl fmt::Display for RuntimeValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuntimeValue::Integer8(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::Integer16(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::Integer32(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::Integer64(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::Integer128(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::UnsignedInteger8(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::UnsignedInteger16(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::UnsignedInteger32(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::UnsignedInteger64(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::UnsignedInteger128(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::BigInt(integer) => write!(f, "{}", integer.value),
+            RuntimeValue::Decimal(decimal) => write!(f, "{}", decimal.value),
+            RuntimeValue::Float32(float) => write!(f, "{}", float.value),
+            RuntimeValue::Float64(float) => write!(f, "{}", float.value),
+            RuntimeValue::String(string) => write!(f, "{}", string.value),
+            RuntimeValue::Character(character) => write!(f, "{}", character.value),
+            RuntimeValue::Boolean(boolean) => write!(f, "{}", boolean.value),
+            RuntimeValue::Null(_) => write!(f, "null"),
+            RuntimeValue::Tuple(tuple) => match tuple.value.len() {
+                1 => write!(f, "({},)", display_nested(&tuple.value[0])),
+                _ => write!(f, "({})", display_list(&tuple.value)),
+            },
+            RuntimeValue::Array(array) => write!(f, "[{}]", display_list(&array.value)),
+            RuntimeValue::Object(object) => match object.value.is_empty() {
+                true => write!(f, "{{}}"),
+                false => write!(f, "{{ {} }}", display_fields(&object.value)),
+            },
+            RuntimeValue::Map(map) => match map.value.is_empty() {
+                true => write!(f, "Map {{}}"),
+                false => write!(f, "Map {{ {} }}", display_entries(map.value.entries())),
+            },
+            RuntimeValue::Set(set) => match set.value.is_empty() {
+                true => write!(f, "Set {{}}"),
+                false => write!(
+                    f,
+                    "Set {{ {} }}",
+                    display_list(&set.value.values().cloned().collect::<Vec<RuntimeValue>>())
+                ),
+            },
+            RuntimeValue::Enum(enum_value) => {
+                let identifier = enum_value.value_type.to_owned().as_string();
+                // Prelude variants are written without their enum, e.g. `Some(1)`
+                match prelude::resolve_variant(&enum_value.variant) {
+                    Some(prelude_enum) if prelude_enum == identifier => {
+                        write!(f, "{}", enum_value.variant)?
+                    }
+                    _ => write!(f, "{}::{}", identifier, enum_value.variant)?,
+                }
+                match &enum_value.payload {
+                    ast::EnumPayload::Unit => Ok(()),
+                    ast::EnumPayload::Tuple(values) => write!(f, "({})", display_list(values)),
+                    ast::EnumPayload::Struct(fields) => {
+                        write!(f, " {{ {} }}", display_fields(fields))
+                    }
+                }
+            }
+            RuntimeValue::Struct(struct_value) => {
+                let identifier = struct_value.value_type.to_owned().as_string();
+                match struct_value.fields.is_empty() {
+                    true => write!(f, "{} {{}}", identifier),
+                    false => write!(
+                        f,
+                        "{} {{ {} }}",
+                        identifier,
+                        display_fields(&struct_value.fields)
+                    ),
+                }
+            }
+            RuntimeValue::Newtype(newtype) => write!(
+                f,
+                "{}({})",
+                newtype.value_type.to_owned().as_string(),
+                display_nested(&newtype.value)
+            ),
+            RuntimeValue::Function(function) => write!(f, "{}", function.signature()),
+            RuntimeValue::NativeFunction(function) => {
+                write!(f, "native fn {}", function.identifier)
+            }
+        }
+    }
+}
+
+fn display_nested(value: &RuntimeValue) -> String {
+    match value {
+        RuntimeValue::String(string) => format!("{:?}", string.value),
+        RuntimeValue::Character(character) => format!("{:?}", character.value),
+        _ => value.to_string(),
+    }
+}
+
+fn display_list(values: &[RuntimeValue]) -> String {
+    values
+        .iter()
+        .map(display_nested)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn display_entries(entries: &[(RuntimeValue, RuntimeValue)]) -> String {
+    entries
+        .iter()
+        .map(|(key, value)| format!("{}: {}", display_nested(key), display_nested(value)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn display_fields(fields: &[(String, RuntimeValue)]) -> String {
+    fields
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, display_nested(value)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 #[derive(Debug, Clone)]
 pub struct Integer8Value {
     pub value_type: ValueType,
@@ -655,6 +989,44 @@ impl Integer128Value {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct BigIntValue {
+    pub value_type: ValueType,
+    pub value: BigInt,
+}
+
+impl BigIntValue {
+    pub fn create(value: BigInt) -> Self {
+        BigIntValue {
+            value_type: ValueType::BigInt,
+            value,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::BigInt(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DecimalValue {
+    pub value_type: ValueType,
+    pub value: Decimal,
+}
+
+impl DecimalValue {
+    pub fn create(value: Decimal) -> Self {
+        DecimalValue {
+            value_type: ValueType::Decimal,
+            value,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Decimal(self)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UnsignedInteger8Value {
     pub value_type: ValueType,
@@ -863,3 +1235,303 @@ impl NullValue {
         RuntimeValue::Null(self)
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct TupleValue {
+    pub value_type: ValueType,
+    pub value: Vec<RuntimeValue>,
+}
+
+impl TupleValue {
+    pub fn create(value: Vec<RuntimeValue>) -> Self {
+        TupleValue {
+            value_type: ValueType::Tuple(
+                value
+                    .iter()
+                    .map(|element| element.to_owned().as_value_type())
+                    .collect(),
+            ),
+            value,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Tuple(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayValue {
+    pub value_type: ValueType,
+    pub value: Vec<RuntimeValue>,
+}
+
+impl ArrayValue {
+    pub fn create(value: Vec<RuntimeValue>) -> Self {
+        ArrayValue {
+            value_type: ValueType::Array,
+            value,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Array(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectValue {
+    pub value_type: ValueType,
+    pub value: Vec<(String, RuntimeValue)>,
+}
+
+impl ObjectValue {
+    pub fn create(value: Vec<(String, RuntimeValue)>) -> Self {
+        ObjectValue {
+            value_type: ValueType::Object,
+            value,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Object(self)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&RuntimeValue> {
+        self.value
+            .iter()
+            .find(|(property, _)| property == key)
+            .map(|(_, value)| value)
+    }
+}
+
+/// A `Map<K, V>`. Maps are values like any other, so methods such as
+/// `insert` return a new map.
+#[derive(Debug, Clone)]
+pub struct MapValue {
+    pub value_type: ValueType,
+    pub value: OrderedMap,
+}
+
+impl MapValue {
+    pub fn create(value: OrderedMap) -> Self {
+        MapValue {
+            value_type: ValueType::Map,
+            value,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Map(self)
+    }
+}
+
+/// A `Set<T>`, which like a map returns a new set from `insert`.
+#[derive(Debug, Clone)]
+pub struct SetValue {
+    pub value_type: ValueType,
+    pub value: OrderedSet,
+}
+
+impl SetValue {
+    pub fn create(value: OrderedSet) -> Self {
+        SetValue {
+            value_type: ValueType::Set,
+            value,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Set(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumValue {
+    pub value_type: ValueType,
+    pub variant: String,
+    pub payload: ast::EnumPayload<RuntimeValue>,
+}
+
+impl EnumValue {
+    pub fn create(
+        enum_identifier: String,
+        variant: String,
+        payload: ast::EnumPayload<RuntimeValue>,
+    ) -> Self {
+        EnumValue {
+            value_type: ValueType::Enum(enum_identifier),
+            variant,
+            payload,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Enum(self)
+    }
+}
+
+#[derive(Clone)]
+pub struct StructValue {
+    pub value_type: ValueType,
+    pub declaration: Rc<ast::StructDeclarationStatement>,
+    pub fields: Vec<(String, RuntimeValue)>,
+}
+
+impl StructValue {
+    pub fn create(
+        declaration: Rc<ast::StructDeclarationStatement>,
+        fields: Vec<(String, RuntimeValue)>,
+    ) -> Self {
+        StructValue {
+            value_type: ValueType::Struct(declaration.identifier.to_owned()),
+            declaration,
+            fields,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Struct(self)
+    }
+
+    pub fn get(&self, field: &str) -> Option<&RuntimeValue> {
+        self.fields
+            .iter()
+            .find(|(identifier, _)| identifier == field)
+            .map(|(_, value)| value)
+    }
+}
+
+/// The declaration is left out as it is shared by every value of the struct.
+impl fmt::Debug for StructValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StructValue")
+            .field("value_type", &self.value_type)
+            .field("fields", &self.fields)
+            .finish()
+    }
+}
+
+/// A value wrapped in a newtype, e.g. `Meters(1.5)`
+#[derive(Debug, Clone)]
+pub struct NewtypeValue {
+    pub value_type: ValueType,
+    pub value: Box<RuntimeValue>,
+}
+
+impl NewtypeValue {
+    pub fn create(identifier: String, value: RuntimeValue) -> Self {
+        NewtypeValue {
+            value_type: ValueType::Newtype(identifier),
+            value: Box::new(value),
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Newtype(self)
+    }
+}
+
+#[derive(Clone)]
+pub struct FunctionValue {
+    pub value_type: ValueType,
+    pub declaration: Rc<ast::FunctionDeclarationStatement>,
+    /// The global environment of the module an imported function was
+    /// declared in, `None` for functions of the running module
+    pub module: Option<Rc<Environment>>,
+}
+
+impl FunctionValue {
+    pub fn create(declaration: ast::FunctionDeclarationStatement) -> Self {
+        FunctionValue {
+            value_type: ValueType::Function,
+            declaration: Rc::new(declaration),
+            module: None,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::Function(self)
+    }
+
+    /// `fn first<T: Display>(xs: [T]) -> T?`
+    pub fn signature(&self) -> String {
+        let declaration = &self.declaration;
+        let mut signature = format!("fn {}", declaration.identifier);
+        if !declaration.type_parameters.is_empty() {
+            let type_parameters: Vec<String> = declaration
+                .type_parameters
+                .iter()
+                .map(|type_parameter| match type_parameter.bounds.is_empty() {
+                    true => type_parameter.identifier.to_owned(),
+                    false => format!(
+                        "{}: {}",
+                        type_parameter.identifier,
+                        type_parameter.bounds.join(" + ")
+                    ),
+                })
+                .collect();
+            signature.push_str(&format!("<{}>", type_parameters.join(", ")));
+        }
+        let parameters: Vec<String> = declaration
+            .parameters
+            .iter()
+            .map(|(identifier, value_type)| format!("{}: {}", identifier, value_type))
+            .collect();
+        signature.push_str(&format!("({})", parameters.join(", ")));
+        if let Some(return_type) = &declaration.return_type {
+            signature.push_str(&format!(" -> {}", return_type));
+        }
+        signature
+    }
+}
+
+pub type NativeFunction = fn(Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError>;
+
+/// A function implemented by the interpreter, such as `print`
+#[derive(Debug, Clone)]
+pub struct NativeFunctionValue {
+    pub value_type: ValueType,
+    pub identifier: &'static str,
+    pub min_arguments: usize,
+    /// `None` for functions taking any number of arguments
+    pub max_arguments: Option<usize>,
+    pub function: NativeFunction,
+}
+
+impl NativeFunctionValue {
+    pub fn create(
+        identifier: &'static str,
+        min_arguments: usize,
+        max_arguments: Option<usize>,
+        function: NativeFunction,
+    ) -> Self {
+        NativeFunctionValue {
+            value_type: ValueType::Function,
+            identifier,
+            min_arguments,
+            max_arguments,
+            function,
+        }
+    }
+
+    pub fn as_raw(self) -> RuntimeValue {
+        RuntimeValue::NativeFunction(self)
+    }
+
+    pub fn accepts(&self, argument_count: usize) -> bool {
+        argument_count >= self.min_arguments
+            && self
+                .max_arguments
+                .is_none_or(|max_arguments| argument_count <= max_arguments)
+    }
+}
+
+impl fmt::Debug for FunctionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FunctionValue")
+            .field("value_type", &self.value_type)
+            .field("signature", &self.signature())
+            .finish()
+    }
+}